@@ -0,0 +1,123 @@
+// Certified document records
+// Maintains a Merkle tree over document ID -> (file_hash, publication_date, owner, retraction)
+// and publishes its root hash as the canister's certified data
+
+use std::cell::RefCell;
+use candid::Principal;
+use ic_certification::{AsHashTree, Hash, HashTree, RbTree, labeled, labeled_hash};
+use serde::Serialize;
+use sha2::{Digest, Sha256};
+use crate::types::{Document, DocumentRetraction};
+use crate::storage::DOCUMENTS;
+
+// Label under which the document tree is certified
+pub const DOCUMENTS_TREE_LABEL: &[u8] = b"documents";
+
+thread_local! {
+    // Heap-only tree, rebuilt from DOCUMENTS on init and after every upgrade
+    static CERTIFIED_DOCUMENTS: RefCell<RbTree<Vec<u8>, Hash>> = const { RefCell::new(RbTree::new()) };
+}
+
+/// Hash of the certified fields of a document
+/// Fields are length-prefixed so that no two records share an encoding
+/// A retraction appends its time, the SHA-256 of its reason and the purge flag; records that
+/// were never retracted keep the original encoding, so receipts exported before remain valid
+/// Mirrored in verifier/src/receipt.rs; keep both encodings in sync
+pub fn document_leaf_hash(file_hash: &str, publication_date: u64, owner: &Principal, retraction: Option<&DocumentRetraction>) -> Hash {
+    let owner_bytes = owner.as_slice();
+    let mut hasher = Sha256::new();
+    hasher.update((file_hash.len() as u32).to_be_bytes());
+    hasher.update(file_hash.as_bytes());
+    hasher.update(publication_date.to_be_bytes());
+    hasher.update((owner_bytes.len() as u32).to_be_bytes());
+    hasher.update(owner_bytes);
+    if let Some(retraction) = retraction {
+        hasher.update(retraction.retracted_at.to_be_bytes());
+        hasher.update(Sha256::digest(retraction.reason.as_bytes()));
+        hasher.update([retraction.file_purged as u8]);
+    }
+    hasher.finalize().into()
+}
+
+fn leaf_hash(document: &Document) -> Hash {
+    document_leaf_hash(&document.file_hash, document.publication_date, &document.owner, document.retraction.as_ref())
+}
+
+// Publish the current root hash as certified data
+fn update_certified_data() {
+    let root_hash = CERTIFIED_DOCUMENTS.with(|tree| {
+        labeled_hash(DOCUMENTS_TREE_LABEL, &tree.borrow().root_hash())
+    });
+    ic_cdk::api::certified_data_set(root_hash);
+}
+
+/// Add or replace a document in the certified tree
+pub fn certify_document(document: &Document) {
+    let leaf = leaf_hash(document);
+    CERTIFIED_DOCUMENTS.with(|tree| {
+        tree.borrow_mut().insert(document.document_id.as_bytes().to_vec(), leaf);
+    });
+    update_certified_data();
+}
+
+/// Rebuild the certified tree from stable storage (heap state does not survive upgrades)
+pub fn rebuild_certified_documents() -> u64 {
+    let tree: RbTree<Vec<u8>, Hash> = DOCUMENTS.with(|storage| {
+        storage.borrow().iter()
+            .map(|(key, doc)| (key.0.into_bytes(), leaf_hash(&doc.0)))
+            .collect()
+    });
+
+    let count = tree.iter().count() as u64;
+    CERTIFIED_DOCUMENTS.with(|certified| *certified.borrow_mut() = tree);
+    update_certified_data();
+    count
+}
+
+/// Witness for a single document, rooted at the certified data
+pub fn document_witness(document_id: &str) -> HashTree {
+    CERTIFIED_DOCUMENTS.with(|tree| {
+        labeled(DOCUMENTS_TREE_LABEL, tree.borrow().witness(document_id.as_bytes()))
+    })
+}
+
+/// CBOR-encode a hash tree (self-describing, as expected by IC agents)
+pub fn encode_witness(witness: &HashTree) -> Result<Vec<u8>, String> {
+    let mut serializer = serde_cbor::Serializer::new(Vec::new());
+    serializer.self_describe()
+        .map_err(|e| format!("Failed to encode witness: {}", e))?;
+    witness.serialize(&mut serializer)
+        .map_err(|e| format!("Failed to encode witness: {}", e))?;
+    Ok(serializer.into_inner())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const FILE_HASH: &str = "2a6614cf988f8e3ff1426ffc23e07823762c84f9b0611caf61b33b0022d55556";
+    const PUBLICATION_DATE: u64 = 1_760_000_000_000_000_000;
+
+    fn owner() -> Principal {
+        Principal::from_text("bkyz2-fmaaa-aaaaa-qaaaq-cai").unwrap()
+    }
+
+    // Same vectors as verifier/tests/verify_receipt.rs
+    #[test]
+    fn leaf_hash_matches_verifier_encoding() {
+        let leaf = document_leaf_hash(FILE_HASH, PUBLICATION_DATE, &owner(), None);
+        assert_eq!(hex::encode(leaf), "35725cd1437d8df6ab95de11f6721cc4b3b9f209d9b3f9136114e1944af99541");
+    }
+
+    #[test]
+    fn retracted_leaf_hash_matches_verifier_encoding() {
+        let retraction = DocumentRetraction {
+            retracted_by: Principal::anonymous(),
+            retracted_at: 1_760_086_400_000_000_000,
+            reason: "Superseded by the restated release".to_string(),
+            file_purged: true,
+        };
+        let leaf = document_leaf_hash(FILE_HASH, PUBLICATION_DATE, &owner(), Some(&retraction));
+        assert_eq!(hex::encode(leaf), "912b29a05c0165418201adaebbfb4ba74cc1cf4ed7f42a3cf0d3a7386cc30239");
+    }
+}
@@ -0,0 +1,100 @@
+// Readable text of HTML and XHTML documents
+
+// Elements whose content is never displayed; ix:header holds the hidden facts of inline XBRL
+const HTML_HIDDEN_ELEMENTS: &[&str] = &["script", "style", "head", "ix:header"];
+// Elements that start a new line of text
+const HTML_BLOCK_ELEMENTS: &[&str] = &[
+    "p", "div", "br", "tr", "li", "table", "section", "h1", "h2", "h3", "h4", "h5", "h6",
+];
+
+/// Extract the human-readable text of an HTML or XHTML document (e.g. an inline XBRL filing)
+/// Tags are dropped, hidden content is skipped and block elements start new lines
+pub fn extract_html_text(html: &str) -> Result<String, String> {
+    let mut text = String::new();
+    let mut hidden_depth = 0usize;
+    let mut rest = html;
+
+    while let Some(tag_start) = rest.find('<') {
+        if hidden_depth == 0 {
+            text.push_str(&decode_html_entities(&rest[..tag_start]));
+        }
+        let Some(tag_length) = rest[tag_start..].find('>') else {
+            break;
+        };
+        let tag = &rest[tag_start + 1..tag_start + tag_length];
+        rest = &rest[tag_start + tag_length + 1..];
+
+        let closing = tag.starts_with('/');
+        let name = tag.trim_start_matches('/')
+            .split(|c: char| c.is_whitespace() || c == '/')
+            .next()
+            .unwrap_or_default()
+            .to_ascii_lowercase();
+
+        if HTML_HIDDEN_ELEMENTS.contains(&name.as_str()) && !tag.ends_with('/') {
+            hidden_depth = if closing { hidden_depth.saturating_sub(1) } else { hidden_depth + 1 };
+        } else if hidden_depth == 0 {
+            let local_name = name.rsplit(':').next().unwrap_or_default();
+            if HTML_BLOCK_ELEMENTS.contains(&local_name) {
+                text.push('\n');
+            } else if matches!(local_name, "td" | "th") && closing {
+                text.push(' ');
+            }
+        }
+    }
+    if hidden_depth == 0 {
+        text.push_str(&decode_html_entities(rest));
+    }
+
+    // Collapse the source formatting: one space between words, no blank lines
+    let cleaned_text = text
+        .lines()
+        .map(|line| line.split_whitespace().collect::<Vec<_>>().join(" "))
+        .filter(|line| !line.is_empty())
+        .collect::<Vec<_>>()
+        .join("\n");
+
+    if cleaned_text.is_empty() {
+        Err("HTML document contains no text content".to_string())
+    } else {
+        Ok(cleaned_text)
+    }
+}
+
+// Named entities common in filings, plus numeric character references
+fn decode_html_entities(text: &str) -> String {
+    let mut decoded = String::with_capacity(text.len());
+    let mut rest = text;
+
+    while let Some(start) = rest.find('&') {
+        decoded.push_str(&rest[..start]);
+        rest = &rest[start..];
+        let entity = rest.find(';').filter(|end| *end <= 10).map(|end| &rest[1..end]);
+        let character = entity.and_then(|entity| match entity {
+            "amp" => Some('&'),
+            "lt" => Some('<'),
+            "gt" => Some('>'),
+            "quot" => Some('"'),
+            "apos" => Some('\''),
+            "nbsp" => Some(' '),
+            _ => entity.strip_prefix("#x").or_else(|| entity.strip_prefix("#X"))
+                .and_then(|hex| u32::from_str_radix(hex, 16).ok())
+                .or_else(|| entity.strip_prefix('#').and_then(|decimal| decimal.parse().ok()))
+                .and_then(char::from_u32),
+        });
+
+        match (entity, character) {
+            (Some(entity), Some(character)) => {
+                decoded.push(character);
+                rest = &rest[entity.len() + 2..];
+            }
+            _ => {
+                decoded.push('&');
+                rest = &rest[1..];
+            }
+        }
+    }
+
+    decoded.push_str(rest);
+    decoded
+}
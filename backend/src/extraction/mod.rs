@@ -0,0 +1,47 @@
+// Text extraction from published files, done once at publication and stored for search, analytics and audits
+
+pub mod html;
+
+pub use html::extract_html_text;
+
+use crate::utils::file_type::{detect_file_type, DetectedFileType};
+
+// Stored text must fit in a single query reply (~2MB including Candid overhead)
+pub const MAX_STORED_TEXT_BYTES: usize = 1_000_000;
+
+// Source formats recorded with the extracted text
+pub const SOURCE_PDF: &str = "PDF";
+pub const SOURCE_HTML: &str = "HTML";
+pub const SOURCE_SPREADSHEET: &str = "SPREADSHEET";
+pub const SOURCE_TEXT: &str = "TEXT";
+
+/// Extract the text of a file according to its detected format
+/// Returns the source format and the text, or None for formats without text (images)
+pub fn extract_document_text(file_data: &[u8]) -> Result<Option<(&'static str, String)>, String> {
+    let extracted = match detect_file_type(file_data) {
+        Some(DetectedFileType::Pdf) => (SOURCE_PDF, crate::pdf::extract_pdf_text(file_data)?),
+        Some(DetectedFileType::Html) => (SOURCE_HTML, extract_html_text(&String::from_utf8_lossy(file_data))?),
+        Some(DetectedFileType::Xlsx | DetectedFileType::XlsxMacro | DetectedFileType::Xlsb | DetectedFileType::Xls) => {
+            let worksheets = crate::spreadsheet::parse_workbook(file_data)?;
+            (SOURCE_SPREADSHEET, crate::spreadsheet::workbook_text(&worksheets))
+        }
+        Some(DetectedFileType::PlainText | DetectedFileType::Xml) => {
+            (SOURCE_TEXT, String::from_utf8_lossy(file_data).into_owned())
+        }
+        Some(DetectedFileType::Png | DetectedFileType::Jpeg) | None => return Ok(None),
+    };
+
+    Ok(Some(extracted))
+}
+
+/// Cut text to at most max_bytes without splitting a character
+pub fn truncate_at_char_boundary(text: &str, max_bytes: usize) -> &str {
+    if text.len() <= max_bytes {
+        return text;
+    }
+    let mut end = max_bytes;
+    while !text.is_char_boundary(end) {
+        end -= 1;
+    }
+    &text[..end]
+}
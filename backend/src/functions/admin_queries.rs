@@ -83,7 +83,7 @@ pub fn admin_create_institution_for_user(
     };
     
     // Store institution
-    crate::storage::update_institution_safe(&institution_id, &institution)?;
+    crate::storage::insert_institution_safe(&institution_id, &institution)?;
     
     // Create or update user profile
     let user_profile = UserProfile {
@@ -146,6 +146,16 @@ pub fn admin_promote_to_super_admin(user_identity: Principal) -> Result<(), Stri
     }
 }
 
+/// Admin function: Set the frontend linked from certificate QR codes (super admin only)
+/// An empty URL restores the default
+#[update]
+pub fn admin_set_verification_base_url(url: String) -> Result<(), String> {
+    require_super_admin()?;
+
+    let normalized = super::verification_queries::normalize_verification_base_url(&url)?;
+    crate::storage::set_verification_base_url_safe(&normalized)
+}
+
 /// Admin function: Delete a user (super admin only)
 #[update]
 pub fn admin_delete_user(user_identity: Principal) -> Result<(), String> {
@@ -342,6 +352,8 @@ pub fn admin_get_storage_info() -> Result<Vec<String>, String> {
     info.push(format!("Document count: {}", documents_count));
     info.push(format!("Institution count: {}", institutions_count));
     info.push(format!("User profiles count: {}", user_profiles_count));
+    info.push(format!("Logical file size: {} bytes", stats.total_file_size_bytes));
+    info.push(format!("Physical file size: {} bytes ({} unique files)", stats.physical_file_size_bytes, stats.unique_file_count));
     
     // Add instructions for accessing full logs
     info.push("To see full logs, check IC Dashboard or use 'dfx canister logs'".to_string());
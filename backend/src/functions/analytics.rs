@@ -4,15 +4,20 @@ use ic_cdk::{update, query};
 use candid::CandidType;
 use serde::{Serialize, Deserialize as SerdeDeserialize};
 use serde_json::json;
-use crate::types::{Document, DocumentType};
-use crate::storage::{get_document_safe};
-use lopdf::Document as PdfDocument;
+use crate::types::{
+    ConsolidatedBalanceSheetData, ConsolidatedCashFlowData, ConsolidatedIncomeData, Document, DocumentText, DocumentType,
+    PerShareData, ReportingScale,
+};
+use crate::utils::format_timestamp_to_human_readable;
+use crate::storage::{get_document_file_safe, get_document_safe, get_document_text_safe};
+use crate::extraction::truncate_at_char_boundary;
 
 // Configuration constants
 const GEMINI_ENDPOINT: &str = "https://generativelanguage.googleapis.com/v1beta/models/gemini-2.0-flash-exp:generateContent";
 const MAX_RESPONSE_BYTES: u64 = 500_000; // 500KB for comprehensive analysis
+#[allow(dead_code)]
 const REQUEST_CYCLES: u128 = 1_000_000_000;
-const MAX_PDF_TEXT_LENGTH: usize = 50_000; // Limit PDF text to ~50K characters to avoid API limits
+const MAX_EXTRACTED_TEXT_LENGTH: usize = 50_000; // Limit extracted text to ~50K characters to avoid API limits
 
 // Add API key constant at the top
 const GEMINI_API_KEY: &str = env!("GEMINI_API_KEY");
@@ -26,6 +31,7 @@ struct GeminiResponse {
 #[derive(SerdeDeserialize, Debug)]
 struct Candidate {
     content: Content,
+    #[allow(dead_code)]
     #[serde(rename = "finishReason")]
     finish_reason: Option<String>,
 }
@@ -74,24 +80,13 @@ pub async fn analyze_document_data(request: AnalyticsRequest) -> AnalyticsRespon
     }
 
     // Determine what to analyze
-    let (content_to_analyze, analysis_type) = match (&request.document_id, &request.input_data) {
-        // Priority 1: If document_id is provided, try to extract PDF content
+    let (content_to_analyze, _analysis_type) = match (&request.document_id, &request.input_data) {
+        // Priority 1: If document_id is provided, analyze the document and its extracted text
         (Some(doc_id), _) => {
             match get_document_safe(doc_id) {
                 Some(document) => {
-                    if document.file_type == "application/pdf" {
-                        // For now, we'll analyze the document metadata and financial data
-                        // In a production system, you'd want to extract actual PDF text
-                        let pdf_content = extract_document_content(&document);
-                        (pdf_content, "pdf_analysis".to_string())
-                    } else {
-                        return AnalyticsResponse {
-                            success: false,
-                            analysis: String::new(),
-                            error_message: "Document is not a PDF file".to_string(),
-                            analysis_type: "error".to_string(),
-                        };
-                    }
+                    let document_content = extract_document_content(&document);
+                    (document_content, "document_analysis".to_string())
                 }
                 None => {
                     return AnalyticsResponse {
@@ -139,7 +134,7 @@ fn truncate_text_smartly(text: &str, max_length: usize) -> String {
     }
 
     // Try to truncate at a sentence boundary
-    let truncated = &text[..max_length];
+    let truncated = truncate_at_char_boundary(text, max_length);
     if let Some(last_period) = truncated.rfind('.') {
         if last_period > max_length / 2 {
             // If we found a period in the latter half, truncate there
@@ -156,135 +151,202 @@ fn truncate_text_smartly(text: &str, max_length: usize) -> String {
     format!("{}\n\n[Note: Document content truncated for analysis]", truncated)
 }
 
-/// Extract text content from PDF binary data using lopdf
-fn extract_pdf_text(pdf_data: &[u8]) -> Result<String, String> {
-    // Try to parse PDF from bytes
-    let pdf_doc = match PdfDocument::load_mem(pdf_data) {
-        Ok(doc) => doc,
-        Err(e) => return Err(format!("Failed to parse PDF document: {:?}", e)),
-    };
-
-    let mut extracted_text = String::new();
-    
-    // Get all pages in the document
-    let pages = pdf_doc.get_pages();
-    
-    if pages.is_empty() {
-        return Err("PDF document contains no pages".to_string());
+/// Text of a document's file: stored at publication, extracted now for documents published before that
+/// Documents whose file was purged on retraction have no text
+fn document_text(document: &Document) -> Option<DocumentText> {
+    if document.retraction.as_ref().is_some_and(|retraction| retraction.file_purged) {
+        return None;
     }
-    
-    // Extract text from each page
-    for (page_id, _page_obj) in pages.iter() {
-        match pdf_doc.extract_text(&[*page_id]) {
-            Ok(page_text) => {
-                let trimmed_text = page_text.trim();
-                if !trimmed_text.is_empty() {
-                    extracted_text.push_str(trimmed_text);
-                    extracted_text.push_str("\n\n");
-                }
-            }
-            Err(e) => {
-                // Log the error but continue with other pages
-                ic_cdk::println!("Warning: Failed to extract text from page {}: {:?}", page_id, e);
-                // Continue processing other pages
-                continue;
-            }
-        }
-    }
-    
-    let cleaned_text = extracted_text.trim();
-    if cleaned_text.is_empty() {
-        Err("PDF contains no extractable text content".to_string())
-    } else {
-        // Truncate if too long to avoid API limits
-        let final_text = truncate_text_smartly(cleaned_text, MAX_PDF_TEXT_LENGTH);
-        Ok(final_text)
+    if let Some(document_text) = get_document_text_safe(&document.document_id) {
+        return Some(document_text);
     }
+    let file_data = get_document_file_safe(&document.document_id)?;
+    super::document::record_document_text(&document.document_id, &file_data)
 }
 
 /// Extract content from document for analysis
 fn extract_document_content(document: &Document) -> String {
-    // First, try to extract actual PDF content if it's a PDF file
-    let pdf_content = if document.file_type == "application/pdf" {
-        match extract_pdf_text(&document.file_data) {
-            Ok(text) => {
-                // Successfully extracted PDF text
-                Some(text)
-            }
-            Err(e) => {
-                // PDF extraction failed, log the error but continue with metadata
-                ic_cdk::println!("PDF text extraction failed: {}", e);
-                None
-            }
-        }
-    } else {
-        None
+    let extracted_content = document_text(document)
+        .map(|document_text| (document_text.source_format, truncate_text_smartly(&document_text.text, MAX_EXTRACTED_TEXT_LENGTH)));
+
+    let period = match &document.document_data {
+        DocumentType::EarningRelease(data) => format!("Quarter: Q{} {}", data.quarter, data.year),
+        DocumentType::AnnualReport(data) => format!("Fiscal Year: {}", data.fiscal_year),
+        DocumentType::DividendAnnouncement(data) => format!("Fiscal Year: {}", data.fiscal_year),
+        DocumentType::BoardResolution(data) => format!("Meeting Date: {}", format_timestamp_to_human_readable(data.meeting_date)),
+        DocumentType::Prospectus(data) => format!(
+            "Offering Period: {} to {}",
+            format_timestamp_to_human_readable(data.offering_start_date),
+            format_timestamp_to_human_readable(data.offering_end_date)
+        ),
     };
 
-    match &document.document_data {
-        DocumentType::EarningRelease(earning_data) => {
-            let mut content = format!(
-                "Financial Document Analysis for: {}\n\
-                Company: {}\n\
-                Description: {}\n\
-                Quarter: Q{} {}\n\n",
-                document.company_name,
-                document.company_name,
-                document.description,
-                earning_data.quarter,
-                earning_data.year
-            );
+    let mut content = format!(
+        "Financial Document Analysis for: {}\n\
+        Company: {}\n\
+        Document Type: {}\n\
+        Description: {}\n\
+        {}\n\n",
+        document.company_name,
+        document.company_name,
+        document.document_data.type_name(),
+        document.description,
+        period
+    );
+
+    // Add extracted document content if available
+    if let Some((ref label, ref text)) = extracted_content {
+        content.push_str(&format!("EXTRACTED {} CONTENT:\n", label));
+        content.push_str("=".repeat(50).as_str());
+        content.push('\n');
+        content.push_str(text);
+        content.push('\n');
+        content.push_str("=".repeat(50).as_str());
+        content.push_str("\n\n");
+    }
 
-            // Add PDF content if available
-            if let Some(ref pdf_text) = pdf_content {
-                content.push_str("EXTRACTED PDF CONTENT:\n");
-                content.push_str("=".repeat(50).as_str());
-                content.push_str("\n");
-                content.push_str(pdf_text);
-                content.push_str("\n");
-                content.push_str("=".repeat(50).as_str());
-                content.push_str("\n\n");
-            }
+    // Add structured data
+    content.push_str(&format_structured_data(&document.document_data));
+
+    content.push_str(&format!(
+        "Document Metadata:\n\
+        - Document Name: {}\n\
+        - File Type: {}\n\
+        - File Size: {} bytes\n\
+        - Analysis includes: {}",
+        document.name,
+        document.file_type,
+        document.file_size,
+        match extracted_content {
+            Some((label, _)) => format!("{} text content + structured data", label),
+            None => "structured data only".to_string(),
+        }
+    ));
+
+    content
+}
 
-            // Add structured financial data
-            content.push_str(&format!(
-                "STRUCTURED FINANCIAL METRICS:\n\
-                Income Statement:\n\
-                - Gross Profit: ${:.2}\n\
-                - Operating Profit: ${:.2}\n\
-                - EBITDA: ${:.2}\n\
-                - Profit Before Tax: ${:.2}\n\
-                - Net Profit: ${:.2}\n\n\
-                Balance Sheet:\n\
-                - Total Assets: ${:.2}\n\
-                - Total Equity: ${:.2}\n\
-                - Total Liabilities: ${:.2}\n\
-                - Total Liabilities and Equity: ${:.2}\n\n\
-                Document Metadata:\n\
-                - Document Name: {}\n\
-                - File Type: {}\n\
-                - File Size: {} bytes\n\
-                - Analysis includes: {}",
-                earning_data.consolidated_income_data.gross_profit,
-                earning_data.consolidated_income_data.operating_profit,
-                earning_data.consolidated_income_data.ebitda,
-                earning_data.consolidated_income_data.profit_before_tax,
-                earning_data.consolidated_income_data.net_profit,
-                earning_data.consolidated_balance_sheet_data.total_assets,
-                earning_data.consolidated_balance_sheet_data.total_equity,
-                earning_data.consolidated_balance_sheet_data.total_liabilities,
-                earning_data.consolidated_balance_sheet_data.total_liabilities_and_equity,
-                document.name,
-                document.file_type,
-                document.file_size,
-                if pdf_content.is_some() { "PDF text content + structured data" } else { "structured data only" }
-            ));
-
-            content
+/// Format the structured payload of a document for the analysis prompt
+fn format_structured_data(document_data: &DocumentType) -> String {
+    match document_data {
+        DocumentType::EarningRelease(data) => format!(
+            "{}{}",
+            format_financial_statements(&data.consolidated_income_data, &data.consolidated_balance_sheet_data),
+            format_cash_flow_and_per_share(&data.consolidated_cash_flow_data, &data.per_share_data)
+        ),
+        DocumentType::AnnualReport(data) => format!(
+            "AUDIT:\n\
+            - Auditor: {}\n\
+            - Audit Opinion: {:?}\n\n{}",
+            data.auditor_name,
+            data.audit_opinion,
+            format_financial_statements(&data.consolidated_income_data, &data.consolidated_balance_sheet_data)
+        ),
+        DocumentType::DividendAnnouncement(data) => format!(
+            "DIVIDEND DETAILS:\n\
+            - Dividend Per Share: ${:.4}\n\
+            - Ex-Dividend Date: {}\n\
+            - Record Date: {}\n\
+            - Payment Date: {}\n\n",
+            data.dividend_per_share,
+            format_timestamp_to_human_readable(data.ex_dividend_date),
+            format_timestamp_to_human_readable(data.record_date),
+            format_timestamp_to_human_readable(data.payment_date)
+        ),
+        DocumentType::BoardResolution(data) => {
+            let mut text = format!(
+                "BOARD RESOLUTION:\n\
+                - Resolution Number: {}\n\
+                - Subject: {}\n",
+                data.resolution_number,
+                data.subject
+            );
+            for (index, resolution) in data.resolutions.iter().enumerate() {
+                text.push_str(&format!("- Resolution {}: {}\n", index + 1, resolution));
+            }
+            text.push('\n');
+            text
         }
+        DocumentType::Prospectus(data) => format!(
+            "OFFERING DETAILS:\n\
+            - Security Type: {}\n\
+            - Offer Price: ${:.2}\n\
+            - Shares Offered: {}\n\
+            - Offering Amount: ${:.2}\n\n",
+            data.security_type,
+            data.offer_price,
+            data.shares_offered,
+            data.offering_amount
+        ),
     }
 }
 
+fn format_financial_statements(income: &ConsolidatedIncomeData, balance: &ConsolidatedBalanceSheetData) -> String {
+    format!(
+        "STRUCTURED FINANCIAL METRICS:\n\
+        Income Statement ({}):\n\
+        - Revenue: {}\n\
+        - Cost of Sales: {}\n\
+        - Gross Profit: {}\n\
+        - Operating Profit: {}\n\
+        - EBITDA: {}\n\
+        - Profit Before Tax: {}\n\
+        - Net Profit: {}\n\n\
+        Balance Sheet ({}):\n\
+        - Total Assets: {}\n\
+        - Total Equity: {}\n\
+        - Total Liabilities: {}\n\
+        - Total Liabilities and Equity: {}\n\n",
+        format_reporting_unit(&income.currency, income.scale),
+        income.revenue,
+        income.cost_of_sales,
+        income.gross_profit,
+        income.operating_profit,
+        income.ebitda,
+        income.profit_before_tax,
+        income.net_profit,
+        format_reporting_unit(&balance.currency, balance.scale),
+        balance.total_assets,
+        balance.total_equity,
+        balance.total_liabilities,
+        balance.total_liabilities_and_equity
+    )
+}
+
+// e.g. "EGP thousands"; records from before currencies were captured have none
+fn format_reporting_unit(currency: &str, scale: ReportingScale) -> String {
+    if currency.is_empty() {
+        format!("currency not specified, {}", scale.label())
+    } else {
+        format!("{} {}", currency, scale.label())
+    }
+}
+
+fn format_cash_flow_and_per_share(cash_flow: &ConsolidatedCashFlowData, per_share: &PerShareData) -> String {
+    format!(
+        "Cash Flow Statement:\n\
+        - Operating Cash Flow: ${:.2}\n\
+        - Investing Cash Flow: ${:.2}\n\
+        - Financing Cash Flow: ${:.2}\n\
+        - Capital Expenditure: ${:.2}\n\
+        - Free Cash Flow: ${:.2}\n\n\
+        Per Share:\n\
+        - EPS (Basic): ${:.4}\n\
+        - EPS (Diluted): ${:.4}\n\
+        - Shares Outstanding: {:.0}\n\
+        - Dividends Per Share: ${:.4}\n\n",
+        cash_flow.operating_cash_flow,
+        cash_flow.investing_cash_flow,
+        cash_flow.financing_cash_flow,
+        cash_flow.capital_expenditure,
+        cash_flow.operating_cash_flow - cash_flow.capital_expenditure,
+        per_share.eps_basic,
+        per_share.eps_diluted,
+        per_share.shares_outstanding,
+        per_share.dividends_per_share
+    )
+}
+
 /// Perform Gemini API request
 async fn perform_gemini_analysis(content: &str, focus: &str) -> Result<String, String> {
     let url = format!("{}?key={}", GEMINI_ENDPOINT, GEMINI_API_KEY);
@@ -1,13 +1,266 @@
 use ic_cdk::update;
 use ic_cdk::api::msg_caller;
-use crate::types::{DocumentResponse, Document};
-use crate::utils::{calculate_file_hash, generate_document_id, get_current_timestamp};
+use candid::Principal;
+use crate::types::{DocumentResponse, Document, DocumentRetraction, DocumentText, DocumentType, PdfMetadata, StampedFile, UserRole, XbrlReconciliation};
+use crate::utils::{calculate_file_hash, format_timestamp_to_human_readable, generate_document_id, get_current_timestamp};
+use crate::utils::file_type::{
+    detect_file_type, resolve_file_type, DetectedFileType, MIME_HTML, MIME_JPEG, MIME_PDF,
+    MIME_PLAIN_TEXT, MIME_PNG, MIME_TEXT_XML, MIME_XHTML, MIME_XLAM, MIME_XLS, MIME_XLSB, MIME_XLSM,
+    MIME_XLSX, MIME_XLTM, MIME_XML,
+};
+use crate::logging::{get_logger, get_severity_for_event_type};
+
+/// Maximum accepted file size in megabytes (applies to direct and chunked uploads)
+pub const MAX_FILE_SIZE_MB: usize = 10;
+
+// Hex characters of the file hash printed on stamped copies
+const STAMP_HASH_PREFIX_LENGTH: usize = 16;
 
 /// Custom upload endpoint for publishing documents to the icp blockchain
 #[update]
 pub async fn upload_file_and_publish_document(
     metadata: Document,
 ) -> DocumentResponse {
+    publish_document(metadata)
+}
+
+/// Validate document metadata before upload (file type, structured data, institution and publisher)
+/// Returns the normalized institution ID on success
+pub fn validate_document_metadata(metadata: &Document) -> Result<String, String> {
+    // Validate declared file type (checked against the content at publication)
+    let allowed_types = [
+        MIME_JPEG,
+        MIME_PNG,
+        MIME_PDF,
+        MIME_PLAIN_TEXT,
+        MIME_XLS,   // .xls
+        MIME_XLSX,  // .xlsx
+        MIME_XLSM,  // .xlsm
+        MIME_XLTM,  // .xltm
+        MIME_XLAM,  // .xlam
+        MIME_XLSB,  // .xlsb
+        MIME_XML,      // .xbrl / .xml instance documents
+        MIME_TEXT_XML,
+        MIME_HTML,     // .htm / .xhtml, e.g. inline XBRL filings
+        MIME_XHTML,
+    ];
+    crate::utils::validate_file_type(&metadata.file_type, &allowed_types)?;
+
+    // Validate structured data (accounting consistency errors block publication)
+    if metadata.document_category != metadata.document_data.category() {
+        return Err(format!("Document category does not match the {} document data", metadata.document_data.type_name()));
+    }
+    crate::validation::ensure_valid(&crate::validation::validate_document_data(&metadata.document_data))?;
+
+    // Normalize and validate institution_id (trim whitespace and check if empty)
+    let normalized_institution_id = metadata.institution_id.trim().to_string();
+    
+    // Validate that institution exists if specified (non-empty institution_id after trimming)
+    if !normalized_institution_id.is_empty() {
+        let institution_exists = crate::storage::get_institution_safe(&normalized_institution_id).is_some();
+        
+        if !institution_exists {
+            return Err("Specified institution does not exist".to_string());
+        }
+    }
+
+    authorize_publisher(&normalized_institution_id)?;
+
+    if let Some(superseded_id) = &metadata.supersedes {
+        validate_amendment(metadata, superseded_id, &normalized_institution_id)?;
+    }
+
+    Ok(normalized_institution_id)
+}
+
+/// Check that the caller may publish under the given institution
+/// Anonymous callers are rejected; institution documents require the institution owner or a linked member
+fn authorize_publisher(institution_id: &str) -> Result<Principal, String> {
+    let caller = crate::utils::require_authenticated_user()
+        .inspect_err(|_| log_publish_denied(&format!("Anonymous publish attempt rejected (institution: '{}')", institution_id)))?;
+
+    if institution_id.is_empty() {
+        return Ok(caller);
+    }
+
+    let is_owner = crate::storage::get_institution_safe(institution_id)
+        .is_some_and(|institution| institution.owner == caller);
+    let is_member = crate::storage::get_user_profile_safe(&caller)
+        .is_some_and(|profile| profile.role == UserRole::InstitutionMember(institution_id.to_string()));
+
+    if !is_owner && !is_member {
+        log_publish_denied(&format!("Principal {} is not a member of institution {}", caller, institution_id));
+        return Err("Access denied. Only the institution owner and its members can publish under this institution.".to_string());
+    }
+
+    Ok(caller)
+}
+
+// Helper function for logging rejected publications
+fn log_publish_denied(message: &str) {
+    let logger = get_logger("document");
+    let severity = get_severity_for_event_type("PUBLISH_DENIED");
+    logger.log(severity, "PUBLISH_DENIED", message, None);
+}
+
+/// Validate that an amendment may supersede the given document
+/// Only the latest, non-retracted version can be amended, by its owner, within the same institution and category
+fn validate_amendment(metadata: &Document, superseded_id: &str, institution_id: &str) -> Result<(), String> {
+    let superseded = crate::storage::get_document_safe(superseded_id)
+        .ok_or("Superseded document does not exist")?;
+
+    if superseded.owner != msg_caller() {
+        return Err("Access denied. You can only amend your own documents.".to_string());
+    }
+
+    if let Some(latest_id) = crate::storage::get_superseding_document_id(superseded_id) {
+        return Err(format!("Document has already been superseded by {}. Amend the latest version instead.", latest_id));
+    }
+
+    if superseded.retraction.is_some() {
+        return Err("Retracted documents cannot be amended".to_string());
+    }
+
+    if superseded.institution_id != institution_id {
+        return Err("Amendments must be published by the same institution".to_string());
+    }
+
+    if std::mem::discriminant(&superseded.document_category) != std::mem::discriminant(&metadata.document_category) {
+        return Err("Amendments must keep the document category".to_string());
+    }
+
+    Ok(())
+}
+
+/// Replace the data of an earning release filed as XBRL or inline XBRL with the figures derived from the filing
+/// Returns None for other documents; XML and HTML files of other document types are stored as-is
+fn apply_xbrl_filing(metadata: &mut Document, file_type: &str) -> Result<Option<XbrlReconciliation>, String> {
+    let DocumentType::EarningRelease(client_data) = &metadata.document_data else {
+        return Ok(None);
+    };
+
+    // Markup declared as plain text is kept as text
+    let instance = match detect_file_type(&metadata.file_data) {
+        _ if file_type == MIME_PLAIN_TEXT => return Ok(None),
+        Some(DetectedFileType::Xml) => crate::xbrl::parse_instance(&metadata.file_data)?,
+        Some(DetectedFileType::Html) if crate::xbrl::is_inline_xbrl(&metadata.file_data) => {
+            crate::xbrl::parse_inline_instance(&metadata.file_data)?
+        }
+        _ => return Ok(None),
+    };
+
+    let derived = crate::xbrl::derive_earning_release(&instance)?;
+    let (data, reconciliation) = crate::xbrl::reconcile_earning_release(client_data, &derived)?;
+
+    // Totals are checked on the filed amounts; rounding each one to the client's scale can break them
+    let in_units = crate::xbrl::statements_in_units(&data, &derived)?;
+    crate::validation::ensure_valid(&crate::validation::validate_earning_release_data(&in_units))?;
+    metadata.document_data = DocumentType::EarningRelease(data);
+
+    Ok(Some(reconciliation))
+}
+
+/// Extract and store the text of a published file so search, analytics and audits share it
+/// Extraction failures (e.g. scanned PDFs without a text layer) do not affect the publication
+pub fn record_document_text(document_id: &str, file_data: &[u8]) -> Option<DocumentText> {
+    let (source_format, text) = match crate::extraction::extract_document_text(file_data) {
+        Ok(extracted) => extracted?,
+        Err(e) => {
+            ic_cdk::println!("Text extraction failed for document {}: {}", document_id, e);
+            return None;
+        }
+    };
+
+    let stored_text = crate::extraction::truncate_at_char_boundary(&text, crate::extraction::MAX_STORED_TEXT_BYTES);
+    let document_text = DocumentText {
+        document_id: document_id.to_string(),
+        source_format: source_format.to_string(),
+        text: stored_text.to_string(),
+        truncated: stored_text.len() < text.len(),
+        extracted_at: get_current_timestamp(),
+    };
+    crate::storage::store_document_text_safe(&document_text);
+
+    Some(document_text)
+}
+
+/// Read the structure of PDF uploads; None for other formats
+fn read_pdf_structure(file_data: &[u8]) -> Result<Option<PdfMetadata>, String> {
+    if detect_file_type(file_data) != Some(DetectedFileType::Pdf) {
+        return Ok(None);
+    }
+    crate::pdf::read_pdf_metadata(file_data).map(Some)
+}
+
+/// Fill blank earning release fields from an Excel workbook using the institution's mapping
+/// Returns the figures that disagree with the workbook: rejected with a configured mapping,
+/// only reported with the default one, whose label search can pick a note number or prior-year column
+fn apply_spreadsheet(metadata: &mut Document, institution_id: &str) -> Result<Vec<String>, String> {
+    let DocumentType::EarningRelease(client_data) = &metadata.document_data else {
+        return Ok(Vec::new());
+    };
+    let is_workbook = matches!(
+        detect_file_type(&metadata.file_data),
+        Some(DetectedFileType::Xlsx | DetectedFileType::XlsxMacro | DetectedFileType::Xlsb | DetectedFileType::Xls)
+    );
+    if !is_workbook {
+        return Ok(Vec::new());
+    }
+
+    let configured_mapping = crate::storage::get_spreadsheet_mapping_safe(institution_id);
+    let is_configured = configured_mapping.is_some();
+    let mapping = configured_mapping.unwrap_or_else(crate::spreadsheet::default_mapping);
+    let worksheets = crate::spreadsheet::parse_workbook(&metadata.file_data)?;
+    let values = crate::spreadsheet::extract_earning_release(&worksheets, &mapping);
+    if values.is_empty() {
+        return Ok(Vec::new());
+    }
+
+    let (data, mismatches) = crate::spreadsheet::apply_spreadsheet_values(client_data, &values);
+    if is_configured && !mismatches.is_empty() {
+        return Err(format!("Submitted data does not match the spreadsheet: {}", mismatches.join("; ")));
+    }
+    crate::validation::ensure_valid(&crate::validation::validate_earning_release_data(&data))?;
+    metadata.document_data = DocumentType::EarningRelease(data);
+
+    Ok(mismatches)
+}
+
+// Submitted figures kept although the workbook located by the default mapping disagrees
+fn log_spreadsheet_mismatches(document_id: &str, mismatches: &[String]) {
+    if mismatches.is_empty() {
+        return;
+    }
+
+    let logger = get_logger("document");
+    let severity = get_severity_for_event_type("SPREADSHEET_MISMATCH");
+    logger.log(
+        severity,
+        "SPREADSHEET_MISMATCH",
+        &format!("Document {}: submitted values differ from the spreadsheet: {}", document_id, mismatches.join("; ")),
+        None,
+    );
+}
+
+// Helper function for logging client figures that disagree with the filing
+fn log_xbrl_mismatches(document_id: &str, reconciliation: &XbrlReconciliation) {
+    if reconciliation.mismatches.is_empty() {
+        return;
+    }
+
+    let fields: Vec<&str> = reconciliation.mismatches.iter().map(|mismatch| mismatch.field.as_str()).collect();
+    let logger = get_logger("document");
+    let severity = get_severity_for_event_type("XBRL_MISMATCH");
+    logger.log(
+        severity,
+        "XBRL_MISMATCH",
+        &format!("Document {}: submitted values replaced by the XBRL filing for {}", document_id, fields.join(", ")),
+        None,
+    );
+}
+
+/// Validate, hash and store a complete document (shared by direct and chunked uploads)
+pub fn publish_document(metadata: Document) -> DocumentResponse {
     // Validate file data is not empty
     if metadata.file_data.is_empty() {
         return DocumentResponse {
@@ -19,7 +272,7 @@ pub async fn upload_file_and_publish_document(
     }
     
     // Validate file size (max 10MB for Excel and other document types)
-    if let Err(e) = crate::utils::validate_file_size(metadata.file_data.len(), 10) {
+    if let Err(e) = crate::utils::validate_file_size(metadata.file_data.len(), MAX_FILE_SIZE_MB) {
         return DocumentResponse {
             success: false,
             document_id: String::new(),
@@ -28,67 +281,99 @@ pub async fn upload_file_and_publish_document(
         };
     }
 
-    // Validate file type
-    let allowed_types = vec![
-        "image/jpeg", 
-        "image/png", 
-        "application/pdf", 
-        "text/plain",
-        "application/vnd.ms-excel",                    // .xls
-        "application/vnd.openxmlformats-officedocument.spreadsheetml.sheet", // .xlsx
-        "application/vnd.ms-excel.sheet.macroEnabled.12", // .xlsm
-        "application/vnd.ms-excel.template.macroEnabled.12", // .xltm
-        "application/vnd.ms-excel.addin.macroEnabled.12", // .xlam
-        "application/vnd.ms-excel.sheet.binary.macroEnabled.12", // .xlsb
-        ""
-    ];
-    if let Err(e) = crate::utils::validate_file_type(&metadata.file_type, &allowed_types) {
-        return DocumentResponse {
-            success: false,
-            document_id: String::new(),
-            error_message: e,
-            file_hash: String::new(),
-        };
-    }
+    let normalized_institution_id = match validate_document_metadata(&metadata) {
+        Ok(institution_id) => institution_id,
+        Err(e) => {
+            return DocumentResponse {
+                success: false,
+                document_id: String::new(),
+                error_message: e,
+                file_hash: String::new(),
+            };
+        }
+    };
 
-    // Normalize and validate institution_id (trim whitespace and check if empty)
-    let normalized_institution_id = metadata.institution_id.trim().to_string();
-    
-    // Validate that institution exists if specified (non-empty institution_id after trimming)
-    if !normalized_institution_id.is_empty() {
-        let institution_exists = crate::storage::get_institution_safe(&normalized_institution_id).is_some();
-        
-        if !institution_exists {
+    // The stored file type comes from the content, not the client
+    let file_type = match resolve_file_type(&metadata.file_type, &metadata.file_data) {
+        Ok(file_type) => file_type,
+        Err(e) => {
             return DocumentResponse {
                 success: false,
                 document_id: String::new(),
-                error_message: "Specified institution does not exist".to_string(),
+                error_message: e,
                 file_hash: String::new(),
             };
         }
-    }
+    };
+
+    // XBRL filings are the source of truth for the statements they contain
+    let mut metadata = metadata;
+    let xbrl_reconciliation = match apply_xbrl_filing(&mut metadata, &file_type) {
+        Ok(reconciliation) => reconciliation,
+        Err(e) => {
+            return DocumentResponse {
+                success: false,
+                document_id: String::new(),
+                error_message: e,
+                file_hash: String::new(),
+            };
+        }
+    };
+
+    // Excel earnings packs fill in and cross-check the submitted figures
+    let spreadsheet_mismatches = match apply_spreadsheet(&mut metadata, &normalized_institution_id) {
+        Ok(mismatches) => mismatches,
+        Err(e) => {
+            return DocumentResponse {
+                success: false,
+                document_id: String::new(),
+                error_message: e,
+                file_hash: String::new(),
+            };
+        }
+    };
+
+    // PDFs are parsed now so corrupt files are rejected before they are notarized
+    let pdf_metadata = match read_pdf_structure(&metadata.file_data) {
+        Ok(pdf_metadata) => pdf_metadata,
+        Err(e) => {
+            return DocumentResponse {
+                success: false,
+                document_id: String::new(),
+                error_message: e,
+                file_hash: String::new(),
+            };
+        }
+    };
+
+    // Amendments continue the version chain of the document they supersede
+    let version = metadata.supersedes.as_deref()
+        .and_then(crate::storage::get_document_safe)
+        .map(|superseded| superseded.version + 1)
+        .unwrap_or(1);
 
     // Generate unique document ID
     let document_id = generate_document_id();
     
     // Calculate file hash for integrity verification and storage
     let calculated_hash = calculate_file_hash(&metadata.file_data);
-    
-    // Get current timestamp (for potential future use)
-    let _uploaded_at = get_current_timestamp();
 
     // Create complete document with file data and calculated hash, using normalized IDs
     let mut document = metadata;
     document.document_id = document_id.clone();
     document.file_hash = calculated_hash.clone();
+    document.file_size = document.file_data.len() as u64;
+    document.file_type = file_type;
     document.institution_id = normalized_institution_id;
     document.publication_date = get_current_timestamp();
     
     // Override the owner with the authenticated caller's principal for security
     document.owner = msg_caller();
+    document.retraction = None;
+    document.version = version;
 
     // Store the complete document using safe storage function
-    if let Err(e) = crate::storage::store_document_safe(&document_id, &document) {
+    if let Err(e) = crate::storage::insert_document_safe(&document_id, &document) {
         return DocumentResponse {
             success: false,
             document_id: String::new(),
@@ -97,6 +382,19 @@ pub async fn upload_file_and_publish_document(
         };
     }
 
+    record_document_text(&document_id, &document.file_data);
+
+    if let Some(mut pdf_metadata) = pdf_metadata {
+        pdf_metadata.document_id = document_id.clone();
+        crate::storage::store_pdf_metadata_safe(&pdf_metadata);
+    }
+
+    if let Some(reconciliation) = xbrl_reconciliation {
+        log_xbrl_mismatches(&document_id, &reconciliation);
+        crate::storage::store_xbrl_reconciliation_safe(&document_id, &reconciliation);
+    }
+    log_spreadsheet_mismatches(&document_id, &spreadsheet_mismatches);
+
     // Return success response
     DocumentResponse {
         success: true,
@@ -106,9 +404,11 @@ pub async fn upload_file_and_publish_document(
     }
 }
 
-/// Delete a document
+/// Retract a published document
+/// The notarized record (hash, timestamp, metadata) is kept and marked as retracted;
+/// purge_file_data additionally drops this document's copy of the file contents
 #[update]
-pub async fn delete_document(document_id: String) -> DocumentResponse {
+pub async fn retract_document(document_id: String, reason: String, purge_file_data: bool) -> DocumentResponse {
     // Require authentication
     let caller = match crate::utils::require_authenticated_user() {
         Ok(principal) => principal,
@@ -122,6 +422,16 @@ pub async fn delete_document(document_id: String) -> DocumentResponse {
         }
     };
 
+    let reason = reason.trim().to_string();
+    if let Err(e) = crate::utils::validate_string_length(&reason, 1, 1000, "Retraction reason") {
+        return DocumentResponse {
+            success: false,
+            document_id: String::new(),
+            error_message: e,
+            file_hash: String::new(),
+        };
+    }
+
     // Get the document to check ownership
     let document = match crate::storage::get_document_safe(&document_id) {
         Some(doc) => doc,
@@ -140,30 +450,86 @@ pub async fn delete_document(document_id: String) -> DocumentResponse {
         return DocumentResponse {
             success: false,
             document_id: String::new(),
-            error_message: "Access denied. You can only delete your own documents.".to_string(),
+            error_message: "Access denied. You can only retract your own documents.".to_string(),
             file_hash: String::new(),
         };
     }
 
-    // Delete the document
-    let deleted = crate::storage::DOCUMENTS.with(|storage| {
-        storage.borrow_mut().remove(&crate::storage::memory::StorableString(document_id.clone()))
-    });
+    let retraction = DocumentRetraction {
+        retracted_by: caller,
+        retracted_at: get_current_timestamp(),
+        reason,
+        file_purged: purge_file_data,
+    };
 
-    if deleted.is_some() {
-        ic_cdk::println!("Document {} deleted by user {}", document_id, caller);
-        DocumentResponse {
-            success: true,
-            document_id: document_id.clone(),
-            error_message: String::new(),
-            file_hash: document.file_hash,
+    match crate::storage::retract_document_safe(&document_id, retraction) {
+        Ok(retracted) => {
+            ic_cdk::println!("Document {} retracted by user {}", document_id, caller);
+            DocumentResponse {
+                success: true,
+                document_id,
+                error_message: String::new(),
+                file_hash: retracted.file_hash,
+            }
         }
-    } else {
-        DocumentResponse {
+        Err(e) => DocumentResponse {
             success: false,
             document_id: String::new(),
-            error_message: "Failed to delete document".to_string(),
+            error_message: e,
             file_hash: String::new(),
-        }
+        },
+    }
+}
+
+/// Build the stamped copy of a PDF document, with the document ID, hash prefix and publication time printed
+/// on every page, so get_stamped_document_file_chunk can serve it; file_hash is that of the stamped copy
+/// The copy is built once and kept until the document is retracted; only signed-in users can have one built
+#[update]
+pub fn prepare_stamped_document_file(document_id: String) -> DocumentResponse {
+    match crate::utils::require_authenticated_user().and_then(|_| stamped_file(&document_id)) {
+        Ok(stamped) => DocumentResponse {
+            success: true,
+            document_id,
+            error_message: String::new(),
+            file_hash: stamped.file_hash,
+        },
+        Err(e) => DocumentResponse {
+            success: false,
+            document_id,
+            error_message: e,
+            file_hash: String::new(),
+        },
     }
-} 
\ No newline at end of file
+}
+
+// Stored stamped copy of a document, built and stored first if there is none
+fn stamped_file(document_id: &str) -> Result<StampedFile, String> {
+    if let Some(stamped) = crate::storage::get_stamped_file_safe(document_id) {
+        return Ok(stamped);
+    }
+
+    let document = crate::storage::get_document_with_file_safe(document_id)
+        .ok_or("Document not found")?;
+
+    if document.retraction.as_ref().is_some_and(|retraction| retraction.file_purged) {
+        return Err("File contents of this retracted document have been purged".to_string());
+    }
+    if detect_file_type(&document.file_data) != Some(DetectedFileType::Pdf) {
+        return Err("Only PDF documents can be stamped".to_string());
+    }
+
+    let mut footer = format!(
+        "Notarized by ChainNotary \u{B7} Document ID {} \u{B7} SHA-256 {}\u{2026} \u{B7} Published {} UTC",
+        document.document_id,
+        &document.file_hash[..std::cmp::min(STAMP_HASH_PREFIX_LENGTH, document.file_hash.len())],
+        format_timestamp_to_human_readable(document.publication_date)
+    );
+    if document.retraction.is_some() {
+        footer.push_str(" \u{B7} RETRACTED");
+    }
+
+    let data = crate::pdf::stamp_pdf(&document.file_data, &footer)?;
+    let stamped = StampedFile { file_hash: calculate_file_hash(&data), data };
+    crate::storage::store_stamped_file_safe(document_id, &stamped);
+    Ok(stamped)
+}
@@ -1,24 +1,136 @@
 use ic_cdk::query;
 use candid::Principal;
-use crate::types::{Document, DocumentType, DocumentSummary};
-use crate::storage::{DOCUMENTS, StorableString};
+use crate::types::{Document, DocumentType, DocumentSummary, DocumentFileChunk, CertifiedDocumentResponse, DocumentText, PdfMetadata, XbrlReconciliation};
+use crate::storage::DOCUMENTS;
 
 // ============================================================================
 // DOCUMENT QUERY FUNCTIONS
 // ============================================================================
 
+// Chunks must fit in a single query reply (~2MB including Candid overhead)
+const MAX_DOWNLOAD_CHUNK_SIZE_BYTES: u64 = 1_900_000;
+
 /// Get document metadata by document ID (fast query, no file data)
 #[query]
 pub fn get_document_metadata(document_id: String) -> Option<Document> {
     crate::storage::get_document_safe(&document_id)
 }
 
+/// Get how the structured data of a document was derived from its XBRL filing
+/// None for documents not published with an XBRL instance
+#[query]
+pub fn get_xbrl_reconciliation(document_id: String) -> Option<XbrlReconciliation> {
+    crate::storage::get_xbrl_reconciliation_safe(&document_id)
+}
+
+/// Get the text extracted from a document's file at publication
+/// None for files without text (images, scanned PDFs) and documents not yet extracted
+#[query]
+pub fn get_document_text(document_id: String) -> Option<DocumentText> {
+    crate::storage::get_document_text_safe(&document_id)
+}
+
+/// Get the page count, PDF version, Info fields, encryption status, embedded files and signatures of a PDF document
+/// None for other formats
+#[query]
+pub fn get_pdf_metadata(document_id: String) -> Option<PdfMetadata> {
+    crate::storage::get_pdf_metadata_safe(&document_id)
+}
+
+/// Get certified document metadata (no file data) with the data certificate and witness
+/// Clients verify the certificate against the IC root key and the witness against the certified data
+#[query]
+pub fn get_certified_document_metadata(document_id: String) -> Result<CertifiedDocumentResponse, String> {
+    let document = crate::storage::get_document_safe(&document_id)
+        .ok_or("Document not found")?;
+
+    let certificate = ic_cdk::api::data_certificate()
+        .ok_or("Data certificate is only available in non-replicated query calls")?;
+
+    let witness = crate::certification::document_witness(&document_id);
+    let witness = crate::certification::encode_witness(&witness)?;
+
+    Ok(CertifiedDocumentResponse {
+        document,
+        certificate,
+        witness,
+    })
+}
+
 /// Get document file data by document ID (loads file data)
 #[query]
 pub fn get_document_file(document_id: String) -> Option<Vec<u8>> {
-    DOCUMENTS.with(|storage| {
-        storage.borrow().get(&StorableString(document_id))
-            .map(|storable_doc| storable_doc.0.file_data)
+    crate::storage::get_document_file_safe(&document_id)
+}
+
+/// Get a byte range of a document file for streaming downloads
+/// The length is capped to the maximum chunk size; clients should check the reassembled bytes against file_hash
+#[query]
+pub fn get_document_file_chunk(document_id: String, offset: u64, length: u64) -> Result<DocumentFileChunk, String> {
+    if length == 0 {
+        return Err("Chunk length must be greater than zero".to_string());
+    }
+
+    let document = crate::storage::get_document_safe(&document_id)
+        .ok_or("Document not found")?;
+
+    if document.retraction.as_ref().is_some_and(|retraction| retraction.file_purged) {
+        return Err("File contents of this retracted document have been purged".to_string());
+    }
+
+    // Records not yet rewritten by the v1 migration still hold their file data inline
+    if !document.file_data.is_empty() {
+        let total_size = document.file_data.len() as u64;
+        return file_chunk(document_id, document.file_hash, total_size, offset, length, |start, end| {
+            document.file_data.get(start as usize..end as usize).map(<[u8]>::to_vec)
+        });
+    }
+
+    // Only the stored pieces covering the requested range are loaded
+    let file_hash = document.file_hash.clone();
+    file_chunk(document_id, document.file_hash, document.file_size, offset, length, |start, end| {
+        crate::storage::read_file_blob_range(&file_hash, start, end)
+    })
+}
+
+/// Get a byte range of the stamped copy of a PDF document built by prepare_stamped_document_file
+/// The stored original is unchanged, so file_hash here is that of the stamped copy
+#[query]
+pub fn get_stamped_document_file_chunk(document_id: String, offset: u64, length: u64) -> Result<DocumentFileChunk, String> {
+    if length == 0 {
+        return Err("Chunk length must be greater than zero".to_string());
+    }
+
+    let stamped = crate::storage::get_stamped_file_safe(&document_id)
+        .ok_or("No stamped copy of this document; call prepare_stamped_document_file first")?;
+
+    let total_size = stamped.data.len() as u64;
+    file_chunk(document_id, stamped.file_hash, total_size, offset, length, |start, end| {
+        stamped.data.get(start as usize..end as usize).map(<[u8]>::to_vec)
+    })
+}
+
+// Build a download chunk, reading only the bytes it covers
+fn file_chunk(
+    document_id: String,
+    file_hash: String,
+    total_size: u64,
+    offset: u64,
+    length: u64,
+    read_range: impl FnOnce(u64, u64) -> Option<Vec<u8>>,
+) -> Result<DocumentFileChunk, String> {
+    if offset > total_size {
+        return Err(format!("Offset {} is beyond the end of the file ({} bytes)", offset, total_size));
+    }
+
+    let end = std::cmp::min(offset.saturating_add(std::cmp::min(length, MAX_DOWNLOAD_CHUNK_SIZE_BYTES)), total_size);
+
+    Ok(DocumentFileChunk {
+        document_id,
+        offset,
+        total_size,
+        chunk: read_range(offset, end).ok_or("File data is not available")?,
+        file_hash,
     })
 }
 
@@ -32,6 +144,7 @@ pub fn get_all_document_ids() -> Vec<String> {
 
 /// Unified document query function with comprehensive filtering, sorting, and pagination
 #[query]
+#[allow(clippy::too_many_arguments)]
 pub fn query_documents(
     // Document ID filter
     doc_id: Option<String>,
@@ -56,15 +169,19 @@ pub fn query_documents(
     sort_order: Option<String>, // "asc", "desc"
     
     // Data control
-    include_file_data: Option<bool>
+    include_file_data: Option<bool>,
+    
+    // Versioning: superseded versions are hidden unless requested (or looked up by ID)
+    include_superseded: Option<bool>
 ) -> (Vec<Document>, u64) { // Returns (documents, total_count)
     let include_file_data = include_file_data.unwrap_or(true);
+    let include_superseded = include_superseded.unwrap_or(false) || doc_id.is_some();
     let offset = offset.unwrap_or(0);
     let limit = limit.unwrap_or(10);
     let sort_by = sort_by.unwrap_or_else(|| "date".to_string());
     let sort_order = sort_order.unwrap_or_else(|| "desc".to_string());
     
-    // Get all documents and apply filters
+    // Get all document metadata and apply filters (file data is attached after pagination)
     let mut filtered_docs: Vec<Document> = DOCUMENTS.with(|storage| {
         storage.borrow().iter()
             .map(|(_, storable_doc)| storable_doc.0)
@@ -99,16 +216,12 @@ pub fn query_documents(
                 
                 // Document type filter
                 if let Some(type_filter) = &document_type {
-                    match &doc.document_data {
-                        DocumentType::EarningRelease(_) => {
-                            if type_filter != "EarningRelease" {
-                                return false;
-                            }
-                        }
+                    if doc.document_data.type_name() != type_filter {
+                        return false;
                     }
                 }
                 
-                // Quarter and year filter
+                // Quarter and year filter (only earning releases are quarterly)
                 if let (Some(quarter_filter), Some(year_filter)) = (quarter, year) {
                     match &doc.document_data {
                         DocumentType::EarningRelease(data) => {
@@ -116,6 +229,7 @@ pub fn query_documents(
                                 return false;
                             }
                         }
+                        _ => return false,
                     }
                 }
                 
@@ -131,6 +245,11 @@ pub fn query_documents(
                     }
                 }
                 
+                // Latest version filter
+                if !include_superseded && crate::storage::get_superseding_document_id(&doc.document_id).is_some() {
+                    return false;
+                }
+                
                 true
             })
             .collect()
@@ -152,11 +271,11 @@ pub fn query_documents(
                 filtered_docs.sort_by(|a, b| b.institution_id.cmp(&a.institution_id));
             }
         }
-        "date" | _ => {
+        _ => {
             if sort_order == "asc" {
-                filtered_docs.sort_by(|a, b| a.publication_date.cmp(&b.publication_date));
+                filtered_docs.sort_by_key(|doc| doc.publication_date);
             } else {
-                filtered_docs.sort_by(|a, b| b.publication_date.cmp(&a.publication_date));
+                filtered_docs.sort_by_key(|doc| std::cmp::Reverse(doc.publication_date));
             }
         }
     }
@@ -173,14 +292,11 @@ pub fn query_documents(
         filtered_docs[start_idx..end_idx].to_vec()
     };
     
-    // Remove file data if requested
+    // Load file data only for the returned page, if requested
     let final_docs = if include_file_data {
-        paginated_docs
+        paginated_docs.into_iter().map(crate::storage::attach_file_data).collect()
     } else {
-        paginated_docs.into_iter().map(|mut doc| {
-            doc.file_data = Vec::new(); // Remove file data
-            doc
-        }).collect()
+        paginated_docs
     };
     
     (final_docs, total_count)
@@ -203,6 +319,7 @@ pub fn get_documents_by_owner(owner: Principal) -> Vec<DocumentSummary> {
         None, // sort_by
         None, // sort_order
         Some(false), // include_file_data = false for DocumentSummary
+        Some(true), // include_superseded: owners see every version they published
     );
     
     // Convert Document to DocumentSummary
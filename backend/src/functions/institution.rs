@@ -1,5 +1,5 @@
 use ic_cdk::{update, api::msg_caller};
-use crate::types::Institution;
+use crate::types::{Institution, SpreadsheetFieldMapping};
 use crate::storage::{INSTITUTIONS, StorableString};
 use crate::utils::{generate_institution_id, get_current_timestamp};
 
@@ -17,15 +17,6 @@ pub fn create_institution(
     
     // Generate unique institution ID
     let institution_id = generate_institution_id();
-    
-    // Check if institution already exists (shouldn't happen with timestamp-based IDs, but safety check)
-    let exists = INSTITUTIONS.with(|storage| {
-        storage.borrow().contains_key(&StorableString(institution_id.clone()))
-    });
-    
-    if exists {
-        return Err("Generated institution ID already exists. Please try again.".to_string());
-    }
 
     let institution = Institution {
         institution_id: institution_id.clone(),
@@ -35,7 +26,7 @@ pub fn create_institution(
         created_at: get_current_timestamp(),
     };
 
-    crate::storage::update_institution_safe(&institution_id, &institution)?;
+    crate::storage::insert_institution_safe(&institution_id, &institution)?;
 
     Ok(institution_id)
 }
@@ -89,7 +80,22 @@ pub fn delete_institution(institution_id: String) -> Result<(), String> {
     Ok(())
 }
 
+/// Configure where the earning release fields are read from in this institution's Excel uploads
+/// (only owner can configure; an empty mapping restores the default row labels)
+#[update]
+pub fn set_spreadsheet_mapping(institution_id: String, mapping: Vec<SpreadsheetFieldMapping>) -> Result<(), String> {
+    let caller = msg_caller();
 
+    let institution = crate::storage::get_institution_safe(&institution_id)
+        .ok_or("Institution not found")?;
 
+    // Check ownership
+    if institution.owner != caller {
+        return Err("Only the institution owner can configure the spreadsheet mapping".to_string());
+    }
 
+    crate::spreadsheet::validate_mapping(&mapping)?;
+    crate::storage::update_spreadsheet_mapping_safe(&institution_id, &mapping);
 
+    Ok(())
+}
@@ -1,6 +1,6 @@
 use ic_cdk::query;
 use candid::Principal;
-use crate::types::Institution;
+use crate::types::{Institution, SpreadsheetFieldMapping};
 use crate::storage::INSTITUTIONS;
 
 // ============================================================================
@@ -13,6 +13,14 @@ pub fn get_institution_metadata(institution_id: String) -> Option<Institution> {
     crate::storage::get_institution_safe(&institution_id)
 }
 
+/// Get the spreadsheet mapping applied to an institution's Excel uploads
+/// Institutions without a configured mapping (and documents without an institution) use the default row labels
+#[query]
+pub fn get_spreadsheet_mapping(institution_id: String) -> Vec<SpreadsheetFieldMapping> {
+    crate::storage::get_spreadsheet_mapping_safe(&institution_id)
+        .unwrap_or_else(crate::spreadsheet::default_mapping)
+}
+
 /// Get all institutions with full metadata
 #[query]
 pub fn get_all_institutions() -> Vec<Institution> {
@@ -1,4 +1,5 @@
 pub mod document;
+pub mod upload_session;
 pub mod institution;
 pub mod analytics;
 pub mod user_management;
@@ -8,8 +9,12 @@ pub mod document_queries;
 pub mod institution_queries;
 pub mod search_queries;
 pub mod admin_queries;
+pub mod verification_queries;
+pub mod version_queries;
+pub mod validation_queries;
 
 pub use document::*;
+pub use upload_session::*;
 pub use institution::*;
 pub use analytics::*;
 pub use user_management::*;
@@ -1,12 +1,13 @@
 use ic_cdk::query;
-use crate::types::{Document, Institution};
-use crate::storage::{DOCUMENTS, INSTITUTIONS};
+use crate::types::{Document, Institution, PdfMetadata, PdfMetadataFilter, PdfSignatureStatus};
+use crate::storage::{DOCUMENTS, INSTITUTIONS, PDF_METADATA};
 
 // ============================================================================
 // SEARCH FUNCTIONS
 // ============================================================================
 
 /// Search documents by name (case-insensitive partial match)
+/// Results carry metadata only; file contents are fetched with get_document_file_chunk
 #[query]
 pub fn search_documents_by_name(search_term: String) -> Vec<Document> {
     let search_term_lower = search_term.to_lowercase();
@@ -18,6 +19,36 @@ pub fn search_documents_by_name(search_term: String) -> Vec<Document> {
     })
 }
 
+/// Search PDF documents by their structural metadata and signatures (all given filters must match)
+#[query]
+pub fn search_documents_by_pdf_metadata(filter: PdfMetadataFilter) -> Vec<PdfMetadata> {
+    let contains = |value: &Option<String>, search_term: &Option<String>| match search_term {
+        Some(search_term) => value.as_ref()
+            .is_some_and(|value| value.to_lowercase().contains(&search_term.to_lowercase())),
+        None => true,
+    };
+
+    PDF_METADATA.with(|storage| {
+        storage.borrow().iter()
+            .map(|(_, storable_metadata)| storable_metadata.0)
+            .filter(|metadata| {
+                contains(&metadata.title, &filter.title)
+                    && contains(&metadata.author, &filter.author)
+                    && contains(&metadata.producer, &filter.producer)
+                    && filter.encrypted.is_none_or(|encrypted| metadata.encrypted == encrypted)
+                    && filter.has_embedded_files.is_none_or(|has_files| metadata.embedded_files.is_empty() != has_files)
+                    && filter.min_pages.is_none_or(|min_pages| metadata.page_count >= min_pages)
+                    && filter.max_pages.is_none_or(|max_pages| metadata.page_count <= max_pages)
+                    && filter.has_signatures.is_none_or(|has_signatures| metadata.signatures.is_empty() != has_signatures)
+                    && filter.signatures_verified.is_none_or(|verified| {
+                        let all_verified = metadata.signatures.iter()
+                            .all(|signature| signature.status == PdfSignatureStatus::IntegrityVerified);
+                        !metadata.signatures.is_empty() && all_verified == verified
+                    })
+            })
+            .collect()
+    })
+}
 
 /// Search institutions by name (case-insensitive partial match)
 #[query]
@@ -0,0 +1,165 @@
+use ic_cdk::update;
+use std::time::Duration;
+use crate::types::{Document, DocumentResponse, UploadSession};
+use crate::utils::{generate_upload_session_id, get_current_timestamp, require_authenticated_user};
+use crate::logging::{get_logger, get_severity_for_event_type};
+use super::document::{publish_document, validate_document_metadata, MAX_FILE_SIZE_MB};
+
+// Chunks must fit in a single ingress message (~2MB including Candid overhead)
+const MAX_CHUNK_SIZE_BYTES: usize = 1_900_000;
+
+// Sessions with no activity for this long are discarded by the cleanup timer
+const UPLOAD_SESSION_TTL_SECONDS: u64 = 60 * 60;
+const UPLOAD_SESSION_CLEANUP_INTERVAL_SECONDS: u64 = 10 * 60;
+const NANOSECONDS_PER_SECOND: u64 = 1_000_000_000;
+
+// Load a session and check that it belongs to the caller
+fn get_owned_session(session_id: &str) -> Result<UploadSession, String> {
+    let caller = require_authenticated_user()?;
+
+    let session = crate::storage::get_upload_session_safe(session_id)
+        .ok_or("Upload session not found or expired")?;
+
+    if session.owner != caller {
+        return Err("Access denied. You can only access your own upload sessions.".to_string());
+    }
+
+    Ok(session)
+}
+
+/// Start a chunked upload session for a document larger than a single ingress message
+/// The metadata is validated up front and file_size must be the total size; file_data is ignored and must be sent via append_document_chunk
+#[update]
+pub fn begin_document_upload(metadata: Document) -> Result<String, String> {
+    let caller = require_authenticated_user()?;
+
+    // Reject uploads that will exceed the size limit before any chunk is sent
+    // The declared size is checked against the reassembled file on finalization
+    if metadata.file_size == 0 {
+        return Err("File size must be declared when starting an upload".to_string());
+    }
+    crate::utils::validate_file_size(metadata.file_size as usize, MAX_FILE_SIZE_MB)?;
+    validate_document_metadata(&metadata)?;
+
+    let session_id = generate_upload_session_id();
+
+    let mut session_metadata = metadata;
+    session_metadata.file_data = Vec::new();
+
+    let now = get_current_timestamp();
+    let session = UploadSession {
+        session_id: session_id.clone(),
+        owner: caller,
+        metadata: session_metadata,
+        chunk_count: 0,
+        received_bytes: 0,
+        created_at: now,
+        last_activity: now,
+    };
+
+    crate::storage::insert_upload_session_safe(&session_id, &session)?;
+
+    Ok(session_id)
+}
+
+/// Append the next chunk of file data to an upload session
+/// Returns the total number of bytes received so far
+#[update]
+pub fn append_document_chunk(session_id: String, chunk: Vec<u8>) -> Result<u64, String> {
+    let mut session = get_owned_session(&session_id)?;
+
+    if chunk.is_empty() {
+        return Err("Chunk cannot be empty".to_string());
+    }
+
+    if chunk.len() > MAX_CHUNK_SIZE_BYTES {
+        return Err(format!("Chunk size exceeds {} bytes limit", MAX_CHUNK_SIZE_BYTES));
+    }
+
+    let received_bytes = session.received_bytes + chunk.len() as u64;
+    if received_bytes > session.metadata.file_size {
+        return Err(format!("Chunk exceeds the declared file size of {} bytes", session.metadata.file_size));
+    }
+
+    crate::storage::store_upload_chunk(&session_id, session.chunk_count, chunk);
+
+    session.chunk_count += 1;
+    session.received_bytes = received_bytes;
+    session.last_activity = get_current_timestamp();
+    crate::storage::update_upload_session_safe(&session_id, &session)?;
+
+    Ok(received_bytes)
+}
+
+/// Reassemble the uploaded chunks, validate and publish the document
+/// The session is kept if publication fails, so missing chunks can still be appended or the upload cancelled
+#[update]
+pub fn finalize_document_upload(session_id: String) -> DocumentResponse {
+    let session = match get_owned_session(&session_id) {
+        Ok(session) => session,
+        Err(e) => {
+            return DocumentResponse {
+                success: false,
+                document_id: String::new(),
+                error_message: e,
+                file_hash: String::new(),
+            };
+        }
+    };
+
+    let file_data = crate::storage::assemble_upload_chunks(&session_id);
+
+    if file_data.len() as u64 != session.metadata.file_size {
+        return DocumentResponse {
+            success: false,
+            document_id: String::new(),
+            error_message: format!(
+                "Uploaded file is {} bytes but {} bytes were declared. Please upload the missing chunks or restart the upload.",
+                file_data.len(), session.metadata.file_size
+            ),
+            file_hash: String::new(),
+        };
+    }
+
+    let mut document = session.metadata;
+    document.file_data = file_data;
+
+    let response = publish_document(document);
+    if response.success {
+        crate::storage::remove_upload_session(&session_id);
+    }
+    response
+}
+
+/// Abort an upload session and discard its chunks
+#[update]
+pub fn cancel_document_upload(session_id: String) -> Result<(), String> {
+    get_owned_session(&session_id)?;
+    crate::storage::remove_upload_session(&session_id);
+    Ok(())
+}
+
+// Discard upload sessions that have been inactive for longer than the TTL
+pub fn cleanup_expired_upload_sessions() -> usize {
+    let cutoff = get_current_timestamp()
+        .saturating_sub(UPLOAD_SESSION_TTL_SECONDS * NANOSECONDS_PER_SECOND);
+    let removed = crate::storage::remove_upload_sessions_inactive_since(cutoff);
+
+    if removed > 0 {
+        let logger = get_logger("upload_session");
+        let severity = get_severity_for_event_type("UPLOAD_SESSION_EXPIRED");
+        logger.log(severity, "UPLOAD_SESSION_EXPIRED", &format!("Removed {} expired upload sessions", removed), None);
+    }
+
+    removed
+}
+
+// Function to start the periodic upload session cleanup timer
+pub fn start_upload_session_cleanup_timer() {
+    ic_cdk_timers::set_timer_interval(
+        Duration::from_secs(UPLOAD_SESSION_CLEANUP_INTERVAL_SECONDS),
+        || {
+            cleanup_expired_upload_sessions();
+        }
+    );
+}
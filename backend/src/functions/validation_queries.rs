@@ -0,0 +1,19 @@
+use ic_cdk::query;
+use crate::types::{DocumentType, EarningReleaseData, ValidationReport};
+
+// ============================================================================
+// VALIDATION QUERY FUNCTIONS
+// ============================================================================
+
+/// Dry-run validation of earning release data before publication
+/// Errors would block publication; warnings are informational
+#[query]
+pub fn validate_earning_release(data: EarningReleaseData) -> ValidationReport {
+    crate::validation::validate_earning_release_data(&data)
+}
+
+/// Dry-run validation of the structured data of any document type
+#[query]
+pub fn validate_document_data(data: DocumentType) -> ValidationReport {
+    crate::validation::validate_document_data(&data)
+}
@@ -0,0 +1,179 @@
+use ic_cdk::query;
+use crate::types::{HashVerificationRecord, HashVerificationResult, NotarizationReceipt, ReceiptRetraction};
+use crate::utils::{calculate_file_hash, format_timestamp_to_human_readable};
+use super::document::MAX_FILE_SIZE_MB;
+
+// Frontend linked from certificate QR codes unless an admin configures another one
+const DEFAULT_VERIFICATION_BASE_URL: &str = "https://jbvl7-biaaa-aaaaj-qnssa-cai.icp0.io";
+// Kept short enough for the QR code and the printed link
+const MAX_VERIFICATION_BASE_URL_LENGTH: usize = 200;
+
+// ============================================================================
+// VERIFICATION FUNCTIONS
+// ============================================================================
+
+// SHA-256 hashes are stored as 64 lowercase hex characters
+fn normalize_file_hash(file_hash: &str) -> Result<String, String> {
+    let normalized = file_hash.trim().to_lowercase();
+    if normalized.len() != 64 || !normalized.chars().all(|c| c.is_ascii_hexdigit()) {
+        return Err("File hash must be a 64 character hex-encoded SHA-256 digest".to_string());
+    }
+    Ok(normalized)
+}
+
+// Look up every document published with the given (normalized) hash
+fn lookup_file_hash(file_hash: String) -> HashVerificationResult {
+    let mut documents: Vec<HashVerificationRecord> = crate::storage::get_document_ids_by_hash(&file_hash)
+        .into_iter()
+        .filter_map(|document_id| crate::storage::get_document_safe(&document_id))
+        .map(|document| {
+            let institution_name = crate::storage::get_institution_safe(&document.institution_id)
+                .map(|institution| institution.name)
+                .unwrap_or_default();
+
+            HashVerificationRecord {
+                document_id: document.document_id,
+                document_name: document.name,
+                owner: document.owner,
+                institution_id: document.institution_id,
+                institution_name,
+                company_name: document.company_name,
+                publication_date: document.publication_date,
+                retraction: document.retraction,
+            }
+        })
+        .collect();
+
+    // Earliest publication first
+    documents.sort_by_key(|record| record.publication_date);
+
+    HashVerificationResult {
+        file_hash,
+        verified: !documents.is_empty(),
+        documents,
+    }
+}
+
+/// Verify that a file with the given SHA-256 hash was published (public, no authentication required)
+#[query]
+pub fn verify_file_hash(file_hash: String) -> Result<HashVerificationResult, String> {
+    let normalized_hash = normalize_file_hash(&file_hash)?;
+    Ok(lookup_file_hash(normalized_hash))
+}
+
+/// Verify a file by its contents; the SHA-256 hash is computed by the canister
+#[query]
+pub fn verify_file_data(file_data: Vec<u8>) -> Result<HashVerificationResult, String> {
+    if file_data.is_empty() {
+        return Err("File data cannot be empty".to_string());
+    }
+    crate::utils::validate_file_size(file_data.len(), MAX_FILE_SIZE_MB)?;
+
+    Ok(lookup_file_hash(calculate_file_hash(&file_data)))
+}
+
+/// Get a notarization receipt for archiving: document hash, publication time, canister ID,
+/// retraction state, IC certificate and Merkle witness, verifiable offline with the chain-notary-verifier crate
+#[query]
+pub fn get_notarization_receipt(document_id: String) -> Result<NotarizationReceipt, String> {
+    let document = crate::storage::get_document_safe(&document_id)
+        .ok_or("Document not found")?;
+
+    let certificate = ic_cdk::api::data_certificate()
+        .ok_or("Data certificate is only available in non-replicated query calls")?;
+
+    let witness = crate::certification::document_witness(&document_id);
+    let witness = crate::certification::encode_witness(&witness)?;
+
+    Ok(NotarizationReceipt {
+        document_id: document.document_id,
+        file_hash: document.file_hash,
+        publication_date: document.publication_date,
+        owner: document.owner,
+        canister_id: ic_cdk::api::canister_self(),
+        retraction: document.retraction.map(|retraction| ReceiptRetraction {
+            retracted_at: retraction.retracted_at,
+            reason: retraction.reason,
+            file_purged: retraction.file_purged,
+        }),
+        certificate,
+        witness,
+    })
+}
+
+/// Get the base URL of the frontend whose public verification pages are linked from certificates
+#[query]
+pub fn get_verification_base_url() -> String {
+    crate::storage::get_verification_base_url_safe()
+        .unwrap_or_else(|| DEFAULT_VERIFICATION_BASE_URL.to_string())
+}
+
+/// Check a verification base URL and strip trailing slashes; an empty URL restores the default
+pub fn normalize_verification_base_url(url: &str) -> Result<String, String> {
+    let normalized = url.trim().trim_end_matches('/');
+    if normalized.is_empty() {
+        return Ok(String::new());
+    }
+    if !normalized.starts_with("https://") && !normalized.starts_with("http://") {
+        return Err("Verification base URL must start with https:// or http://".to_string());
+    }
+    if normalized.contains(['?', '#']) || normalized.chars().any(char::is_whitespace) {
+        return Err("Verification base URL must not contain a query, fragment or whitespace".to_string());
+    }
+    crate::utils::validate_string_length(normalized, 1, MAX_VERIFICATION_BASE_URL_LENGTH, "Verification base URL")?;
+    Ok(normalized.to_string())
+}
+
+/// Get a printable PDF certificate of a document's notarization with a QR code linking to its public
+/// verification page; the same document always yields the same bytes while the base URL is unchanged
+#[query]
+pub fn get_document_certificate(document_id: String) -> Result<Vec<u8>, String> {
+    let document = crate::storage::get_document_safe(&document_id)
+        .ok_or("Document not found")?;
+    let institution_name = crate::storage::get_institution_safe(&document.institution_id)
+        .map(|institution| institution.name)
+        .unwrap_or_default();
+
+    let retraction_notice = document.retraction.as_ref().map(|retraction| {
+        format!(
+            "Retracted on {} UTC: {}",
+            format_timestamp_to_human_readable(retraction.retracted_at),
+            retraction.reason
+        )
+    });
+
+    let details = crate::pdf::CertificateDetails {
+        verification_url: format!("{}/verify/{}", get_verification_base_url(), document.document_id),
+        document_name: document.name,
+        company_name: document.company_name,
+        institution_name,
+        file_hash: document.file_hash,
+        publication_time: format!("{} UTC", format_timestamp_to_human_readable(document.publication_date)),
+        canister_id: ic_cdk::api::canister_self().to_string(),
+        document_id: document.document_id,
+        retraction_notice,
+    };
+
+    crate::pdf::render_certificate(&details)
+}
+
+/// Get a notarization receipt as JSON for the chain-notary-verify command-line tool
+/// Binary fields are hex-encoded and principals are in textual form
+#[query]
+pub fn get_notarization_receipt_json(document_id: String) -> Result<String, String> {
+    let receipt = get_notarization_receipt(document_id)?;
+
+    let json = serde_json::json!({
+        "document_id": receipt.document_id,
+        "file_hash": receipt.file_hash,
+        "publication_date": receipt.publication_date,
+        "owner": receipt.owner.to_text(),
+        "canister_id": receipt.canister_id.to_text(),
+        "retraction": receipt.retraction,
+        "certificate": hex::encode(&receipt.certificate),
+        "witness": hex::encode(&receipt.witness),
+    });
+
+    serde_json::to_string_pretty(&json)
+        .map_err(|e| format!("Failed to encode receipt: {}", e))
+}
@@ -0,0 +1,92 @@
+use ic_cdk::query;
+use crate::types::{Document, DocumentType, EarningReleaseFieldChange};
+
+// ============================================================================
+// DOCUMENT VERSION QUERY FUNCTIONS
+// ============================================================================
+
+/// Get the latest version of a document's amendment chain (metadata only)
+#[query]
+pub fn get_latest_document_version(document_id: String) -> Option<Document> {
+    crate::storage::get_document_safe(&document_id)?;
+    crate::storage::get_document_safe(&crate::storage::get_latest_document_id(&document_id))
+}
+
+/// Get every version of a document's amendment chain, oldest first (metadata only)
+#[query]
+pub fn get_document_history(document_id: String) -> Vec<Document> {
+    let Some(mut current) = crate::storage::get_document_safe(&document_id) else {
+        return Vec::new();
+    };
+
+    // Walk back to the original publication
+    while let Some(previous) = current.supersedes.as_deref().and_then(crate::storage::get_document_safe) {
+        current = previous;
+    }
+
+    // Then forward through each amendment
+    let mut history = vec![current];
+    while let Some(next) = crate::storage::get_superseding_document_id(&history[history.len() - 1].document_id)
+        .and_then(|next_id| crate::storage::get_document_safe(&next_id))
+    {
+        history.push(next);
+    }
+
+    history
+}
+
+/// Compare the earning release data of two versions of the same document
+/// Returns the changed fields, from the first document's values to the second's
+#[query]
+pub fn diff_earning_release_versions(from_document_id: String, to_document_id: String) -> Result<Vec<EarningReleaseFieldChange>, String> {
+    let history = get_document_history(from_document_id.clone());
+    if history.is_empty() {
+        return Err(format!("Document {} not found", from_document_id));
+    }
+
+    let find_version = |document_id: &str| {
+        history.iter().find(|doc| doc.document_id == document_id)
+            .ok_or(format!("Document {} is not a version of the same release", document_id))
+    };
+    let from = find_version(&from_document_id)?;
+    let to = find_version(&to_document_id)?;
+
+    let (DocumentType::EarningRelease(from_data), DocumentType::EarningRelease(to_data)) = (&from.document_data, &to.document_data) else {
+        return Err("Both documents must be earning releases".to_string());
+    };
+
+    let from_fields = flatten_fields(serde_json::to_value(from_data).map_err(|e| e.to_string())?);
+    let to_fields = flatten_fields(serde_json::to_value(to_data).map_err(|e| e.to_string())?);
+
+    let changes = to_fields.into_iter()
+        .filter_map(|(field, new_value)| {
+            let previous_value = from_fields.iter()
+                .find(|(from_field, _)| *from_field == field)
+                .map(|(_, value)| value.clone())
+                .unwrap_or_default();
+            (previous_value != new_value).then_some(EarningReleaseFieldChange { field, previous_value, new_value })
+        })
+        .collect();
+
+    Ok(changes)
+}
+
+// Flatten nested JSON objects into (dotted path, value) pairs
+fn flatten_fields(value: serde_json::Value) -> Vec<(String, String)> {
+    let mut fields = Vec::new();
+    flatten_into(String::new(), value, &mut fields);
+    fields
+}
+
+fn flatten_into(prefix: String, value: serde_json::Value, fields: &mut Vec<(String, String)>) {
+    match value {
+        serde_json::Value::Object(map) => {
+            for (key, nested) in map {
+                let path = if prefix.is_empty() { key } else { format!("{}.{}", prefix, key) };
+                flatten_into(path, nested, fields);
+            }
+        }
+        serde_json::Value::String(text) => fields.push((prefix, text)),
+        other => fields.push((prefix, other.to_string())),
+    }
+}
@@ -12,6 +12,12 @@ pub mod functions;
 pub mod utils;
 pub mod lifecycle;
 pub mod logging;
+pub mod certification;
+pub mod validation;
+pub mod xbrl;
+pub mod spreadsheet;
+pub mod pdf;
+pub mod extraction;
 
 // Re-export main types and functions
 pub use types::*;
@@ -3,9 +3,12 @@
 
 use ic_cdk::{post_upgrade, init, println};
 use crate::storage;
+use crate::certification;
 use crate::utils::helpers::get_current_timestamp;
 use crate::logging::{get_logger, get_severity_for_event_type};
 use crate::logging::memory_logger::start_memory_check_timer;
+use crate::functions::upload_session::start_upload_session_cleanup_timer;
+use crate::utils::id_generator::start_id_seed_timer;
 
 // Helper function for logging lifecycle events
 fn log_lifecycle_event(event_type: &str, message: &str, detailed_data: Option<String>) {
@@ -34,6 +37,18 @@ fn init() {
     
     log_lifecycle_event("CANISTER_INIT", &message, detailed_data);
     
+    // New canisters already use the current storage layout
+    storage::mark_schema_current();
+    
+    // Publish the (empty) certified document tree
+    certification::rebuild_certified_documents();
+    
+    // Start the upload session cleanup timer
+    start_upload_session_cleanup_timer();
+    
+    // Seed the ID generator from raw_rand
+    start_id_seed_timer();
+    
     println!("=== INITIALIZATION COMPLETE ===");
 }
 
@@ -42,6 +57,15 @@ fn init() {
 fn post_upgrade() {
     println!("=== Post-Upgrade Process ===");
     
+    // Bring stored data up to the current storage layout; slow parts continue from a timer
+    storage::run_pending_migrations();
+    storage::start_document_rewrites();
+    storage::start_pdf_signature_backfill();
+    
+    // Rebuild the certified document tree (heap state is lost on upgrade)
+    let certified = certification::rebuild_certified_documents();
+    println!("Certified data rebuilt for {} documents", certified);
+    
     let (docs, inst, users) = get_current_stats();
     let message = format!("Post-upgrade: {} documents, {} institutions, {} users", docs, inst, users);
     
@@ -50,6 +74,12 @@ fn post_upgrade() {
     //Start the memory check timer
     start_memory_check_timer();
     println!("Memory monitoring timer started with {} hours interval", 24);
+    
+    // Start the upload session cleanup timer
+    start_upload_session_cleanup_timer();
+    
+    // Seed the ID generator from raw_rand (heap state is lost on upgrade)
+    start_id_seed_timer();
     println!("=== POST-UPGRADE COMPLETE ===");
 }
 
@@ -20,6 +20,12 @@ pub struct ExternalLogger {
     webhook_url: String,
 }
 
+impl Default for ExternalLogger {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
 impl ExternalLogger {
     pub fn new() -> Self {
         Self {
@@ -1,8 +1,6 @@
 // Memory-specific logging functionality
 // Handles memory event logging, storage health, and Discord webhooks
 
-use crate::utils::helpers::get_current_timestamp;
-use crate::functions::admin_queries::require_super_admin;
 use crate::storage;
 use super::{Logger, get_logger, get_severity_for_event_type};
 use super::external::{log_memory_wipe_event, get_discord_logger};
@@ -14,6 +12,12 @@ pub struct MemoryLogger {
     logger: Logger,
 }
 
+impl Default for MemoryLogger {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
 impl MemoryLogger {
     pub fn new() -> Self {
         Self {
@@ -89,7 +93,7 @@ pub fn perform_memory_wipe_check(check_type: &str, use_discord_only: bool) -> (S
         memory_logger.log_memory_event(&format!("{}_MEMORY_WIPE_CHECK", check_type), &stats_message, None);
         
         if is_wiped {
-            let message = format!("MEMORY WIPE DETECTED: All storage is empty!");
+            let message = "MEMORY WIPE DETECTED: All storage is empty!".to_string();
             memory_logger.log_memory_event_with_webhook(&format!("{}_MEMORY_WIPE_CHECK", check_type), &message, Some(format!("Stats: {:?}", stats)));
             (message, true)
         } else {
@@ -82,6 +82,12 @@ pub fn get_severity_for_event_type(event_type: &str) -> LogSeverity {
         "SERIALIZATION_ERROR" | "DESERIALIZATION_ERROR" => LogSeverity::Critical,
         "CORRUPTED_DATA" => LogSeverity::Debug,
         "USER_REGISTRATION" => LogSeverity::Info,
+        "UPLOAD_SESSION_EXPIRED" => LogSeverity::Info,
+        "STORAGE_MIGRATION" => LogSeverity::Warning,
+        "PUBLISH_DENIED" => LogSeverity::Warning,
+        "XBRL_MISMATCH" => LogSeverity::Warning,
+        "SPREADSHEET_MISMATCH" => LogSeverity::Warning,
+        "ID_SEED_FAILURE" => LogSeverity::Warning,
         _ => LogSeverity::Info,
     }
 }
\ No newline at end of file
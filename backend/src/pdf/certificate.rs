@@ -0,0 +1,303 @@
+// Printable notarization certificate: one A4 page with the document details and a verification QR code
+// Output is deterministic (no dates, random file IDs or compression), so identical details give identical bytes
+
+use lopdf::content::{Content, Operation};
+use lopdf::{dictionary, Document as PdfDocument, Object, Stream};
+use qrcodegen::{QrCode, QrCodeEcc};
+use super::encode_win_ansi;
+
+// A4 in points
+const PAGE_WIDTH: f32 = 595.0;
+const PAGE_HEIGHT: f32 = 842.0;
+const MARGIN: f32 = 60.0;
+const VALUE_COLUMN: f32 = 180.0;
+const LINE_HEIGHT: f32 = 14.0;
+// Values wrap to fit the space right of the label column
+const VALUE_LINE_CHARS: usize = 60;      // Helvetica 10pt
+const VALUE_LINE_CHARS_MONO: usize = 65; // Courier 9pt, wide enough for a SHA-256 hex digest
+const MAX_VALUE_LINES: usize = 3;
+const QR_SIZE: f32 = 150.0;
+const QR_QUIET_ZONE: i32 = 4; // blank modules around the code, required by scanners
+// Courier glyphs are 0.6 em wide, used to center monospaced lines
+const COURIER_CHAR_WIDTH: f32 = 0.6;
+
+/// Everything printed on a certificate, already formatted for display
+pub struct CertificateDetails {
+    pub document_name: String,
+    pub company_name: String,
+    pub institution_name: String,
+    pub file_hash: String,
+    pub publication_time: String,
+    pub canister_id: String,
+    pub document_id: String,
+    pub verification_url: String,
+    pub retraction_notice: Option<String>,
+}
+
+/// Render the certificate PDF
+pub fn render_certificate(details: &CertificateDetails) -> Result<Vec<u8>, String> {
+    let mut operations = Vec::new();
+    let mut y = PAGE_HEIGHT - 90.0;
+
+    show_text(&mut operations, "F2", 22.0, MARGIN, y, "Certificate of Notarization");
+    y -= 22.0;
+    show_text(&mut operations, "F1", 11.0, MARGIN, y, "This document was published and notarized on the Internet Computer with ChainNotary.");
+    y -= 14.0;
+    operations.extend([
+        Operation::new("w", vec![Object::Real(0.5)]),
+        Operation::new("m", vec![Object::Real(MARGIN), Object::Real(y)]),
+        Operation::new("l", vec![Object::Real(PAGE_WIDTH - MARGIN), Object::Real(y)]),
+        Operation::new("S", vec![]),
+    ]);
+    y -= 30.0;
+
+    let mut fields = vec![
+        ("Document", details.document_name.as_str(), false),
+        ("Company", details.company_name.as_str(), false),
+        ("Institution", details.institution_name.as_str(), false),
+        ("Published", details.publication_time.as_str(), false),
+        ("Document ID", details.document_id.as_str(), true),
+        ("SHA-256 hash", details.file_hash.as_str(), true),
+        ("Canister ID", details.canister_id.as_str(), true),
+    ];
+    if let Some(notice) = &details.retraction_notice {
+        fields.push(("Status", notice.as_str(), false));
+    }
+
+    for (label, value, monospaced) in fields {
+        show_text(&mut operations, "F2", 10.0, MARGIN, y, label);
+        let (font, size, line_chars) = if monospaced { ("F3", 9.0, VALUE_LINE_CHARS_MONO) } else { ("F1", 10.0, VALUE_LINE_CHARS) };
+        let lines = wrap_text(value, line_chars);
+        for line in &lines {
+            show_text(&mut operations, font, size, VALUE_COLUMN, y, line);
+            y -= LINE_HEIGHT;
+        }
+        y -= 8.0;
+    }
+
+    // Verification QR code, centered below the details
+    y -= 20.0;
+    let qr_left = (PAGE_WIDTH - QR_SIZE) / 2.0;
+    draw_qr_code(&mut operations, &details.verification_url, qr_left, y)?;
+    y -= QR_SIZE + 16.0;
+    show_centered_mono(&mut operations, 10.0, y, "Scan to verify this document");
+    y -= 14.0;
+    for line in wrap_text(&details.verification_url, 90) {
+        show_centered_mono(&mut operations, 8.0, y, &line);
+        y -= 11.0;
+    }
+
+    show_text(&mut operations, "F1", 8.0, MARGIN, MARGIN + 12.0,
+        "The SHA-256 hash of the original file must match the hash above. Anyone can check it with the canister's");
+    show_text(&mut operations, "F1", 8.0, MARGIN, MARGIN,
+        "verify_file_hash query, or verify the notarization receipt offline with the chain-notary-verify tool.");
+
+    build_single_page_pdf(operations, &format!("Certificate of Notarization - {}", details.document_name))
+}
+
+// Assemble a one-page document using the standard Helvetica, Helvetica-Bold and Courier fonts
+fn build_single_page_pdf(operations: Vec<Operation>, title: &str) -> Result<Vec<u8>, String> {
+    let mut document = PdfDocument::with_version("1.7");
+    let pages_id = document.new_object_id();
+
+    let font = |base_font: &str| dictionary! {
+        "Type" => "Font",
+        "Subtype" => "Type1",
+        "BaseFont" => base_font.to_string(),
+        "Encoding" => "WinAnsiEncoding",
+    };
+    let resources_id = document.add_object(dictionary! {
+        "Font" => dictionary! {
+            "F1" => font("Helvetica"),
+            "F2" => font("Helvetica-Bold"),
+            "F3" => font("Courier"),
+        },
+    });
+
+    let content = Content { operations }.encode()
+        .map_err(|e| format!("Failed to encode certificate content: {}", e))?;
+    let content_id = document.add_object(Stream::new(dictionary! {}, content));
+    let page_id = document.add_object(dictionary! {
+        "Type" => "Page",
+        "Parent" => pages_id,
+        "MediaBox" => vec![0.into(), 0.into(), Object::Real(PAGE_WIDTH), Object::Real(PAGE_HEIGHT)],
+        "Resources" => resources_id,
+        "Contents" => content_id,
+    });
+    document.objects.insert(pages_id, Object::Dictionary(dictionary! {
+        "Type" => "Pages",
+        "Kids" => vec![page_id.into()],
+        "Count" => 1,
+    }));
+
+    let catalog_id = document.add_object(dictionary! {
+        "Type" => "Catalog",
+        "Pages" => pages_id,
+    });
+    let info_id = document.add_object(dictionary! {
+        "Title" => Object::string_literal(encode_win_ansi(title)),
+        "Producer" => Object::string_literal("ChainNotary"),
+    });
+    document.trailer.set("Root", catalog_id);
+    document.trailer.set("Info", info_id);
+
+    let mut bytes = Vec::new();
+    document.save_to(&mut bytes)
+        .map_err(|e| format!("Failed to write certificate PDF: {}", e))?;
+    Ok(bytes)
+}
+
+fn show_text(operations: &mut Vec<Operation>, font: &str, size: f32, x: f32, y: f32, text: &str) {
+    operations.extend([
+        Operation::new("BT", vec![]),
+        Operation::new("Tf", vec![font.into(), Object::Real(size)]),
+        Operation::new("Td", vec![Object::Real(x), Object::Real(y)]),
+        Operation::new("Tj", vec![Object::string_literal(encode_win_ansi(text))]),
+        Operation::new("ET", vec![]),
+    ]);
+}
+
+fn show_centered_mono(operations: &mut Vec<Operation>, size: f32, y: f32, text: &str) {
+    let width = text.chars().count() as f32 * COURIER_CHAR_WIDTH * size;
+    show_text(operations, "F3", size, (PAGE_WIDTH - width) / 2.0, y, text);
+}
+
+// Dark modules as filled squares, merged into horizontal runs; (left, top) is the outer corner of the quiet zone
+fn draw_qr_code(operations: &mut Vec<Operation>, url: &str, left: f32, top: f32) -> Result<(), String> {
+    let qr_code = QrCode::encode_text(url, QrCodeEcc::Medium)
+        .map_err(|_| "Verification URL is too long for a QR code".to_string())?;
+    let size = qr_code.size();
+    let module = QR_SIZE / (size + 2 * QR_QUIET_ZONE) as f32;
+
+    operations.push(Operation::new("q", vec![]));
+    operations.push(Operation::new("g", vec![0.into()]));
+    for row in 0..size {
+        let mut column = 0;
+        while column < size {
+            if !qr_code.get_module(column, row) {
+                column += 1;
+                continue;
+            }
+            let run_start = column;
+            while column < size && qr_code.get_module(column, row) {
+                column += 1;
+            }
+            operations.push(Operation::new("re", vec![
+                Object::Real(left + (run_start + QR_QUIET_ZONE) as f32 * module),
+                Object::Real(top - (row + QR_QUIET_ZONE + 1) as f32 * module),
+                Object::Real((column - run_start) as f32 * module),
+                Object::Real(module),
+            ]));
+        }
+    }
+    operations.push(Operation::new("f", vec![]));
+    operations.push(Operation::new("Q", vec![]));
+    Ok(())
+}
+
+// Word-wrap to lines of at most line_chars characters; words longer than a line are split
+// Text beyond MAX_VALUE_LINES is cut and marked with "..."
+fn wrap_text(text: &str, line_chars: usize) -> Vec<String> {
+    let mut lines: Vec<String> = Vec::new();
+    let mut current = String::new();
+
+    for word in text.split_whitespace() {
+        let word: Vec<char> = word.chars().collect();
+        for piece in word.chunks(line_chars) {
+            let piece: String = piece.iter().collect();
+            if !current.is_empty() && current.chars().count() + 1 + piece.chars().count() > line_chars {
+                lines.push(std::mem::take(&mut current));
+            }
+            if !current.is_empty() {
+                current.push(' ');
+            }
+            current.push_str(&piece);
+        }
+    }
+    if !current.is_empty() || lines.is_empty() {
+        lines.push(current);
+    }
+
+    if lines.len() > MAX_VALUE_LINES {
+        lines.truncate(MAX_VALUE_LINES);
+        let last = &mut lines[MAX_VALUE_LINES - 1];
+        let kept: String = last.chars().take(line_chars.saturating_sub(3)).collect();
+        *last = format!("{}...", kept);
+    }
+    lines
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn details() -> CertificateDetails {
+        CertificateDetails {
+            document_name: "Q3 2025 Earnings Release".to_string(),
+            company_name: "Example Holdings".to_string(),
+            institution_name: "Example Exchange".to_string(),
+            file_hash: "9f86d081884c7d659a2feaa0c55ad015a3bf4f1b2b0b822cd15d6c15b0f00a08".to_string(),
+            publication_time: "Sunday, 21 September 2025 3:08 AM".to_string(),
+            canister_id: "uxrrr-q7777-77774-qaaaq-cai".to_string(),
+            document_id: "DOC-0000000001".to_string(),
+            verification_url: "https://verify.example.com/document/DOC-0000000001".to_string(),
+            retraction_notice: None,
+        }
+    }
+
+    fn page_text(data: &[u8]) -> String {
+        let document = PdfDocument::load_mem(data).unwrap();
+        document.extract_text(&[1]).unwrap()
+    }
+
+    #[test]
+    fn same_details_give_identical_bytes() {
+        assert_eq!(render_certificate(&details()).unwrap(), render_certificate(&details()).unwrap());
+
+        let mut other = details();
+        other.document_id = "DOC-0000000002".to_string();
+        assert_ne!(render_certificate(&details()).unwrap(), render_certificate(&other).unwrap());
+    }
+
+    #[test]
+    fn certificate_is_a_readable_single_page_pdf() {
+        let bytes = render_certificate(&details()).unwrap();
+        let document = PdfDocument::load_mem(&bytes).unwrap();
+        assert_eq!(document.get_pages().len(), 1);
+
+        let text = page_text(&bytes);
+        let details = details();
+        for expected in ["Certificate of Notarization", &details.document_name, &details.company_name, &details.file_hash,
+            &details.publication_time, &details.canister_id, &details.document_id, &details.verification_url] {
+            assert!(text.contains(expected), "{} missing from {}", expected, text);
+        }
+        assert!(!text.contains("Status"));
+    }
+
+    #[test]
+    fn retraction_notice_is_printed() {
+        let mut details = details();
+        details.retraction_notice = Some("Retracted on 1 October 2025".to_string());
+        let text = page_text(&render_certificate(&details).unwrap());
+        assert!(text.contains("Status"));
+        assert!(text.contains("Retracted on 1 October 2025"));
+    }
+
+    #[test]
+    fn verification_urls_too_long_for_a_qr_code_are_rejected() {
+        let mut details = details();
+        details.verification_url = format!("https://verify.example.com/{}", "a".repeat(3000));
+        assert!(render_certificate(&details).is_err());
+    }
+
+    #[test]
+    fn long_values_are_wrapped_and_cut() {
+        assert_eq!(wrap_text("one two three", 7), vec!["one two", "three"]);
+        assert_eq!(wrap_text("abcdefghij", 4), vec!["abcd", "efgh", "ij"]);
+        assert_eq!(wrap_text("", 10), vec![""]);
+
+        let lines = wrap_text(&"word ".repeat(20), 10);
+        assert_eq!(lines.len(), MAX_VALUE_LINES);
+        assert_eq!(lines[MAX_VALUE_LINES - 1], "word wo...");
+    }
+}
@@ -0,0 +1,180 @@
+// Page count, version, Info dictionary, encryption, embedded files and signatures of a PDF
+
+use lopdf::{Document as PdfDocument, Object};
+use super::{decode_text_string, load_pdf, read_pdf_signatures, resolve_dictionary, PdfEncryption};
+use crate::types::PdfMetadata;
+
+// Name trees are shallow in practice; deeper nesting is treated as corrupt
+const MAX_NAME_TREE_DEPTH: usize = 32;
+
+const NANOS_PER_SECOND: i64 = 1_000_000_000;
+
+/// Read the structural metadata of a PDF; corrupt files and files without pages are rejected
+/// The document ID is left empty for the caller to assign
+pub fn read_pdf_metadata(data: &[u8]) -> Result<PdfMetadata, String> {
+    let (document, encryption) = load_pdf(data)?;
+
+    let page_count = document.get_pages().len() as u32;
+    if page_count == 0 {
+        return Err("PDF document contains no pages".to_string());
+    }
+
+    // Strings of files that could not be decrypted are unreadable
+    let readable = encryption != PdfEncryption::PasswordProtected;
+    let info = document.trailer.get(b"Info").ok().and_then(|info| resolve_dictionary(&document, info));
+    let info_text = |key: &[u8]| {
+        info.filter(|_| readable)
+            .and_then(|info| info.get(key).ok())
+            .and_then(|value| document.dereference(value).ok())
+            .and_then(|(_, value)| value.as_str().ok())
+            .map(|bytes| decode_text_string(bytes).trim().to_string())
+            .filter(|text| !text.is_empty())
+    };
+
+    // Decryption also rewrites the signature contents, so signatures are read from the file as stored
+    let signatures = match encryption {
+        PdfEncryption::None => read_pdf_signatures(&document, data),
+        _ => PdfDocument::load_mem(data).map(|stored| read_pdf_signatures(&stored, data)).unwrap_or_default(),
+    };
+
+    Ok(PdfMetadata {
+        document_id: String::new(),
+        pdf_version: document.version.clone(),
+        page_count,
+        title: info_text(b"Title"),
+        author: info_text(b"Author"),
+        producer: info_text(b"Producer"),
+        creation_date: info_text(b"CreationDate").and_then(|date| parse_pdf_date(&date)),
+        modification_date: info_text(b"ModDate").and_then(|date| parse_pdf_date(&date)),
+        encrypted: encryption != PdfEncryption::None,
+        embedded_files: if readable { embedded_file_names(&document) } else { Vec::new() },
+        signatures,
+    })
+}
+
+// File names from the EmbeddedFiles name tree of the document catalog
+fn embedded_file_names(document: &PdfDocument) -> Vec<String> {
+    let mut names = Vec::new();
+    let tree = document.catalog().ok()
+        .and_then(|catalog| catalog.get(b"Names").ok())
+        .and_then(|names| resolve_dictionary(document, names))
+        .and_then(|names| names.get(b"EmbeddedFiles").ok());
+    if let Some(tree) = tree {
+        collect_name_tree(document, tree, 0, &mut names);
+    }
+    names
+}
+
+// Leaves hold [key, file specification] pairs; intermediate nodes list their Kids
+fn collect_name_tree(document: &PdfDocument, node: &Object, depth: usize, names: &mut Vec<String>) {
+    let Some(node) = resolve_dictionary(document, node).filter(|_| depth < MAX_NAME_TREE_DEPTH) else {
+        return;
+    };
+
+    if let Ok(entries) = node.get(b"Names").and_then(Object::as_array) {
+        for [key, file_spec] in entries.as_chunks::<2>().0 {
+            let key = key.as_str().map(decode_text_string).unwrap_or_default();
+            // The file specification's Unicode name (UF) or file name (F), else the tree key
+            let file_name = resolve_dictionary(document, file_spec)
+                .and_then(|spec| spec.get(b"UF").or_else(|_| spec.get(b"F")).ok())
+                .and_then(|name| name.as_str().ok())
+                .map(decode_text_string)
+                .filter(|name| !name.is_empty())
+                .unwrap_or(key);
+            names.push(file_name);
+        }
+    }
+
+    if let Ok(kids) = node.get(b"Kids").and_then(Object::as_array) {
+        for kid in kids {
+            collect_name_tree(document, kid, depth + 1, names);
+        }
+    }
+}
+
+/// Parse a PDF date ("D:YYYYMMDDHHmmSSOHH'mm'") into nanoseconds since epoch (UTC)
+/// Only the year is required; missing parts default to the start of the period
+pub fn parse_pdf_date(text: &str) -> Option<u64> {
+    let text = text.trim();
+    let text = text.strip_prefix("D:").unwrap_or(text);
+    let digits_end = text.find(|c: char| !c.is_ascii_digit()).unwrap_or(text.len());
+    let (digits, zone) = text.split_at(digits_end);
+    if digits.len() < 4 {
+        return None;
+    }
+
+    let part = |start: usize, default: u32| digits.get(start..start + 2).map_or(Some(default), |part| part.parse().ok());
+    let year: i64 = digits[..4].parse().ok()?;
+    let (month, day) = (part(4, 1)?, part(6, 1)?);
+    let (hour, minute, second) = (part(8, 0)?, part(10, 0)?, part(12, 0)?);
+    if !(1..=12).contains(&month) || !(1..=31).contains(&day) || hour > 23 || minute > 59 || second > 59 {
+        return None;
+    }
+
+    // Local time = UTC + offset, so the offset is subtracted
+    let offset_seconds = match zone.chars().next() {
+        Some(sign @ ('+' | '-')) => {
+            let zone_digits: String = zone[1..].chars().filter(char::is_ascii_digit).collect();
+            let hours: i64 = zone_digits.get(..2)?.parse().ok()?;
+            let minutes: i64 = zone_digits.get(2..4).map_or(Some(0), |minutes| minutes.parse().ok())?;
+            let offset = hours * 3600 + minutes * 60;
+            if sign == '+' { offset } else { -offset }
+        }
+        _ => 0, // "Z" or unspecified
+    };
+
+    let days = crate::utils::days_from_civil(year, month, day);
+    let seconds = days * 86_400 + (hour as i64) * 3600 + (minute as i64) * 60 + second as i64 - offset_seconds;
+    u64::try_from(seconds.checked_mul(NANOS_PER_SECOND)?).ok()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    // 2025-10-09T12:00:00Z
+    const NOON_UTC: u64 = 1_760_011_200 * NANOS_PER_SECOND as u64;
+    const HOUR: u64 = 3600 * NANOS_PER_SECOND as u64;
+
+    #[test]
+    fn pdf_dates_are_converted_to_utc() {
+        assert_eq!(parse_pdf_date("D:20251009120000Z"), Some(NOON_UTC));
+        assert_eq!(parse_pdf_date("D:20251009120000Z00'00'"), Some(NOON_UTC));
+        assert_eq!(parse_pdf_date("D:20251009120000"), Some(NOON_UTC));
+        assert_eq!(parse_pdf_date(" 20251009120000 "), Some(NOON_UTC));
+    }
+
+    #[test]
+    fn time_zone_offsets_are_subtracted() {
+        assert_eq!(parse_pdf_date("D:20251009140000+02'00'"), Some(NOON_UTC));
+        assert_eq!(parse_pdf_date("D:20251009070000-05'00'"), Some(NOON_UTC));
+        assert_eq!(parse_pdf_date("D:20251009173000+05'30'"), Some(NOON_UTC));
+        assert_eq!(parse_pdf_date("D:20251009083000-03'30"), Some(NOON_UTC));
+        // Minutes of the offset are optional
+        assert_eq!(parse_pdf_date("D:20251009130000+01"), Some(NOON_UTC));
+        // The offset can move the date across midnight
+        assert_eq!(parse_pdf_date("D:20251010010000+13'00'"), Some(NOON_UTC));
+        assert_eq!(parse_pdf_date("D:20251009000000-01'00'"), Some(NOON_UTC - 11 * HOUR));
+    }
+
+    #[test]
+    fn missing_parts_default_to_the_start_of_the_period() {
+        assert_eq!(parse_pdf_date("D:2025"), Some(1_735_689_600 * NANOS_PER_SECOND as u64));
+        assert_eq!(parse_pdf_date("D:202510091200"), Some(NOON_UTC));
+        assert_eq!(parse_pdf_date("D:2025100912+02'00'"), Some(NOON_UTC - 2 * HOUR));
+    }
+
+    #[test]
+    fn invalid_dates_are_rejected() {
+        assert_eq!(parse_pdf_date(""), None);
+        assert_eq!(parse_pdf_date("D:"), None);
+        assert_eq!(parse_pdf_date("D:202"), None);
+        assert_eq!(parse_pdf_date("D:20251309120000Z"), None);
+        assert_eq!(parse_pdf_date("D:20251032120000Z"), None);
+        assert_eq!(parse_pdf_date("D:20251009246000Z"), None);
+        assert_eq!(parse_pdf_date("D:20251009120000+"), None);
+        assert_eq!(parse_pdf_date("October 9, 2025"), None);
+        // Before the epoch
+        assert_eq!(parse_pdf_date("D:19691231"), None);
+    }
+}
@@ -0,0 +1,76 @@
+// PDF documents: structure, signatures and text read at upload, certificates and stamped copies generated on request
+// Parsing goes through lopdf; encrypted files are opened with the empty user password when possible
+
+pub mod certificate;
+pub mod metadata;
+pub mod signatures;
+pub mod stamp;
+pub mod text;
+
+pub use certificate::{render_certificate, CertificateDetails};
+pub use metadata::read_pdf_metadata;
+pub use signatures::read_pdf_signatures;
+pub use stamp::stamp_pdf;
+pub use text::extract_pdf_text;
+
+use lopdf::{Dictionary, Document as PdfDocument, Object};
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum PdfEncryption {
+    None,
+    Decrypted,         // owner password only; contents are readable
+    PasswordProtected, // strings and streams stay encrypted
+}
+
+/// Parse a PDF, decrypting it when it only has an owner password
+pub fn load_pdf(data: &[u8]) -> Result<(PdfDocument, PdfEncryption), String> {
+    let mut document = PdfDocument::load_mem(data)
+        .map_err(|e| format!("File is not a valid PDF: {}", e))?;
+
+    if !document.is_encrypted() {
+        return Ok((document, PdfEncryption::None));
+    }
+    let encryption = match document.decrypt("") {
+        Ok(()) => PdfEncryption::Decrypted,
+        Err(_) => PdfEncryption::PasswordProtected,
+    };
+
+    Ok((document, encryption))
+}
+
+/// Follow a reference to the dictionary it points to (direct dictionaries are returned as-is)
+pub fn resolve_dictionary<'a>(document: &'a PdfDocument, object: &'a Object) -> Option<&'a Dictionary> {
+    document.dereference(object).ok().and_then(|(_, object)| object.as_dict().ok())
+}
+
+/// Decode a PDF text string: UTF-16BE or UTF-8 with a byte order mark, otherwise PDFDocEncoding (read as Latin-1)
+pub fn decode_text_string(bytes: &[u8]) -> String {
+    if let Some(utf16) = bytes.strip_prefix(b"\xFE\xFF") {
+        let units: Vec<u16> = utf16.as_chunks::<2>().0.iter().map(|pair| u16::from_be_bytes(*pair)).collect();
+        return String::from_utf16_lossy(&units);
+    }
+    if let Some(utf8) = bytes.strip_prefix(b"\xEF\xBB\xBF") {
+        return String::from_utf8_lossy(utf8).into_owned();
+    }
+    bytes.iter().map(|&byte| byte as char).collect()
+}
+
+/// Encode text for the standard fonts with WinAnsiEncoding (Windows-1252); other characters become '?'
+pub fn encode_win_ansi(text: &str) -> Vec<u8> {
+    text.chars()
+        .map(|c| match c {
+            ' '..='~' | '\u{A0}'..='\u{FF}' => c as u8,
+            '€' => 0x80,
+            '…' => 0x85,
+            '‘' => 0x91,
+            '’' => 0x92,
+            '“' => 0x93,
+            '”' => 0x94,
+            '•' => 0x95,
+            '–' => 0x96,
+            '—' => 0x97,
+            '™' => 0x99,
+            _ => b'?',
+        })
+        .collect()
+}
@@ -0,0 +1,584 @@
+// Embedded digital signatures: ByteRange digest and CMS SignedData checks
+// The signer certificate is taken from the signature itself and only checked against the signing time;
+// trust in the signer (chain, revocation) is not established here
+
+use std::collections::BTreeSet;
+use cms::cert::CertificateChoices;
+use cms::content_info::ContentInfo;
+use cms::signed_data::{SignedAttributes, SignedData, SignerIdentifier};
+use der::asn1::{ObjectIdentifier, OctetString};
+use der::asn1::AnyRef;
+use der::{Decode, Encode, Reader, SliceReader};
+use lopdf::{Dictionary, Document as PdfDocument, Object, ObjectId};
+use rsa::pkcs8::DecodePublicKey;
+use rsa::{Pkcs1v15Sign, RsaPublicKey};
+use sha2::Digest;
+use x509_cert::time::Time;
+use x509_cert::Certificate;
+use super::{decode_text_string, resolve_dictionary};
+use super::metadata::parse_pdf_date;
+use crate::types::{PdfSignature, PdfSignatureStatus};
+
+// Form fields are shallow in practice; deeper nesting is treated as corrupt
+const MAX_FIELD_DEPTH: usize = 32;
+
+const OID_SIGNED_DATA: ObjectIdentifier = ObjectIdentifier::new_unwrap("1.2.840.113549.1.7.2");
+const OID_MESSAGE_DIGEST: ObjectIdentifier = ObjectIdentifier::new_unwrap("1.2.840.113549.1.9.4");
+const OID_SIGNING_TIME: ObjectIdentifier = ObjectIdentifier::new_unwrap("1.2.840.113549.1.9.5");
+const OID_SUBJECT_KEY_IDENTIFIER: ObjectIdentifier = ObjectIdentifier::new_unwrap("2.5.29.14");
+const OID_EC_PUBLIC_KEY: ObjectIdentifier = ObjectIdentifier::new_unwrap("1.2.840.10045.2.1");
+const OID_P256: ObjectIdentifier = ObjectIdentifier::new_unwrap("1.2.840.10045.3.1.7");
+const OID_P384: ObjectIdentifier = ObjectIdentifier::new_unwrap("1.3.132.0.34");
+
+// Tag of SignerInfo.signedAttrs ([0] IMPLICIT, constructed) and of the SET OF they are signed as
+const SIGNED_ATTRIBUTES_TAG: u8 = 0xa0;
+const SET_OF_TAG: u8 = 0x31;
+
+// rsaEncryption and sha1/sha256/sha384/sha512WithRSAEncryption (PKCS #1 v1.5)
+const RSA_SIGNATURE_ALGORITHMS: &[&str] = &[
+    "1.2.840.113549.1.1.1", "1.2.840.113549.1.1.5", "1.2.840.113549.1.1.11", "1.2.840.113549.1.1.12", "1.2.840.113549.1.1.13",
+];
+// ecdsa-with-SHA1, -SHA256, -SHA384 and -SHA512
+const ECDSA_SIGNATURE_ALGORITHMS: &[&str] = &[
+    "1.2.840.10045.4.1", "1.2.840.10045.4.3.2", "1.2.840.10045.4.3.3", "1.2.840.10045.4.3.4",
+];
+
+#[derive(Clone, Copy)]
+enum DigestAlgorithm {
+    Sha1,
+    Sha256,
+    Sha384,
+    Sha512,
+}
+
+impl DigestAlgorithm {
+    fn from_oid(oid: &ObjectIdentifier) -> Option<Self> {
+        match oid.to_string().as_str() {
+            "1.3.14.3.2.26" => Some(Self::Sha1),
+            "2.16.840.1.101.3.4.2.1" => Some(Self::Sha256),
+            "2.16.840.1.101.3.4.2.2" => Some(Self::Sha384),
+            "2.16.840.1.101.3.4.2.3" => Some(Self::Sha512),
+            _ => None,
+        }
+    }
+
+    fn name(self) -> &'static str {
+        match self {
+            Self::Sha1 => "SHA-1",
+            Self::Sha256 => "SHA-256",
+            Self::Sha384 => "SHA-384",
+            Self::Sha512 => "SHA-512",
+        }
+    }
+
+    fn digest(self, parts: &[&[u8]]) -> Vec<u8> {
+        fn digest_parts<D: Digest>(parts: &[&[u8]]) -> Vec<u8> {
+            let mut hasher = D::new();
+            for part in parts {
+                hasher.update(part);
+            }
+            hasher.finalize().to_vec()
+        }
+        match self {
+            Self::Sha1 => digest_parts::<sha1::Sha1>(parts),
+            Self::Sha256 => digest_parts::<sha2::Sha256>(parts),
+            Self::Sha384 => digest_parts::<sha2::Sha384>(parts),
+            Self::Sha512 => digest_parts::<sha2::Sha512>(parts),
+        }
+    }
+
+    fn pkcs1v15_scheme(self) -> Pkcs1v15Sign {
+        match self {
+            Self::Sha1 => Pkcs1v15Sign::new::<sha1::Sha1>(),
+            Self::Sha256 => Pkcs1v15Sign::new::<sha2::Sha256>(),
+            Self::Sha384 => Pkcs1v15Sign::new::<sha2::Sha384>(),
+            Self::Sha512 => Pkcs1v15Sign::new::<sha2::Sha512>(),
+        }
+    }
+}
+
+// Why a signature is not valid
+type Rejection = (PdfSignatureStatus, String);
+
+fn malformed(detail: impl Into<String>) -> Rejection {
+    (PdfSignatureStatus::Malformed, detail.into())
+}
+
+fn unsupported(detail: impl Into<String>) -> Rejection {
+    (PdfSignatureStatus::Unsupported, detail.into())
+}
+
+/// Find and check the signatures of a PDF, in signing order
+/// The document must be loaded without decrypting it: signature contents are never encrypted
+pub fn read_pdf_signatures(document: &PdfDocument, data: &[u8]) -> Vec<PdfSignature> {
+    // Field names are encrypted strings in encrypted files and are left out there
+    let mut found = if document.is_encrypted() { Vec::new() } else { signature_fields(document) };
+
+    // Signature dictionaries not reachable from the form (e.g. damaged AcroForm) are still checked
+    for (id, object) in &document.objects {
+        if let Ok(dictionary) = object.as_dict() {
+            if is_signature_dictionary(dictionary) && !found.iter().any(|(found_id, _, _)| *found_id == Some(*id)) {
+                found.push((Some(*id), None, dictionary));
+            }
+        }
+    }
+
+    let mut signatures: Vec<(i64, PdfSignature)> = found.into_iter()
+        .map(|(_, field_name, dictionary)| {
+            let signed_end = byte_range(dictionary).map_or(0, |[_, _, start, length]| start + length);
+            (signed_end, check_signature(data, dictionary, field_name))
+        })
+        .collect();
+    signatures.sort_by_key(|(signed_end, _)| *signed_end);
+    signatures.into_iter().map(|(_, signature)| signature).collect()
+}
+
+fn check_signature(data: &[u8], dictionary: &Dictionary, field_name: Option<String>) -> PdfSignature {
+    let mut signature = PdfSignature {
+        field_name,
+        sub_filter: dictionary.get(b"SubFilter").and_then(Object::as_name_str).ok().map(str::to_string),
+        signer_subject: None,
+        signer_issuer: None,
+        signing_time: dictionary.get(b"M").and_then(Object::as_str).ok()
+            .and_then(|date| parse_pdf_date(&decode_text_string(date))),
+        digest_algorithm: None,
+        covers_whole_document: false,
+        status: PdfSignatureStatus::IntegrityVerified,
+        status_detail: String::new(),
+    };
+
+    if let Err((status, detail)) = verify_signature(data, dictionary, &mut signature) {
+        signature.status = status;
+        signature.status_detail = detail;
+    }
+    signature
+}
+
+// Fills in the signer details while checking, so they are recorded even when a later step fails
+fn verify_signature(data: &[u8], dictionary: &Dictionary, signature: &mut PdfSignature) -> Result<(), Rejection> {
+    let range = byte_range(dictionary).ok_or_else(|| malformed("ByteRange must list two byte ranges"))?;
+    let [start1, length1, start2, length2] = range.map(|value| usize::try_from(value).unwrap_or(usize::MAX));
+    let first_end = start1.checked_add(length1).filter(|end| *end <= data.len());
+    let second_end = start2.checked_add(length2).filter(|end| *end <= data.len());
+    let (Some(first_end), Some(second_end)) = (first_end, second_end) else {
+        return Err(malformed("ByteRange lies outside the file"));
+    };
+    // Only the signature's own Contents hex string may be excluded from the signed bytes
+    let excluded = data.get(first_end..start2).unwrap_or_default();
+    let is_contents_string = excluded.len() >= 2
+        && excluded.first() == Some(&b'<')
+        && excluded.last() == Some(&b'>')
+        && excluded[1..excluded.len() - 1].iter().all(|byte| byte.is_ascii_hexdigit() || byte.is_ascii_whitespace());
+    if !is_contents_string {
+        return Err(malformed("ByteRange excludes more than the signature contents"));
+    }
+    let (first, second) = (&data[start1..first_end], &data[start2..second_end]);
+    signature.covers_whole_document = start1 == 0 && second_end == data.len();
+
+    if signature.sub_filter.as_deref() == Some("ETSI.RFC3161") {
+        return Err(unsupported("Document timestamps are not supported"));
+    }
+
+    let contents = dictionary.get(b"Contents").and_then(Object::as_str)
+        .map_err(|_| malformed("Signature has no Contents"))?;
+    // Contents is zero-padded after the DER structure
+    let mut reader = SliceReader::new(contents).map_err(|e| malformed(format!("Invalid CMS structure: {}", e)))?;
+    let content_info = ContentInfo::decode(&mut reader).map_err(|e| malformed(format!("Invalid CMS structure: {}", e)))?;
+    if content_info.content_type != OID_SIGNED_DATA {
+        return Err(malformed("Signature contents are not CMS SignedData"));
+    }
+    let signed_data: SignedData = content_info.content.decode_as()
+        .map_err(|e| malformed(format!("Invalid CMS SignedData: {}", e)))?;
+    let signer_info = signed_data.signer_infos.0.iter().next()
+        .ok_or_else(|| malformed("CMS SignedData has no signer"))?;
+
+    let certificate = signer_certificate(&signed_data, &signer_info.sid);
+    if let Some(certificate) = certificate {
+        signature.signer_subject = Some(certificate.tbs_certificate.subject.to_string());
+        signature.signer_issuer = Some(certificate.tbs_certificate.issuer.to_string());
+    }
+    if let Some(signing_time) = signer_info.signed_attrs.as_ref().and_then(signing_time) {
+        signature.signing_time = Some(signing_time);
+    }
+
+    let digest_algorithm = DigestAlgorithm::from_oid(&signer_info.digest_alg.oid)
+        .ok_or_else(|| unsupported(format!("Digest algorithm {} is not supported", signer_info.digest_alg.oid)))?;
+    signature.digest_algorithm = Some(digest_algorithm.name().to_string());
+
+    // Detached signatures (adbe.pkcs7.detached, ETSI.CAdES.detached) sign the byte ranges directly;
+    // adbe.pkcs7.sha1 signs an encapsulated SHA-1 digest of them
+    let content_digest = match &signed_data.encap_content_info.econtent {
+        None => digest_algorithm.digest(&[first, second]),
+        Some(econtent) => {
+            let encapsulated = econtent.decode_as::<OctetString>()
+                .map_err(|_| malformed("Encapsulated content is not an octet string"))?;
+            if encapsulated.as_bytes() != DigestAlgorithm::Sha1.digest(&[first, second]).as_slice() {
+                return Err((PdfSignatureStatus::DigestMismatch, "Signed bytes were modified after signing".to_string()));
+            }
+            digest_algorithm.digest(&[encapsulated.as_bytes()])
+        }
+    };
+
+    // With signed attributes the signature covers their encoding, which carries the content digest
+    let signed_digest = match &signer_info.signed_attrs {
+        None => content_digest,
+        Some(attributes) => {
+            let message_digest = attribute_value(attributes, OID_MESSAGE_DIGEST)
+                .and_then(|value| value.decode_as::<OctetString>().ok())
+                .ok_or_else(|| malformed("Signed attributes have no message digest"))?;
+            if message_digest.as_bytes() != content_digest.as_slice() {
+                return Err((PdfSignatureStatus::DigestMismatch, "Signed bytes were modified after signing".to_string()));
+            }
+            let encoded = encoded_signed_attributes(&content_info.content)
+                .map_err(|e| malformed(format!("Invalid signed attributes: {}", e)))?;
+            digest_algorithm.digest(&[&encoded])
+        }
+    };
+
+    let certificate = certificate.ok_or_else(|| malformed("Signer certificate is not included in the signature"))?;
+    verify_with_certificate(
+        certificate,
+        &signer_info.signature_algorithm.oid,
+        digest_algorithm,
+        &signed_digest,
+        signer_info.signature.as_bytes(),
+    )?;
+    check_validity_period(certificate, signature.signing_time)
+}
+
+// The signer certificate must have been valid when the document was signed; without a signing time there is nothing to compare
+fn check_validity_period(certificate: &Certificate, signing_time: Option<u64>) -> Result<(), Rejection> {
+    let Some(signing_time) = signing_time else {
+        return Ok(());
+    };
+    let validity = &certificate.tbs_certificate.validity;
+    let nanos = |time: &Time| u64::try_from(time.to_unix_duration().as_nanos()).unwrap_or(u64::MAX);
+    if signing_time < nanos(&validity.not_before) || signing_time > nanos(&validity.not_after) {
+        return Err((
+            PdfSignatureStatus::OutsideCertificateValidity,
+            format!("Signed at a time the signer certificate was not valid ({} to {})", validity.not_before, validity.not_after),
+        ));
+    }
+    Ok(())
+}
+
+fn verify_with_certificate(
+    certificate: &Certificate,
+    signature_algorithm: &ObjectIdentifier,
+    digest_algorithm: DigestAlgorithm,
+    digest: &[u8],
+    signature: &[u8],
+) -> Result<(), Rejection> {
+    let public_key_info = &certificate.tbs_certificate.subject_public_key_info;
+    let algorithm = signature_algorithm.to_string();
+
+    if RSA_SIGNATURE_ALGORITHMS.contains(&algorithm.as_str()) {
+        let encoded_key = public_key_info.to_der().map_err(|e| malformed(format!("Invalid signer public key: {}", e)))?;
+        let public_key = RsaPublicKey::from_public_key_der(&encoded_key)
+            .map_err(|e| malformed(format!("Invalid RSA public key: {}", e)))?;
+        return public_key.verify(digest_algorithm.pkcs1v15_scheme(), digest, signature).map_err(invalid_signature);
+    }
+
+    if ECDSA_SIGNATURE_ALGORITHMS.contains(&algorithm.as_str()) {
+        if public_key_info.algorithm.oid != OID_EC_PUBLIC_KEY {
+            return Err(malformed("Signer certificate does not hold an elliptic curve key"));
+        }
+        let curve = public_key_info.algorithm.parameters.as_ref()
+            .and_then(|parameters| parameters.decode_as::<ObjectIdentifier>().ok());
+        let point = public_key_info.subject_public_key.raw_bytes();
+        return match curve {
+            Some(curve) if curve == OID_P256 => {
+                use p256::ecdsa::signature::hazmat::PrehashVerifier;
+                let key = p256::ecdsa::VerifyingKey::from_sec1_bytes(point).map_err(|_| malformed("Invalid P-256 public key"))?;
+                let signature = p256::ecdsa::DerSignature::try_from(signature).map_err(invalid_signature)?;
+                key.verify_prehash(digest, &signature).map_err(invalid_signature)
+            }
+            Some(curve) if curve == OID_P384 => {
+                use p384::ecdsa::signature::hazmat::PrehashVerifier;
+                let key = p384::ecdsa::VerifyingKey::from_sec1_bytes(point).map_err(|_| malformed("Invalid P-384 public key"))?;
+                let signature = p384::ecdsa::DerSignature::try_from(signature).map_err(invalid_signature)?;
+                key.verify_prehash(digest, &signature).map_err(invalid_signature)
+            }
+            _ => Err(unsupported("Elliptic curve of the signer key is not supported")),
+        };
+    }
+
+    Err(unsupported(format!("Signature algorithm {} is not supported", algorithm)))
+}
+
+fn invalid_signature<E>(_: E) -> Rejection {
+    (PdfSignatureStatus::InvalidSignature, "Signature does not match the signer certificate".to_string())
+}
+
+// The signer's certificate, matched by issuer and serial number or by subject key identifier
+fn signer_certificate<'a>(signed_data: &'a SignedData, signer: &SignerIdentifier) -> Option<&'a Certificate> {
+    let mut certificates = signed_data.certificates.as_ref()?.0.iter().filter_map(|choice| match choice {
+        CertificateChoices::Certificate(certificate) => Some(certificate),
+        _ => None,
+    });
+
+    match signer {
+        SignerIdentifier::IssuerAndSerialNumber(id) => certificates.find(|certificate| {
+            certificate.tbs_certificate.issuer == id.issuer && certificate.tbs_certificate.serial_number == id.serial_number
+        }),
+        SignerIdentifier::SubjectKeyIdentifier(key_id) => certificates.find(|certificate| {
+            certificate.tbs_certificate.extensions.iter().flatten()
+                .filter(|extension| extension.extn_id == OID_SUBJECT_KEY_IDENTIFIER)
+                .any(|extension| OctetString::from_der(extension.extn_value.as_bytes()).is_ok_and(|value| value == key_id.0))
+        }),
+    }
+}
+
+// Signed attributes exactly as the signer encoded them, under the SET OF tag they are signed with
+// instead of their [0] IMPLICIT tag (RFC 5652, section 5.4); re-encoding them could change the bytes
+fn encoded_signed_attributes(signed_data: &der::Any) -> der::Result<Vec<u8>> {
+    // SignedData: version, digestAlgorithms, encapContentInfo, [0] certificates, [1] crls, signerInfos
+    let mut reader = SliceReader::new(signed_data.value())?;
+    let mut signer_infos = &[][..];
+    while !reader.is_finished() {
+        signer_infos = reader.tlv_bytes()?;
+    }
+
+    // First SignerInfo: version, sid, digestAlgorithm, [0] signedAttrs, ...
+    let signer_info = AnyRef::from_der(SliceReader::new(AnyRef::from_der(signer_infos)?.value())?.tlv_bytes()?)?;
+    let mut reader = SliceReader::new(signer_info.value())?;
+    for _ in 0..3 {
+        reader.tlv_bytes()?;
+    }
+    if reader.peek_byte() != Some(SIGNED_ATTRIBUTES_TAG) {
+        return Err(der::ErrorKind::TagUnexpected { expected: None, actual: reader.peek_tag()? }.into());
+    }
+    let mut encoded = reader.tlv_bytes()?.to_vec();
+    encoded[0] = SET_OF_TAG;
+    Ok(encoded)
+}
+
+fn attribute_value(attributes: &SignedAttributes, oid: ObjectIdentifier) -> Option<&der::Any> {
+    attributes.iter().find(|attribute| attribute.oid == oid)?.values.iter().next()
+}
+
+// CMS signingTime attribute in nanoseconds since epoch
+fn signing_time(attributes: &SignedAttributes) -> Option<u64> {
+    let time = Time::from_der(&attribute_value(attributes, OID_SIGNING_TIME)?.to_der().ok()?).ok()?;
+    u64::try_from(time.to_unix_duration().as_nanos()).ok()
+}
+
+// ByteRange [start1 length1 start2 length2]: the signed bytes around the Contents hex string
+fn byte_range(dictionary: &Dictionary) -> Option<[i64; 4]> {
+    let values = dictionary.get(b"ByteRange").and_then(Object::as_array).ok()?;
+    let values: Vec<i64> = values.iter().map(|value| value.as_i64().ok()).collect::<Option<_>>()?;
+    values.try_into().ok()
+}
+
+// Signature dictionaries (Type Sig, or untyped with the required entries); document timestamps are included
+fn is_signature_dictionary(dictionary: &Dictionary) -> bool {
+    match dictionary.get(b"Type").and_then(Object::as_name_str) {
+        Ok(type_name) => matches!(type_name, "Sig" | "DocTimeStamp"),
+        Err(_) => dictionary.has(b"ByteRange") && dictionary.has(b"Contents") && dictionary.has(b"Filter"),
+    }
+}
+
+// Signed signature fields of the interactive form with their fully qualified names
+fn signature_fields(document: &PdfDocument) -> Vec<(Option<ObjectId>, Option<String>, &Dictionary)> {
+    let mut fields = Vec::new();
+    let form_fields = document.catalog().ok()
+        .and_then(|catalog| catalog.get(b"AcroForm").ok())
+        .and_then(|form| resolve_dictionary(document, form))
+        .and_then(|form| form.get(b"Fields").and_then(Object::as_array).ok());
+
+    if let Some(form_fields) = form_fields {
+        let mut seen = BTreeSet::new();
+        for field in form_fields {
+            collect_signature_fields(document, field, None, false, 0, &mut seen, &mut fields);
+        }
+    }
+    fields
+}
+
+fn collect_signature_fields<'a>(
+    document: &'a PdfDocument,
+    field: &'a Object,
+    parent_name: Option<&str>,
+    parent_is_signature: bool,
+    depth: usize,
+    seen: &mut BTreeSet<ObjectId>,
+    fields: &mut Vec<(Option<ObjectId>, Option<String>, &'a Dictionary)>,
+) {
+    if depth >= MAX_FIELD_DEPTH {
+        return;
+    }
+    // Fields referenced twice (or cyclic Kids) are visited once
+    if let Object::Reference(id) = field {
+        if !seen.insert(*id) {
+            return;
+        }
+    }
+    let Some(node) = resolve_dictionary(document, field) else {
+        return;
+    };
+
+    let partial_name = node.get(b"T").and_then(Object::as_str).ok().map(decode_text_string);
+    let name = match (parent_name, partial_name) {
+        (Some(parent), Some(partial)) => Some(format!("{}.{}", parent, partial)),
+        (None, partial) => partial,
+        (parent, None) => parent.map(str::to_string),
+    };
+    // The field type is inherited by kids that do not set their own
+    let is_signature = match node.get(b"FT").and_then(Object::as_name_str) {
+        Ok(field_type) => field_type == "Sig",
+        Err(_) => parent_is_signature,
+    };
+
+    if is_signature {
+        if let Ok(value) = node.get(b"V") {
+            if let Some(dictionary) = resolve_dictionary(document, value) {
+                fields.push((value.as_reference().ok(), name.clone(), dictionary));
+            }
+        }
+    }
+
+    if let Ok(kids) = node.get(b"Kids").and_then(Object::as_array) {
+        for kid in kids {
+            collect_signature_fields(document, kid, name.as_deref(), is_signature, depth + 1, seen, fields);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    // Generated by tests/fixtures/generate_signed_pdfs.py
+    const SIGNED_RSA: &[u8] = include_bytes!("../../tests/fixtures/signed_rsa.pdf");
+    const SIGNED_EC: &[u8] = include_bytes!("../../tests/fixtures/signed_ec.pdf");
+
+    fn signatures(data: &[u8]) -> Vec<PdfSignature> {
+        read_pdf_signatures(&PdfDocument::load_mem(data).unwrap(), data)
+    }
+
+    fn only_signature(data: &[u8]) -> PdfSignature {
+        let mut signatures = signatures(data);
+        assert_eq!(signatures.len(), 1);
+        signatures.remove(0)
+    }
+
+    // Same-length edits keep the cross-reference offsets valid
+    fn replace(data: &[u8], from: &[u8], to: &[u8]) -> Vec<u8> {
+        assert_eq!(from.len(), to.len());
+        let position = data.windows(from.len()).position(|window| window == from).unwrap();
+        let mut edited = data.to_vec();
+        edited[position..position + to.len()].copy_from_slice(to);
+        edited
+    }
+
+    fn fixture_byte_range(data: &[u8]) -> [i64; 4] {
+        let start = data.windows(12).position(|window| window == b"/ByteRange [").unwrap() + 12;
+        let end = start + data[start..].iter().position(|byte| *byte == b']').unwrap();
+        let values: Vec<i64> = std::str::from_utf8(&data[start..end]).unwrap()
+            .split_whitespace()
+            .map(|value| value.parse().unwrap())
+            .collect();
+        values.try_into().unwrap()
+    }
+
+    fn with_byte_range(data: &[u8], range: [i64; 4]) -> Vec<u8> {
+        let format = |[start1, length1, start2, length2]: [i64; 4]| {
+            format!("/ByteRange [{} {:010} {:010} {:010}]", start1, length1, start2, length2)
+        };
+        replace(data, format(fixture_byte_range(data)).as_bytes(), format(range).as_bytes())
+    }
+
+    // Flip the last byte of the CMS structure, which belongs to the signature value
+    fn with_corrupted_signature_value(data: &[u8]) -> Vec<u8> {
+        let [_, contents_start, _, _] = fixture_byte_range(data);
+        let hex_start = contents_start as usize + 1;
+        let header = hex::decode(&data[hex_start..hex_start + 8]).unwrap();
+        assert_eq!(header[..2], [0x30, 0x82]);
+        let last_byte = hex_start + (4 + u16::from_be_bytes([header[2], header[3]]) as usize - 1) * 2;
+        let mut edited = data.to_vec();
+        edited[last_byte] = if edited[last_byte] == b'0' { b'1' } else { b'0' };
+        edited
+    }
+
+    #[test]
+    fn valid_signatures_are_verified() {
+        for data in [SIGNED_RSA, SIGNED_EC] {
+            let signature = only_signature(data);
+            assert_eq!(signature.status, PdfSignatureStatus::IntegrityVerified, "{}", signature.status_detail);
+            assert!(signature.covers_whole_document);
+            assert_eq!(signature.field_name.as_deref(), Some("Signature1"));
+            assert_eq!(signature.sub_filter.as_deref(), Some("ETSI.CAdES.detached"));
+            assert_eq!(signature.digest_algorithm.as_deref(), Some("SHA-256"));
+            assert!(signature.signer_subject.unwrap().contains("CN=Test Signer"));
+            // The CMS signing time replaces the /M entry
+            assert_ne!(signature.signing_time, parse_pdf_date("D:20251009120000Z"));
+        }
+    }
+
+    #[test]
+    fn bytes_appended_after_signing_are_not_covered() {
+        let mut data = SIGNED_RSA.to_vec();
+        data.extend_from_slice(b"% appended after signing\n");
+        let signature = only_signature(&data);
+        assert_eq!(signature.status, PdfSignatureStatus::IntegrityVerified);
+        assert!(!signature.covers_whole_document);
+    }
+
+    #[test]
+    fn tampered_content_fails_the_digest_check() {
+        for data in [SIGNED_RSA, SIGNED_EC] {
+            let tampered = replace(data, b"(Signed hello)", b"(Signed jello)");
+            assert_eq!(only_signature(&tampered).status, PdfSignatureStatus::DigestMismatch);
+        }
+    }
+
+    #[test]
+    fn corrupted_signature_value_does_not_verify() {
+        for data in [SIGNED_RSA, SIGNED_EC] {
+            let signature = only_signature(&with_corrupted_signature_value(data));
+            assert_eq!(signature.status, PdfSignatureStatus::InvalidSignature);
+            // Signer details are still reported
+            assert!(signature.signer_subject.is_some());
+        }
+    }
+
+    #[test]
+    fn byte_range_outside_the_file_is_malformed() {
+        let [start1, length1, start2, length2] = fixture_byte_range(SIGNED_RSA);
+        let signature = only_signature(&with_byte_range(SIGNED_RSA, [start1, length1, start2, length2 + 1]));
+        assert_eq!(signature.status, PdfSignatureStatus::Malformed);
+        assert_eq!(signature.status_detail, "ByteRange lies outside the file");
+    }
+
+    #[test]
+    fn byte_range_excluding_more_than_the_contents_is_malformed() {
+        let [start1, length1, start2, length2] = fixture_byte_range(SIGNED_RSA);
+        for range in [
+            [start1, length1 - 20, start2, length2],
+            [start1, length1, start2 + 1, length2 - 1],
+            // Overlapping ranges leave no Contents string between them
+            [start1, start2 + 10, start2, length2],
+        ] {
+            let signature = only_signature(&with_byte_range(SIGNED_RSA, range));
+            assert_eq!(signature.status, PdfSignatureStatus::Malformed);
+            assert_eq!(signature.status_detail, "ByteRange excludes more than the signature contents");
+        }
+    }
+
+    #[test]
+    fn incomplete_byte_range_is_malformed() {
+        let data = replace(SIGNED_RSA, b"/ByteRange [0 ", b"/ByteRange [  ");
+        let signature = only_signature(&data);
+        assert_eq!(signature.status, PdfSignatureStatus::Malformed);
+        assert_eq!(signature.status_detail, "ByteRange must list two byte ranges");
+    }
+
+    #[test]
+    fn contents_that_are_not_cms_are_malformed() {
+        let [_, contents_start, _, _] = fixture_byte_range(SIGNED_RSA);
+        let hex_start = contents_start as usize + 1;
+        let mut data = SIGNED_RSA.to_vec();
+        data[hex_start..hex_start + 4].copy_from_slice(b"0000");
+        assert_eq!(only_signature(&data).status, PdfSignatureStatus::Malformed);
+    }
+}
@@ -0,0 +1,208 @@
+// Stamped copies of published PDFs: a notarization footer drawn on every page
+// The footer is appended as an incremental update, so the original bytes and any signatures over them stay intact;
+// the stored original is never modified, and copies are identical for the same input and are cached per document
+
+use lopdf::content::{Content, Operation};
+use lopdf::{dictionary, Dictionary, Document as PdfDocument, IncrementalDocument, Object, ObjectId, Stream};
+use super::{encode_win_ansi, load_pdf, resolve_dictionary, PdfEncryption};
+
+// Resource name of the footer font, unlikely to clash with the page's own fonts
+const STAMP_FONT: &str = "ChainNotaryStamp";
+const STAMP_FONT_SIZE: f32 = 7.0;
+// Distance of the footer baseline from the bottom and left edges of the visible page
+const STAMP_BOTTOM_OFFSET: f32 = 12.0;
+const STAMP_LEFT_OFFSET: f32 = 20.0;
+// Letter-size page, used when a page has no valid MediaBox
+const DEFAULT_PAGE_BOX: [f32; 4] = [0.0, 0.0, 612.0, 792.0];
+
+/// Copy a PDF with the footer text drawn along the bottom edge of every page
+/// Existing page content is wrapped in q/Q so its graphics state cannot affect the footer
+pub fn stamp_pdf(data: &[u8], footer: &str) -> Result<Vec<u8>, String> {
+    let (original, encryption) = load_pdf(data)?;
+    // An update to an encrypted file would have to encrypt its own objects
+    match encryption {
+        PdfEncryption::None => {}
+        PdfEncryption::Decrypted => return Err("Encrypted PDFs cannot be stamped".to_string()),
+        PdfEncryption::PasswordProtected => return Err("PDF is password protected".to_string()),
+    }
+
+    let page_ids: Vec<ObjectId> = original.get_pages().into_values().collect();
+    if page_ids.is_empty() {
+        return Err("PDF document contains no pages".to_string());
+    }
+
+    let version = original.version.clone();
+    let mut update = IncrementalDocument::create_from(data.to_vec(), original);
+    update.new_document.version = version;
+    // The original trailer is carried over; entries describing its own cross-reference data are not
+    update.new_document.trailer.remove(b"XRefStm");
+    update.new_document.trailer.remove(b"DecodeParms");
+
+    let font_id = update.new_document.add_object(dictionary! {
+        "Type" => "Font",
+        "Subtype" => "Type1",
+        "BaseFont" => "Helvetica",
+        "Encoding" => "WinAnsiEncoding",
+    });
+    let save_state_id = update.new_document.add_object(Stream::new(dictionary! {}, b"q\n".to_vec()));
+
+    for page_id in page_ids {
+        let original = update.get_prev_documents();
+        let resources = page_resources_with_font(original, page_id, font_id);
+        let footer_content = footer_content(original, page_id, footer)?;
+        let mut contents = vec![Object::Reference(save_state_id)];
+        contents.extend(page_content_streams(original, page_id));
+
+        let footer_id = update.new_document.add_object(Stream::new(dictionary! {}, footer_content));
+        contents.push(Object::Reference(footer_id));
+
+        update.opt_clone_object_to_new_document(page_id)
+            .map_err(|e| format!("Failed to update page: {}", e))?;
+        let page = update.new_document.get_dictionary_mut(page_id)
+            .map_err(|e| format!("Failed to update page: {}", e))?;
+        page.set("Resources", resources);
+        page.set("Contents", contents);
+    }
+
+    let mut bytes = Vec::new();
+    update.save_to(&mut bytes)
+        .map_err(|e| format!("Failed to write stamped PDF: {}", e))?;
+    Ok(bytes)
+}
+
+// Restore the page's graphics state, then draw the footer rotated with the page so it reads along the visible bottom
+fn footer_content(document: &PdfDocument, page_id: ObjectId, footer: &str) -> Result<Vec<u8>, String> {
+    let [left, bottom, right, top] = page_box(document, page_id);
+    let rotation = inherited_attribute(document, page_id, b"Rotate")
+        .and_then(|rotate| rotate.as_i64().ok())
+        .unwrap_or(0)
+        .rem_euclid(360);
+
+    // Text matrix: unit vectors of the visible page's x and y axes, then the footer origin in user space
+    let matrix = match rotation {
+        90 => [0.0, 1.0, -1.0, 0.0, right - STAMP_BOTTOM_OFFSET, bottom + STAMP_LEFT_OFFSET],
+        180 => [-1.0, 0.0, 0.0, -1.0, right - STAMP_LEFT_OFFSET, top - STAMP_BOTTOM_OFFSET],
+        270 => [0.0, -1.0, 1.0, 0.0, left + STAMP_BOTTOM_OFFSET, top - STAMP_LEFT_OFFSET],
+        _ => [1.0, 0.0, 0.0, 1.0, left + STAMP_LEFT_OFFSET, bottom + STAMP_BOTTOM_OFFSET],
+    };
+
+    let operations = vec![
+        Operation::new("Q", vec![]),
+        Operation::new("q", vec![]),
+        Operation::new("g", vec![Object::Real(0.25)]),
+        Operation::new("BT", vec![]),
+        Operation::new("Tf", vec![STAMP_FONT.into(), Object::Real(STAMP_FONT_SIZE)]),
+        Operation::new("Tm", matrix.into_iter().map(Object::Real).collect()),
+        Operation::new("Tj", vec![Object::string_literal(encode_win_ansi(footer))]),
+        Operation::new("ET", vec![]),
+        Operation::new("Q", vec![]),
+    ];
+    Content { operations }.encode()
+        .map_err(|e| format!("Failed to encode footer: {}", e))
+}
+
+// Copy of the page's effective resources (own or inherited) with the footer font added
+// Setting a copy on each page leaves resources shared with other pages untouched
+fn page_resources_with_font(document: &PdfDocument, page_id: ObjectId, font_id: ObjectId) -> Dictionary {
+    let mut resources = inherited_attribute(document, page_id, b"Resources")
+        .and_then(|resources| resolve_dictionary(document, resources))
+        .cloned()
+        .unwrap_or_default();
+
+    let mut fonts = resources.get(b"Font").ok()
+        .and_then(|fonts| resolve_dictionary(document, fonts))
+        .cloned()
+        .unwrap_or_default();
+    fonts.set(STAMP_FONT, Object::Reference(font_id));
+    resources.set("Font", fonts);
+    resources
+}
+
+// Content streams of a page, whether Contents is a stream, an array or a reference to an array
+fn page_content_streams(document: &PdfDocument, page_id: ObjectId) -> Vec<Object> {
+    let Ok(contents) = document.get_dictionary(page_id).and_then(|page| page.get(b"Contents")) else {
+        return Vec::new();
+    };
+    match document.dereference(contents) {
+        Ok((_, Object::Array(streams))) => streams.clone(),
+        Ok((_, Object::Stream(_))) => vec![contents.clone()],
+        _ => Vec::new(),
+    }
+}
+
+// Visible area of the page: CropBox, else MediaBox, normalized to [left, bottom, right, top]
+fn page_box(document: &PdfDocument, page_id: ObjectId) -> [f32; 4] {
+    let read_box = |key: &[u8]| {
+        let object = inherited_attribute(document, page_id, key)?;
+        let values = document.dereference(object).ok()?.1.as_array().ok()?;
+        let numbers: Vec<f32> = values.iter().filter_map(|value| value.as_float().ok()).collect();
+        let [x1, y1, x2, y2] = numbers.as_slice() else {
+            return None;
+        };
+        Some([x1.min(*x2), y1.min(*y2), x1.max(*x2), y1.max(*y2)])
+    };
+    read_box(b"CropBox").or_else(|| read_box(b"MediaBox")).unwrap_or(DEFAULT_PAGE_BOX)
+}
+
+// Page attributes like Resources, MediaBox and Rotate may be set on an ancestor in the page tree
+fn inherited_attribute<'a>(document: &'a PdfDocument, page_id: ObjectId, key: &[u8]) -> Option<&'a Object> {
+    let mut node = document.get_dictionary(page_id).ok()?;
+    // Bounded walk in case of a cyclic Parent chain
+    for _ in 0..64 {
+        if let Ok(value) = node.get(key) {
+            return Some(value);
+        }
+        node = node.get(b"Parent").ok().and_then(|parent| resolve_dictionary(document, parent))?;
+    }
+    None
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::pdf::signatures::read_pdf_signatures;
+    use crate::types::PdfSignatureStatus;
+
+    // Generated by tests/fixtures/generate_signed_pdfs.py
+    const SIGNED_RSA: &[u8] = include_bytes!("../../tests/fixtures/signed_rsa.pdf");
+
+    const FOOTER: &str = "Notarized by ChainNotary, document 42";
+
+    fn page_text(data: &[u8]) -> String {
+        let document = PdfDocument::load_mem(data).unwrap();
+        let pages: Vec<u32> = document.get_pages().into_keys().collect();
+        document.extract_text(&pages).unwrap()
+    }
+
+    #[test]
+    fn stamp_is_appended_after_the_original_bytes() {
+        let stamped = stamp_pdf(SIGNED_RSA, FOOTER).unwrap();
+        assert!(stamped.len() > SIGNED_RSA.len());
+        assert_eq!(&stamped[..SIGNED_RSA.len()], SIGNED_RSA);
+        assert_eq!(stamp_pdf(SIGNED_RSA, FOOTER).unwrap(), stamped);
+    }
+
+    #[test]
+    fn signatures_still_verify_on_stamped_copies() {
+        let stamped = stamp_pdf(SIGNED_RSA, FOOTER).unwrap();
+        let signatures = read_pdf_signatures(&PdfDocument::load_mem(&stamped).unwrap(), &stamped);
+        assert_eq!(signatures.len(), 1);
+        assert_eq!(signatures[0].status, PdfSignatureStatus::IntegrityVerified, "{}", signatures[0].status_detail);
+        // The footer is outside the signed range
+        assert!(!signatures[0].covers_whole_document);
+    }
+
+    #[test]
+    fn stamped_pages_keep_their_content_and_show_the_footer() {
+        let stamped = stamp_pdf(SIGNED_RSA, FOOTER).unwrap();
+        let text = page_text(&stamped);
+        assert!(text.contains("Signed hello"), "{}", text);
+        assert!(text.contains(FOOTER), "{}", text);
+        assert!(!page_text(SIGNED_RSA).contains(FOOTER));
+    }
+
+    #[test]
+    fn files_that_are_not_pdfs_are_rejected() {
+        assert!(stamp_pdf(b"not a pdf", FOOTER).is_err());
+    }
+}
@@ -0,0 +1,46 @@
+// Text content of PDF pages
+
+use super::{load_pdf, PdfEncryption};
+
+/// Extract text content from PDF binary data using lopdf
+pub fn extract_pdf_text(pdf_data: &[u8]) -> Result<String, String> {
+    let (pdf_doc, encryption) = load_pdf(pdf_data)?;
+    if encryption == PdfEncryption::PasswordProtected {
+        return Err("PDF is password protected".to_string());
+    }
+
+    let mut extracted_text = String::new();
+    
+    // Get all pages in the document
+    let pages = pdf_doc.get_pages();
+    
+    if pages.is_empty() {
+        return Err("PDF document contains no pages".to_string());
+    }
+    
+    // Extract text from each page
+    for page_id in pages.keys() {
+        match pdf_doc.extract_text(&[*page_id]) {
+            Ok(page_text) => {
+                let trimmed_text = page_text.trim();
+                if !trimmed_text.is_empty() {
+                    extracted_text.push_str(trimmed_text);
+                    extracted_text.push_str("\n\n");
+                }
+            }
+            Err(e) => {
+                // Log the error but continue with other pages
+                ic_cdk::println!("Warning: Failed to extract text from page {}: {:?}", page_id, e);
+                // Continue processing other pages
+                continue;
+            }
+        }
+    }
+    
+    let cleaned_text = extracted_text.trim();
+    if cleaned_text.is_empty() {
+        Err("PDF contains no extractable text content".to_string())
+    } else {
+        Ok(cleaned_text.to_string())
+    }
+}
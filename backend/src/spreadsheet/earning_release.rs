@@ -0,0 +1,294 @@
+// Fill in and cross-check earning release data from an Excel earnings pack
+// Sheet figures are converted from the scale set in the mapping to the reporting scale of the submitted statements
+
+use calamine::DataType;
+use super::{cell_number, cell_reference, parse_cell_reference, Worksheet};
+use crate::types::earning_release_fields::{find_field, FieldTarget, EARNING_RELEASE_FIELDS};
+use crate::types::{Decimal, EarningReleaseData, ReportingScale, SpreadsheetFieldMapping};
+use crate::validation::financial_statements::{amounts_match, per_share_amounts_match};
+
+/// Value read from a workbook for an earning release field
+pub struct SpreadsheetValue {
+    pub field: String,
+    pub sheet: String,
+    pub cell: String,
+    pub value: f64,
+    pub scale: Option<ReportingScale>, // None: already in the scale of the submitted statements
+}
+
+/// Label-based mapping used by institutions without a configured one
+pub fn default_mapping() -> Vec<SpreadsheetFieldMapping> {
+    EARNING_RELEASE_FIELDS.iter()
+        .map(|field| SpreadsheetFieldMapping {
+            field: field.name.to_string(),
+            sheet: None,
+            cell: None,
+            labels: field.sheet_labels.iter().map(|label| label.to_string()).collect(),
+            scale: None,
+        })
+        .collect()
+}
+
+/// Check that every entry names a known field once and can locate a value
+pub fn validate_mapping(mapping: &[SpreadsheetFieldMapping]) -> Result<(), String> {
+    for (index, entry) in mapping.iter().enumerate() {
+        let field = find_field(&entry.field).ok_or_else(|| format!("Unknown earning release field '{}'", entry.field))?;
+        if entry.scale.is_some() && matches!(field.target, FieldTarget::PerShare(_)) {
+            return Err(format!("Per-share field '{}' cannot have a reporting scale", entry.field));
+        }
+        if mapping[..index].iter().any(|earlier| earlier.field == entry.field) {
+            return Err(format!("Field '{}' is mapped more than once", entry.field));
+        }
+        if let Some(cell) = &entry.cell {
+            parse_cell_reference(cell).ok_or_else(|| format!("Invalid cell reference '{}' for {}", cell, entry.field))?;
+        } else if entry.labels.iter().all(|label| normalize_label(label).is_empty()) {
+            return Err(format!("Field '{}' needs a cell reference or at least one row label", entry.field));
+        }
+    }
+    Ok(())
+}
+
+/// Read the mapped fields from the worksheets; fields without a numeric value are left out
+pub fn extract_earning_release(worksheets: &[Worksheet], mapping: &[SpreadsheetFieldMapping]) -> Vec<SpreadsheetValue> {
+    mapping.iter()
+        .filter_map(|entry| {
+            let candidates = worksheets.iter().filter(|worksheet| {
+                entry.sheet.as_ref().is_none_or(|sheet| sheet.trim().eq_ignore_ascii_case(worksheet.name.trim()))
+            });
+            candidates
+                .filter_map(|worksheet| locate_value(worksheet, entry).map(|(position, value)| (worksheet, position, value)))
+                .next()
+                .map(|(worksheet, position, value)| SpreadsheetValue {
+                    field: entry.field.clone(),
+                    sheet: worksheet.name.clone(),
+                    cell: cell_reference(position),
+                    value,
+                    scale: entry.scale,
+                })
+        })
+        .collect()
+}
+
+/// Fill the fields left blank (zero) with the workbook values
+/// Submitted values are kept; those that disagree with the workbook are returned as mismatches
+pub fn apply_spreadsheet_values(client: &EarningReleaseData, values: &[SpreadsheetValue]) -> (EarningReleaseData, Vec<String>) {
+    let mut data = client.clone();
+    let mut mismatches = Vec::new();
+
+    let income_scale = data.consolidated_income_data.scale;
+    let balance_scale = data.consolidated_balance_sheet_data.scale;
+    for value in values {
+        let Some(field) = find_field(&value.field) else {
+            continue;
+        };
+        let location = format!("{}!{}", value.sheet, value.cell);
+
+        match field.target {
+            FieldTarget::Income(slot) => match sheet_amount(value, income_scale) {
+                Ok(amount) => fill_decimal(slot(&mut data.consolidated_income_data), amount, value, &location, &mut mismatches),
+                Err(e) => mismatches.push(format!("{} at {}: {}", value.field, location, e)),
+            },
+            FieldTarget::Balance(slot) => match sheet_amount(value, balance_scale) {
+                Ok(amount) => fill_decimal(slot(&mut data.consolidated_balance_sheet_data), amount, value, &location, &mut mismatches),
+                Err(e) => mismatches.push(format!("{} at {}: {}", value.field, location, e)),
+            },
+            FieldTarget::CashFlow(slot) => match sheet_amount(value, income_scale) {
+                Ok(amount) => fill_amount(slot(&mut data.consolidated_cash_flow_data), amount.to_f64(), value, &location, amounts_match, &mut mismatches),
+                Err(e) => mismatches.push(format!("{} at {}: {}", value.field, location, e)),
+            },
+            FieldTarget::PerShare(slot) => fill_amount(slot(&mut data.per_share_data), value.value, value, &location, per_share_amounts_match, &mut mismatches),
+        }
+    }
+
+    (data, mismatches)
+}
+
+// Sheet value in the reporting scale of the statement it belongs to
+fn sheet_amount(value: &SpreadsheetValue, statement_scale: ReportingScale) -> Result<Decimal, String> {
+    let amount = Decimal::from_f64(value.value)?;
+    let Some(sheet_scale) = value.scale else {
+        return Ok(amount);
+    };
+    amount.checked_mul(sheet_scale.multiplier())
+        .and_then(|units| units.div_rounded(statement_scale.multiplier()))
+        .ok_or_else(|| format!("Amount {} in {} is out of range in {}", amount, sheet_scale.label(), statement_scale.label()))
+}
+
+// Excel caches unrounded values, so submitted figures only need to agree up to rounding
+fn fill_decimal(client_value: &mut Decimal, sheet_value: Decimal, value: &SpreadsheetValue, location: &str, mismatches: &mut Vec<String>) {
+    if client_value.is_zero() {
+        *client_value = sheet_value;
+    } else if !amounts_match(client_value.to_f64(), sheet_value.to_f64()) {
+        mismatches.push(format!("{} is {} but {} has {}", value.field, client_value, location, sheet_value));
+    }
+}
+
+fn fill_amount(
+    client_value: &mut f64,
+    sheet_value: f64,
+    value: &SpreadsheetValue,
+    location: &str,
+    matches: fn(f64, f64) -> bool,
+    mismatches: &mut Vec<String>,
+) {
+    if *client_value == 0.0 {
+        *client_value = sheet_value;
+    } else if !matches(*client_value, sheet_value) {
+        mismatches.push(format!("{} is {} but {} has {}", value.field, client_value, location, sheet_value));
+    }
+}
+
+// Absolute position and value of the mapped cell, or of the first number right of a matching row label
+fn locate_value(worksheet: &Worksheet, entry: &SpreadsheetFieldMapping) -> Option<((u32, u32), f64)> {
+    if let Some(cell) = &entry.cell {
+        let position = parse_cell_reference(cell)?;
+        return worksheet.cells.get_value(position).and_then(cell_number).map(|value| (position, value));
+    }
+
+    let labels: Vec<String> = entry.labels.iter().map(|label| normalize_label(label)).filter(|label| !label.is_empty()).collect();
+    let (start_row, start_column) = worksheet.cells.start()?;
+    worksheet.cells.rows().enumerate().find_map(|(row_index, row)| {
+        let label_index = row.iter().position(|cell| {
+            cell.get_string().is_some_and(|text| labels.contains(&normalize_label(text)))
+        })?;
+        row.iter().enumerate().skip(label_index + 1).find_map(|(column_index, cell)| {
+            let position = (start_row + row_index as u32, start_column + column_index as u32);
+            cell_number(cell).map(|value| (position, value))
+        })
+    })
+}
+
+// Case, surrounding punctuation and repeated whitespace do not matter when matching labels
+fn normalize_label(label: &str) -> String {
+    label
+        .split_whitespace()
+        .collect::<Vec<_>>()
+        .join(" ")
+        .trim_end_matches(':')
+        .trim()
+        .to_lowercase()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use calamine::{Cell, Data, Range};
+
+    fn worksheet(name: &str, cells: &[((u32, u32), Data)]) -> Worksheet {
+        let cells = cells.iter().map(|(position, value)| Cell::new(*position, value.clone())).collect();
+        Worksheet { name: name.to_string(), cells: Range::from_sparse(cells) }
+    }
+
+    fn text(value: &str) -> Data {
+        Data::String(value.to_string())
+    }
+
+    fn mapping(field: &str, cell: Option<&str>, labels: &[&str], scale: Option<ReportingScale>) -> SpreadsheetFieldMapping {
+        SpreadsheetFieldMapping {
+            field: field.to_string(),
+            sheet: None,
+            cell: cell.map(str::to_string),
+            labels: labels.iter().map(|label| label.to_string()).collect(),
+            scale,
+        }
+    }
+
+    fn value(field: &str, value: f64, scale: Option<ReportingScale>) -> SpreadsheetValue {
+        SpreadsheetValue { field: field.to_string(), sheet: "P&L".to_string(), cell: "C3".to_string(), value, scale }
+    }
+
+    const REVENUE: &str = "consolidated_income_data.revenue";
+
+    #[test]
+    fn labels_match_ignoring_case_whitespace_and_colons() {
+        assert_eq!(normalize_label("  Total   Revenue: "), "total revenue");
+        assert_eq!(normalize_label("NET SALES"), "net sales");
+        assert_eq!(normalize_label(" : "), "");
+    }
+
+    #[test]
+    fn default_labels_find_the_first_number_right_of_the_label() {
+        // The sheet starts at B2, so positions are offset from the range start
+        let sheets = [worksheet("P&L", &[
+            ((1, 1), text("Income statement")),
+            ((2, 1), text(" Total  Revenues:")),
+            ((2, 2), text("")),
+            ((2, 3), Data::Float(1234.5679)),
+            ((2, 4), Data::Float(1100.0)),
+            ((3, 1), text("Net income")),
+            ((3, 3), text("(45.5)")),
+        ])];
+        let values = extract_earning_release(&sheets, &default_mapping());
+        let found: Vec<(&str, &str, f64)> = values.iter().map(|value| (value.field.as_str(), value.cell.as_str(), value.value)).collect();
+        assert_eq!(found, [
+            (REVENUE, "D3", 1234.5679),
+            ("consolidated_income_data.net_profit", "D4", -45.5),
+        ]);
+    }
+
+    #[test]
+    fn mapped_cells_and_sheets_are_read_directly() {
+        let sheets = [
+            worksheet("Cover", &[((2, 3), Data::Int(7))]),
+            worksheet("P&L", &[((2, 3), Data::Int(3000))]),
+        ];
+        let mut entry = mapping(REVENUE, Some("D3"), &[], Some(ReportingScale::Thousands));
+        assert_eq!(extract_earning_release(&sheets, &[entry.clone()])[0].value, 7.0);
+
+        entry.sheet = Some("p&l ".to_string());
+        let values = extract_earning_release(&sheets, &[entry.clone()]);
+        assert_eq!((values[0].sheet.as_str(), values[0].value, values[0].scale), ("P&L", 3000.0, Some(ReportingScale::Thousands)));
+
+        entry.cell = Some("E3".to_string());
+        assert!(extract_earning_release(&sheets, &[entry]).is_empty());
+    }
+
+    #[test]
+    fn sheet_figures_are_converted_to_the_statement_scale() {
+        let in_thousands = value(REVENUE, 1234.5, Some(ReportingScale::Thousands));
+        assert_eq!(sheet_amount(&in_thousands, ReportingScale::Units), Ok(Decimal(12_345_000_000)));
+        assert_eq!(sheet_amount(&in_thousands, ReportingScale::Thousands), Ok(Decimal(12_345_000)));
+        // 1.2345 million, rounded half away from zero
+        assert_eq!(sheet_amount(&in_thousands, ReportingScale::Millions), Ok(Decimal(12_345)));
+        assert_eq!(sheet_amount(&value(REVENUE, -0.5, Some(ReportingScale::Units)), ReportingScale::Thousands), Ok(Decimal(-5)));
+        // Without a mapped scale the figure is already in the statement scale
+        assert_eq!(sheet_amount(&value(REVENUE, 1234.5, None), ReportingScale::Millions), Ok(Decimal(12_345_000)));
+        assert!(sheet_amount(&value(REVENUE, 1e12, Some(ReportingScale::Millions)), ReportingScale::Units).is_err());
+        assert!(sheet_amount(&value(REVENUE, f64::NAN, None), ReportingScale::Units).is_err());
+    }
+
+    #[test]
+    fn blank_fields_are_filled_and_rounding_differences_accepted() {
+        let mut client = EarningReleaseData::default();
+        client.consolidated_income_data.net_profit = "1234.6".parse().unwrap();
+        let values = [
+            value(REVENUE, 5000.0, None),
+            // Excel caches the unrounded value behind the displayed 1,234.6
+            value("consolidated_income_data.net_profit", 1234.5679, None),
+        ];
+        let (data, mismatches) = apply_spreadsheet_values(&client, &values);
+        assert!(mismatches.is_empty(), "{:?}", mismatches);
+        assert_eq!(data.consolidated_income_data.revenue, Decimal(50_000_000));
+        assert_eq!(data.consolidated_income_data.net_profit, Decimal(12_346_000));
+    }
+
+    #[test]
+    fn disagreeing_figures_are_reported_and_the_submitted_value_kept() {
+        let mut client = EarningReleaseData::default();
+        client.consolidated_income_data.revenue = "5000".parse().unwrap();
+        let (data, mismatches) = apply_spreadsheet_values(&client, &[value(REVENUE, 12.0, None)]);
+        assert_eq!(mismatches, ["consolidated_income_data.revenue is 5000 but P&L!C3 has 12"]);
+        assert_eq!(data.consolidated_income_data.revenue, Decimal(50_000_000));
+    }
+
+    #[test]
+    fn mappings_are_validated() {
+        assert!(validate_mapping(&default_mapping()).is_ok());
+        assert!(validate_mapping(&[mapping("consolidated_income_data.sales", Some("A1"), &[], None)]).is_err());
+        assert!(validate_mapping(&[mapping(REVENUE, Some("A0"), &[], None)]).is_err());
+        assert!(validate_mapping(&[mapping(REVENUE, None, &[" : "], None)]).is_err());
+        assert!(validate_mapping(&[mapping("per_share_data.eps_basic", Some("A1"), &[], Some(ReportingScale::Thousands))]).is_err());
+        let duplicate = mapping(REVENUE, Some("A1"), &[], None);
+        assert!(validate_mapping(&[duplicate.clone(), duplicate]).is_err());
+    }
+}
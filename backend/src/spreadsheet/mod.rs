@@ -0,0 +1,143 @@
+// Excel workbooks (xlsx, xlsm, xlsb and legacy xls): cell values of every worksheet
+// Formulas are not evaluated; the values cached by Excel when the file was saved are read
+
+pub mod earning_release;
+
+pub use earning_release::{apply_spreadsheet_values, default_mapping, extract_earning_release, validate_mapping, SpreadsheetValue};
+
+use std::io::Cursor;
+use calamine::{Data, Range, Reader};
+
+pub struct Worksheet {
+    pub name: String,
+    pub cells: Range<Data>,
+}
+
+/// Read the worksheets of a workbook in workbook order (chart sheets are skipped)
+pub fn parse_workbook(data: &[u8]) -> Result<Vec<Worksheet>, String> {
+    let mut workbook = calamine::open_workbook_auto_from_rs(Cursor::new(data))
+        .map_err(|e| format!("Failed to read Excel workbook: {}", e))?;
+
+    let mut worksheets = Vec::new();
+    for name in workbook.sheet_names() {
+        match workbook.worksheet_range(&name) {
+            Ok(cells) => worksheets.push(Worksheet { name, cells }),
+            Err(e) => ic_cdk::println!("Skipping sheet '{}': {}", name, e),
+        }
+    }
+
+    if worksheets.is_empty() {
+        return Err("Excel workbook contains no worksheets".to_string());
+    }
+    Ok(worksheets)
+}
+
+/// Parse an A1 cell reference into zero-based (row, column)
+pub fn parse_cell_reference(reference: &str) -> Option<(u32, u32)> {
+    let reference = reference.trim().to_ascii_uppercase().replace('$', "");
+    let split = reference.find(|c: char| c.is_ascii_digit())?;
+    let (letters, digits) = reference.split_at(split);
+    if letters.is_empty() || letters.len() > 3 || !letters.bytes().all(|b| b.is_ascii_uppercase()) {
+        return None;
+    }
+
+    let column = letters.bytes().fold(0u32, |column, letter| column * 26 + (letter - b'A' + 1) as u32) - 1;
+    let row = digits.parse::<u32>().ok().filter(|row| *row > 0)? - 1;
+    Some((row, column))
+}
+
+/// A1 reference of a zero-based (row, column) position
+pub fn cell_reference((row, column): (u32, u32)) -> String {
+    let mut letters = Vec::new();
+    let mut remaining = column + 1;
+    while remaining > 0 {
+        letters.push(b'A' + ((remaining - 1) % 26) as u8);
+        remaining = (remaining - 1) / 26;
+    }
+    letters.reverse();
+    format!("{}{}", String::from_utf8_lossy(&letters), row + 1)
+}
+
+/// Numeric value of a cell; text like "1,234.5" or "(1,234)" is read as a number too
+pub fn cell_number(cell: &Data) -> Option<f64> {
+    match cell {
+        Data::Int(value) => Some(*value as f64),
+        Data::Float(value) if value.is_finite() => Some(*value),
+        Data::String(text) => {
+            let trimmed = text.trim();
+            let (negative, unsigned) = match trimmed.strip_prefix('(').and_then(|inner| inner.strip_suffix(')')) {
+                Some(inner) => (true, inner),
+                None => (false, trimmed),
+            };
+            let value: f64 = unsigned.replace(',', "").trim().parse().ok().filter(|value: &f64| value.is_finite())?;
+            Some(if negative { -value } else { value })
+        }
+        _ => None,
+    }
+}
+
+/// Sheet contents as text: one line per non-empty row, cells separated by tabs
+pub fn workbook_text(worksheets: &[Worksheet]) -> String {
+    let mut text = String::new();
+    for worksheet in worksheets {
+        text.push_str(&format!("Sheet: {}\n", worksheet.name));
+        for row in worksheet.cells.rows() {
+            let values: Vec<String> = row.iter().map(|cell| cell.to_string().trim().to_string()).collect();
+            if values.iter().any(|value| !value.is_empty()) {
+                text.push_str(values.join("\t").trim_end());
+                text.push('\n');
+            }
+        }
+        text.push('\n');
+    }
+    text
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn cell_references_parse_to_zero_based_positions() {
+        assert_eq!(parse_cell_reference("A1"), Some((0, 0)));
+        assert_eq!(parse_cell_reference(" c12 "), Some((11, 2)));
+        assert_eq!(parse_cell_reference("$B$7"), Some((6, 1)));
+        assert_eq!(parse_cell_reference("Z1"), Some((0, 25)));
+        assert_eq!(parse_cell_reference("AA10"), Some((9, 26)));
+        assert_eq!(parse_cell_reference("XFD1048576"), Some((1_048_575, 16_383)));
+    }
+
+    #[test]
+    fn invalid_cell_references_are_rejected() {
+        for reference in ["", "A", "12", "A0", "1A", "A1B", "ABCD1", "A-1", "Sheet1!A1"] {
+            assert_eq!(parse_cell_reference(reference), None, "{}", reference);
+        }
+    }
+
+    #[test]
+    fn cell_references_round_trip() {
+        for reference in ["A1", "Z9", "AA10", "AZ3", "BA100", "XFD1048576"] {
+            assert_eq!(cell_reference(parse_cell_reference(reference).unwrap()), reference);
+        }
+    }
+
+    #[test]
+    fn cell_numbers_are_read_from_values_and_text() {
+        assert_eq!(cell_number(&Data::Int(42)), Some(42.0));
+        assert_eq!(cell_number(&Data::Float(1234.5679)), Some(1234.5679));
+        assert_eq!(cell_number(&Data::String("1,234.5".to_string())), Some(1234.5));
+        assert_eq!(cell_number(&Data::String(" (1,234) ".to_string())), Some(-1234.0));
+        assert_eq!(cell_number(&Data::String("-12".to_string())), Some(-12.0));
+    }
+
+    #[test]
+    fn cells_without_a_number_are_skipped() {
+        assert_eq!(cell_number(&Data::Float(f64::NAN)), None);
+        assert_eq!(cell_number(&Data::Float(f64::INFINITY)), None);
+        assert_eq!(cell_number(&Data::String("Revenue".to_string())), None);
+        assert_eq!(cell_number(&Data::String("inf".to_string())), None);
+        assert_eq!(cell_number(&Data::String("()".to_string())), None);
+        assert_eq!(cell_number(&Data::Bool(true)), None);
+        assert_eq!(cell_number(&Data::Empty), None);
+    }
+}
@@ -0,0 +1,636 @@
+// Frozen layouts of records written by earlier canister versions
+// These must never change; new layouts get a new record version instead
+
+use candid::Principal;
+use serde::Deserialize;
+use crate::types::{
+    AnnualReportData, AuditOpinion, BoardResolutionData, CollectionCategory, ConsolidatedBalanceSheetData,
+    ConsolidatedCashFlowData, ConsolidatedIncomeData, Decimal, DividendAnnouncementData, Document, DocumentRetraction,
+    DocumentType, EarningReleaseData, PdfMetadata, PerShareData, ProspectusData, ReportingScale,
+};
+
+// Document as stored before records were versioned (plain bincode, no header)
+#[derive(Deserialize)]
+pub struct DocumentV0 {
+    pub institution_id: String,
+    pub document_id: String,
+    pub owner: Principal,
+    pub name: String,
+    pub company_name: String,
+    pub description: String,
+    pub document_data: DocumentTypeV2,
+    pub document_category: CollectionCategory,
+    pub file_hash: String,
+    pub file_size: u64,
+    pub file_type: String,
+    pub file_data: Vec<u8>,
+    pub publication_date: u64,
+}
+
+impl From<DocumentV0> for DocumentV1 {
+    fn from(legacy: DocumentV0) -> Self {
+        DocumentV1 {
+            institution_id: legacy.institution_id,
+            document_id: legacy.document_id,
+            owner: legacy.owner,
+            name: legacy.name,
+            company_name: legacy.company_name,
+            description: legacy.description,
+            document_data: legacy.document_data,
+            document_category: legacy.document_category,
+            file_hash: legacy.file_hash,
+            file_size: legacy.file_size,
+            file_type: legacy.file_type,
+            file_data: legacy.file_data,
+            publication_date: legacy.publication_date,
+            retraction: None,
+        }
+    }
+}
+
+// Record version 1: adds retraction status
+#[derive(Deserialize)]
+pub struct DocumentV1 {
+    pub institution_id: String,
+    pub document_id: String,
+    pub owner: Principal,
+    pub name: String,
+    pub company_name: String,
+    pub description: String,
+    pub document_data: DocumentTypeV2,
+    pub document_category: CollectionCategory,
+    pub file_hash: String,
+    pub file_size: u64,
+    pub file_type: String,
+    pub file_data: Vec<u8>,
+    pub publication_date: u64,
+    pub retraction: Option<DocumentRetraction>,
+}
+
+impl From<DocumentV1> for DocumentV2 {
+    fn from(legacy: DocumentV1) -> Self {
+        DocumentV2 {
+            institution_id: legacy.institution_id,
+            document_id: legacy.document_id,
+            owner: legacy.owner,
+            name: legacy.name,
+            company_name: legacy.company_name,
+            description: legacy.description,
+            document_data: legacy.document_data,
+            document_category: legacy.document_category,
+            file_hash: legacy.file_hash,
+            file_size: legacy.file_size,
+            file_type: legacy.file_type,
+            file_data: legacy.file_data,
+            publication_date: legacy.publication_date,
+            retraction: legacy.retraction,
+            supersedes: None,
+            version: 1,
+        }
+    }
+}
+
+// Record version 2: adds version chains
+#[derive(Deserialize)]
+pub struct DocumentV2 {
+    pub institution_id: String,
+    pub document_id: String,
+    pub owner: Principal,
+    pub name: String,
+    pub company_name: String,
+    pub description: String,
+    pub document_data: DocumentTypeV2,
+    pub document_category: CollectionCategory,
+    pub file_hash: String,
+    pub file_size: u64,
+    pub file_type: String,
+    pub file_data: Vec<u8>,
+    pub publication_date: u64,
+    pub retraction: Option<DocumentRetraction>,
+    pub supersedes: Option<String>,
+    pub version: u32,
+}
+
+impl From<DocumentV2> for DocumentV3 {
+    fn from(legacy: DocumentV2) -> Self {
+        DocumentV3 {
+            institution_id: legacy.institution_id,
+            document_id: legacy.document_id,
+            owner: legacy.owner,
+            name: legacy.name,
+            company_name: legacy.company_name,
+            description: legacy.description,
+            document_data: legacy.document_data.into(),
+            document_category: legacy.document_category,
+            file_hash: legacy.file_hash,
+            file_size: legacy.file_size,
+            file_type: legacy.file_type,
+            file_data: legacy.file_data,
+            publication_date: legacy.publication_date,
+            retraction: legacy.retraction,
+            supersedes: legacy.supersedes,
+            version: legacy.version,
+        }
+    }
+}
+
+// Record version 3: adds cash flow and per-share data
+#[derive(Deserialize)]
+pub struct DocumentV3 {
+    pub institution_id: String,
+    pub document_id: String,
+    pub owner: Principal,
+    pub name: String,
+    pub company_name: String,
+    pub description: String,
+    pub document_data: DocumentTypeV3,
+    pub document_category: CollectionCategory,
+    pub file_hash: String,
+    pub file_size: u64,
+    pub file_type: String,
+    pub file_data: Vec<u8>,
+    pub publication_date: u64,
+    pub retraction: Option<DocumentRetraction>,
+    pub supersedes: Option<String>,
+    pub version: u32,
+}
+
+// Fails if a float amount has no decimal equivalent (NaN, infinity or out of range)
+impl TryFrom<DocumentV3> for Document {
+    type Error = String;
+
+    fn try_from(legacy: DocumentV3) -> Result<Self, String> {
+        Ok(Document {
+            institution_id: legacy.institution_id,
+            document_id: legacy.document_id,
+            owner: legacy.owner,
+            name: legacy.name,
+            company_name: legacy.company_name,
+            description: legacy.description,
+            document_data: legacy.document_data.try_into()?,
+            document_category: legacy.document_category,
+            file_hash: legacy.file_hash,
+            file_size: legacy.file_size,
+            file_type: legacy.file_type,
+            file_data: legacy.file_data,
+            publication_date: legacy.publication_date,
+            retraction: legacy.retraction,
+            supersedes: legacy.supersedes,
+            version: legacy.version,
+        })
+    }
+}
+
+// Document payloads up to record version 2
+#[derive(Deserialize)]
+pub enum DocumentTypeV2 {
+    EarningRelease(EarningReleaseDataV2),
+    AnnualReport(AnnualReportDataV2),
+    DividendAnnouncement(DividendAnnouncementDataV3),
+    BoardResolution(BoardResolutionDataV3),
+    Prospectus(ProspectusDataV3),
+}
+
+impl From<DocumentTypeV2> for DocumentTypeV3 {
+    fn from(legacy: DocumentTypeV2) -> Self {
+        match legacy {
+            DocumentTypeV2::EarningRelease(data) => DocumentTypeV3::EarningRelease(data.into()),
+            DocumentTypeV2::AnnualReport(data) => DocumentTypeV3::AnnualReport(data.into()),
+            DocumentTypeV2::DividendAnnouncement(data) => DocumentTypeV3::DividendAnnouncement(data),
+            DocumentTypeV2::BoardResolution(data) => DocumentTypeV3::BoardResolution(data),
+            DocumentTypeV2::Prospectus(data) => DocumentTypeV3::Prospectus(data),
+        }
+    }
+}
+
+// Document payloads of record version 3, before amounts carried currency and scale
+#[derive(Deserialize)]
+pub enum DocumentTypeV3 {
+    EarningRelease(EarningReleaseDataV3),
+    AnnualReport(AnnualReportDataV3),
+    DividendAnnouncement(DividendAnnouncementDataV3),
+    BoardResolution(BoardResolutionDataV3),
+    Prospectus(ProspectusDataV3),
+}
+
+impl TryFrom<DocumentTypeV3> for DocumentType {
+    type Error = String;
+
+    fn try_from(legacy: DocumentTypeV3) -> Result<Self, String> {
+        Ok(match legacy {
+            DocumentTypeV3::EarningRelease(data) => DocumentType::EarningRelease(data.try_into()?),
+            DocumentTypeV3::AnnualReport(data) => DocumentType::AnnualReport(data.try_into()?),
+            DocumentTypeV3::DividendAnnouncement(data) => DocumentType::DividendAnnouncement(data.into()),
+            DocumentTypeV3::BoardResolution(data) => DocumentType::BoardResolution(data.into()),
+            DocumentTypeV3::Prospectus(data) => DocumentType::Prospectus(data.into()),
+        })
+    }
+}
+
+// Earning releases before cash flow and per-share data were reported
+#[derive(Deserialize)]
+pub struct EarningReleaseDataV2 {
+    pub earning_release_id: String,
+    pub quarter: u8,
+    pub year: u16,
+    pub consolidated_income_data: ConsolidatedIncomeDataV2,
+    pub consolidated_balance_sheet_data: ConsolidatedBalanceSheetDataV3,
+}
+
+impl From<EarningReleaseDataV2> for EarningReleaseDataV3 {
+    fn from(legacy: EarningReleaseDataV2) -> Self {
+        EarningReleaseDataV3 {
+            earning_release_id: legacy.earning_release_id,
+            quarter: legacy.quarter,
+            year: legacy.year,
+            consolidated_income_data: legacy.consolidated_income_data.into(),
+            consolidated_balance_sheet_data: legacy.consolidated_balance_sheet_data,
+            consolidated_cash_flow_data: ConsolidatedCashFlowDataV3::default(),
+            per_share_data: PerShareDataV3::default(),
+        }
+    }
+}
+
+#[derive(Deserialize)]
+pub struct EarningReleaseDataV3 {
+    pub earning_release_id: String,
+    pub quarter: u8,
+    pub year: u16,
+    pub consolidated_income_data: ConsolidatedIncomeDataV3,
+    pub consolidated_balance_sheet_data: ConsolidatedBalanceSheetDataV3,
+    pub consolidated_cash_flow_data: ConsolidatedCashFlowDataV3,
+    pub per_share_data: PerShareDataV3,
+}
+
+impl TryFrom<EarningReleaseDataV3> for EarningReleaseData {
+    type Error = String;
+
+    fn try_from(legacy: EarningReleaseDataV3) -> Result<Self, String> {
+        Ok(EarningReleaseData {
+            earning_release_id: legacy.earning_release_id,
+            quarter: legacy.quarter,
+            year: legacy.year,
+            consolidated_income_data: legacy.consolidated_income_data.try_into()?,
+            consolidated_balance_sheet_data: legacy.consolidated_balance_sheet_data.try_into()?,
+            consolidated_cash_flow_data: legacy.consolidated_cash_flow_data.into(),
+            per_share_data: legacy.per_share_data.into(),
+        })
+    }
+}
+
+#[derive(Deserialize)]
+pub struct AnnualReportDataV2 {
+    pub annual_report_id: String,
+    pub fiscal_year: u16,
+    pub auditor_name: String,
+    pub audit_opinion: AuditOpinionV3,
+    pub consolidated_income_data: ConsolidatedIncomeDataV2,
+    pub consolidated_balance_sheet_data: ConsolidatedBalanceSheetDataV3,
+}
+
+impl From<AnnualReportDataV2> for AnnualReportDataV3 {
+    fn from(legacy: AnnualReportDataV2) -> Self {
+        AnnualReportDataV3 {
+            annual_report_id: legacy.annual_report_id,
+            fiscal_year: legacy.fiscal_year,
+            auditor_name: legacy.auditor_name,
+            audit_opinion: legacy.audit_opinion,
+            consolidated_income_data: legacy.consolidated_income_data.into(),
+            consolidated_balance_sheet_data: legacy.consolidated_balance_sheet_data,
+        }
+    }
+}
+
+#[derive(Deserialize)]
+pub struct AnnualReportDataV3 {
+    pub annual_report_id: String,
+    pub fiscal_year: u16,
+    pub auditor_name: String,
+    pub audit_opinion: AuditOpinionV3,
+    pub consolidated_income_data: ConsolidatedIncomeDataV3,
+    pub consolidated_balance_sheet_data: ConsolidatedBalanceSheetDataV3,
+}
+
+impl TryFrom<AnnualReportDataV3> for AnnualReportData {
+    type Error = String;
+
+    fn try_from(legacy: AnnualReportDataV3) -> Result<Self, String> {
+        Ok(AnnualReportData {
+            annual_report_id: legacy.annual_report_id,
+            fiscal_year: legacy.fiscal_year,
+            auditor_name: legacy.auditor_name,
+            audit_opinion: legacy.audit_opinion.into(),
+            consolidated_income_data: legacy.consolidated_income_data.try_into()?,
+            consolidated_balance_sheet_data: legacy.consolidated_balance_sheet_data.try_into()?,
+        })
+    }
+}
+
+#[derive(Deserialize)]
+pub enum AuditOpinionV3 {
+    Unqualified,
+    Qualified,
+    Adverse,
+    Disclaimer,
+}
+
+impl From<AuditOpinionV3> for AuditOpinion {
+    fn from(legacy: AuditOpinionV3) -> Self {
+        match legacy {
+            AuditOpinionV3::Unqualified => AuditOpinion::Unqualified,
+            AuditOpinionV3::Qualified => AuditOpinion::Qualified,
+            AuditOpinionV3::Adverse => AuditOpinion::Adverse,
+            AuditOpinionV3::Disclaimer => AuditOpinion::Disclaimer,
+        }
+    }
+}
+
+#[derive(Deserialize)]
+pub struct DividendAnnouncementDataV3 {
+    pub dividend_announcement_id: String,
+    pub fiscal_year: u16,
+    pub dividend_per_share: f64,
+    pub ex_dividend_date: u64,
+    pub record_date: u64,
+    pub payment_date: u64,
+}
+
+impl From<DividendAnnouncementDataV3> for DividendAnnouncementData {
+    fn from(legacy: DividendAnnouncementDataV3) -> Self {
+        DividendAnnouncementData {
+            dividend_announcement_id: legacy.dividend_announcement_id,
+            fiscal_year: legacy.fiscal_year,
+            dividend_per_share: legacy.dividend_per_share,
+            ex_dividend_date: legacy.ex_dividend_date,
+            record_date: legacy.record_date,
+            payment_date: legacy.payment_date,
+        }
+    }
+}
+
+#[derive(Deserialize)]
+pub struct BoardResolutionDataV3 {
+    pub board_resolution_id: String,
+    pub resolution_number: String,
+    pub meeting_date: u64,
+    pub subject: String,
+    pub resolutions: Vec<String>,
+}
+
+impl From<BoardResolutionDataV3> for BoardResolutionData {
+    fn from(legacy: BoardResolutionDataV3) -> Self {
+        BoardResolutionData {
+            board_resolution_id: legacy.board_resolution_id,
+            resolution_number: legacy.resolution_number,
+            meeting_date: legacy.meeting_date,
+            subject: legacy.subject,
+            resolutions: legacy.resolutions,
+        }
+    }
+}
+
+#[derive(Deserialize)]
+pub struct ProspectusDataV3 {
+    pub prospectus_id: String,
+    pub security_type: String,
+    pub offer_price: f64,
+    pub shares_offered: u64,
+    pub offering_amount: f64,
+    pub offering_start_date: u64,
+    pub offering_end_date: u64,
+}
+
+impl From<ProspectusDataV3> for ProspectusData {
+    fn from(legacy: ProspectusDataV3) -> Self {
+        ProspectusData {
+            prospectus_id: legacy.prospectus_id,
+            security_type: legacy.security_type,
+            offer_price: legacy.offer_price,
+            shares_offered: legacy.shares_offered,
+            offering_amount: legacy.offering_amount,
+            offering_start_date: legacy.offering_start_date,
+            offering_end_date: legacy.offering_end_date,
+        }
+    }
+}
+
+// Income statement before revenue and cost of sales were reported
+#[derive(Deserialize)]
+pub struct ConsolidatedIncomeDataV2 {
+    pub gross_profit: f64,
+    pub operating_profit: f64,
+    pub ebitda: f64,
+    pub profit_before_tax: f64,
+    pub net_profit: f64,
+}
+
+impl From<ConsolidatedIncomeDataV2> for ConsolidatedIncomeDataV3 {
+    fn from(legacy: ConsolidatedIncomeDataV2) -> Self {
+        ConsolidatedIncomeDataV3 {
+            revenue: 0.0,
+            cost_of_sales: 0.0,
+            gross_profit: legacy.gross_profit,
+            operating_profit: legacy.operating_profit,
+            ebitda: legacy.ebitda,
+            profit_before_tax: legacy.profit_before_tax,
+            net_profit: legacy.net_profit,
+        }
+    }
+}
+
+// Income statement as bare floating point amounts
+#[derive(Deserialize)]
+pub struct ConsolidatedIncomeDataV3 {
+    pub revenue: f64,
+    pub cost_of_sales: f64,
+    pub gross_profit: f64,
+    pub operating_profit: f64,
+    pub ebitda: f64,
+    pub profit_before_tax: f64,
+    pub net_profit: f64,
+}
+
+// The currency was never recorded, so it stays empty; amounts were entered in units
+impl TryFrom<ConsolidatedIncomeDataV3> for ConsolidatedIncomeData {
+    type Error = String;
+
+    fn try_from(legacy: ConsolidatedIncomeDataV3) -> Result<Self, String> {
+        Ok(ConsolidatedIncomeData {
+            currency: String::new(),
+            scale: ReportingScale::Units,
+            revenue: Decimal::from_f64(legacy.revenue)?,
+            cost_of_sales: Decimal::from_f64(legacy.cost_of_sales)?,
+            gross_profit: Decimal::from_f64(legacy.gross_profit)?,
+            operating_profit: Decimal::from_f64(legacy.operating_profit)?,
+            ebitda: Decimal::from_f64(legacy.ebitda)?,
+            profit_before_tax: Decimal::from_f64(legacy.profit_before_tax)?,
+            net_profit: Decimal::from_f64(legacy.net_profit)?,
+        })
+    }
+}
+
+// Balance sheet as bare floating point amounts
+#[derive(Deserialize)]
+pub struct ConsolidatedBalanceSheetDataV3 {
+    pub total_assets: f64,
+    pub total_equity: f64,
+    pub total_liabilities: f64,
+    pub total_liabilities_and_equity: f64,
+}
+
+impl TryFrom<ConsolidatedBalanceSheetDataV3> for ConsolidatedBalanceSheetData {
+    type Error = String;
+
+    fn try_from(legacy: ConsolidatedBalanceSheetDataV3) -> Result<Self, String> {
+        Ok(ConsolidatedBalanceSheetData {
+            currency: String::new(),
+            scale: ReportingScale::Units,
+            total_assets: Decimal::from_f64(legacy.total_assets)?,
+            total_equity: Decimal::from_f64(legacy.total_equity)?,
+            total_liabilities: Decimal::from_f64(legacy.total_liabilities)?,
+            total_liabilities_and_equity: Decimal::from_f64(legacy.total_liabilities_and_equity)?,
+        })
+    }
+}
+
+#[derive(Deserialize, Default)]
+pub struct ConsolidatedCashFlowDataV3 {
+    pub operating_cash_flow: f64,
+    pub investing_cash_flow: f64,
+    pub financing_cash_flow: f64,
+    pub capital_expenditure: f64,
+}
+
+impl From<ConsolidatedCashFlowDataV3> for ConsolidatedCashFlowData {
+    fn from(legacy: ConsolidatedCashFlowDataV3) -> Self {
+        ConsolidatedCashFlowData {
+            operating_cash_flow: legacy.operating_cash_flow,
+            investing_cash_flow: legacy.investing_cash_flow,
+            financing_cash_flow: legacy.financing_cash_flow,
+            capital_expenditure: legacy.capital_expenditure,
+        }
+    }
+}
+
+#[derive(Deserialize, Default)]
+pub struct PerShareDataV3 {
+    pub eps_basic: f64,
+    pub eps_diluted: f64,
+    pub shares_outstanding: f64,
+    pub dividends_per_share: f64,
+}
+
+impl From<PerShareDataV3> for PerShareData {
+    fn from(legacy: PerShareDataV3) -> Self {
+        PerShareData {
+            eps_basic: legacy.eps_basic,
+            eps_diluted: legacy.eps_diluted,
+            shares_outstanding: legacy.shares_outstanding,
+            dividends_per_share: legacy.dividends_per_share,
+        }
+    }
+}
+
+// PDF metadata as stored before records were versioned (plain bincode, no header, no signatures)
+#[derive(Deserialize)]
+pub struct PdfMetadataV0 {
+    pub document_id: String,
+    pub pdf_version: String,
+    pub page_count: u32,
+    pub title: Option<String>,
+    pub author: Option<String>,
+    pub producer: Option<String>,
+    pub creation_date: Option<u64>,
+    pub modification_date: Option<u64>,
+    pub encrypted: bool,
+    pub embedded_files: Vec<String>,
+}
+
+impl From<PdfMetadataV0> for PdfMetadata {
+    fn from(legacy: PdfMetadataV0) -> Self {
+        PdfMetadata {
+            document_id: legacy.document_id,
+            pdf_version: legacy.pdf_version,
+            page_count: legacy.page_count,
+            title: legacy.title,
+            author: legacy.author,
+            producer: legacy.producer,
+            creation_date: legacy.creation_date,
+            modification_date: legacy.modification_date,
+            encrypted: legacy.encrypted,
+            embedded_files: legacy.embedded_files,
+            signatures: Vec::new(),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    // Until the live types change, the frozen copies must read exactly what they write
+    #[test]
+    fn frozen_payloads_read_records_written_with_the_original_types() {
+        let dividend = DividendAnnouncementData {
+            dividend_announcement_id: "DIV-1".to_string(),
+            fiscal_year: 2024,
+            dividend_per_share: 1.25,
+            ex_dividend_date: 1,
+            record_date: 2,
+            payment_date: 3,
+        };
+        let bytes = bincode::serialize(&dividend).unwrap();
+        let decoded: DividendAnnouncementData = bincode::deserialize::<DividendAnnouncementDataV3>(&bytes).unwrap().into();
+        assert_eq!(bincode::serialize(&decoded).unwrap(), bytes);
+
+        let resolution = BoardResolutionData {
+            board_resolution_id: "BR-1".to_string(),
+            resolution_number: "2024/7".to_string(),
+            meeting_date: 4,
+            subject: "Dividend".to_string(),
+            resolutions: vec!["Approve the final dividend".to_string()],
+        };
+        let bytes = bincode::serialize(&resolution).unwrap();
+        let decoded: BoardResolutionData = bincode::deserialize::<BoardResolutionDataV3>(&bytes).unwrap().into();
+        assert_eq!(bincode::serialize(&decoded).unwrap(), bytes);
+
+        let prospectus = ProspectusData {
+            prospectus_id: "PR-1".to_string(),
+            security_type: "Bonds".to_string(),
+            offer_price: 100.5,
+            shares_offered: 1_000,
+            offering_amount: 100_500.0,
+            offering_start_date: 5,
+            offering_end_date: 6,
+        };
+        let bytes = bincode::serialize(&prospectus).unwrap();
+        let decoded: ProspectusData = bincode::deserialize::<ProspectusDataV3>(&bytes).unwrap().into();
+        assert_eq!(bincode::serialize(&decoded).unwrap(), bytes);
+
+        let cash_flow = ConsolidatedCashFlowData {
+            operating_cash_flow: 10.0,
+            investing_cash_flow: -4.0,
+            financing_cash_flow: -1.5,
+            capital_expenditure: 3.0,
+        };
+        let bytes = bincode::serialize(&cash_flow).unwrap();
+        let decoded: ConsolidatedCashFlowData = bincode::deserialize::<ConsolidatedCashFlowDataV3>(&bytes).unwrap().into();
+        assert_eq!(bincode::serialize(&decoded).unwrap(), bytes);
+
+        let per_share = PerShareData {
+            eps_basic: 1.1,
+            eps_diluted: 1.05,
+            shares_outstanding: 1_000_000.5,
+            dividends_per_share: 0.4,
+        };
+        let bytes = bincode::serialize(&per_share).unwrap();
+        let decoded: PerShareData = bincode::deserialize::<PerShareDataV3>(&bytes).unwrap().into();
+        assert_eq!(bincode::serialize(&decoded).unwrap(), bytes);
+
+        for opinion in [AuditOpinion::Unqualified, AuditOpinion::Qualified, AuditOpinion::Adverse, AuditOpinion::Disclaimer] {
+            let bytes = bincode::serialize(&opinion).unwrap();
+            let decoded: AuditOpinion = bincode::deserialize::<AuditOpinionV3>(&bytes).unwrap().into();
+            assert_eq!(bincode::serialize(&decoded).unwrap(), bytes);
+        }
+    }
+}
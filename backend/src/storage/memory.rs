@@ -1,14 +1,15 @@
 use ic_stable_structures::{
     memory_manager::{MemoryId, MemoryManager, VirtualMemory},
-    DefaultMemoryImpl, StableBTreeMap, 
+    DefaultMemoryImpl, StableBTreeMap, StableCell,
     storable::Bound, 
     Storable, Memory as MemoryTrait,
 };
 use std::cell::RefCell;
 use candid::Principal;
-use crate::types::{Document, Institution, UserProfile, StorageStats};
+use crate::types::{Document, DocumentRetraction, Institution, UserProfile, UploadSession, FileBlobRecord, StorageStats, DocumentText, PdfMetadata, StampedFile, SpreadsheetFieldMapping, XbrlReconciliation};
 use std::borrow::Cow;
 use crate::logging::{get_logger, get_severity_for_event_type};
+use super::legacy;
 
 type Memory = VirtualMemory<DefaultMemoryImpl>;
 
@@ -16,6 +17,9 @@ type Memory = VirtualMemory<DefaultMemoryImpl>;
 const MAX_STRING_KEY_SIZE: usize = 1000;
 const MAX_PRINCIPAL_SIZE: usize = 29;
 
+// Files are stored in pieces of this size, so a ranged read only loads the pieces it covers
+const FILE_BLOB_CHUNK_SIZE_BYTES: usize = 1_000_000;
+
 // Helper function for logging serialization errors
 fn log_serialization_error(type_name: &str, error: &impl std::fmt::Display) {
     let logger = get_logger("storage");
@@ -37,7 +41,7 @@ fn log_deserialization_error(type_name: &str, error: &impl std::fmt::Display, da
 macro_rules! impl_storable_with_logging {
     ($type:ty, $wrapper:ty, $constructor:expr, $default_constructor:expr) => {
         impl Storable for $wrapper {
-            fn to_bytes(&self) -> Cow<[u8]> {
+            fn to_bytes(&self) -> Cow<'_, [u8]> {
                 match bincode::serialize(&self.0) {
                     Ok(bytes) => Cow::Owned(bytes),
                     Err(e) => {
@@ -80,8 +84,100 @@ pub struct StorableInstitution(pub Institution);
 #[derive(Clone)]
 pub struct StorableUserProfile(pub UserProfile);
 
-// Implement Storable for Document wrapper using macro
-impl_storable_with_logging!(Document, StorableDocument, StorableDocument, StorableDocument(crate::types::Document::default()));
+#[derive(Clone)]
+pub struct StorableUploadSession(pub UploadSession);
+
+#[derive(Clone)]
+pub struct StorableFileBlobRecord(pub FileBlobRecord);
+
+#[derive(Clone)]
+pub struct StorableXbrlReconciliation(pub XbrlReconciliation);
+
+#[derive(Clone)]
+pub struct StorableSpreadsheetMapping(pub Vec<SpreadsheetFieldMapping>);
+
+#[derive(Clone)]
+pub struct StorablePdfMetadata(pub PdfMetadata);
+
+#[derive(Clone)]
+pub struct StorableDocumentText(pub DocumentText);
+
+// Documents are stored as DOCUMENT_RECORD_MAGIC, a layout version byte and the bincode payload,
+// so the Document struct can evolve; records without the header use the original layout
+const DOCUMENT_RECORD_MAGIC: &[u8] = b"CNDOC";
+const DOCUMENT_RECORD_VERSION: u8 = 4;
+
+impl Storable for StorableDocument {
+    fn to_bytes(&self) -> Cow<'_, [u8]> {
+        match bincode::serialize(&self.0) {
+            Ok(payload) => {
+                let mut bytes = Vec::with_capacity(DOCUMENT_RECORD_MAGIC.len() + 1 + payload.len());
+                bytes.extend_from_slice(DOCUMENT_RECORD_MAGIC);
+                bytes.push(DOCUMENT_RECORD_VERSION);
+                bytes.extend_from_slice(&payload);
+                Cow::Owned(bytes)
+            }
+            Err(e) => {
+                log_serialization_error("Document", &e);
+                Cow::Owned(Vec::new())
+            }
+        }
+    }
+
+    fn from_bytes(bytes: Cow<[u8]>) -> Self {
+        if bytes.is_empty() {
+            let logger = get_logger("storage");
+            let severity = get_severity_for_event_type("CORRUPTED_DATA");
+            logger.log(severity, "CORRUPTED_DATA", "Attempted to deserialize empty bytes - returning default Document", None);
+            return StorableDocument(Document::default());
+        }
+
+        let decoded = match bytes.strip_prefix(DOCUMENT_RECORD_MAGIC) {
+            Some([DOCUMENT_RECORD_VERSION, payload @ ..]) => bincode::deserialize::<Document>(payload),
+            Some([3, payload @ ..]) => bincode::deserialize::<legacy::DocumentV3>(payload).and_then(convert_legacy_document),
+            Some([2, payload @ ..]) => bincode::deserialize::<legacy::DocumentV2>(payload)
+                .and_then(|document| convert_legacy_document(document.into())),
+            Some([1, payload @ ..]) => bincode::deserialize::<legacy::DocumentV1>(payload)
+                .and_then(|document| convert_legacy_document(legacy::DocumentV2::from(document).into())),
+            Some(_) => Err(Box::new(bincode::ErrorKind::Custom("Unknown document record version".to_string()))),
+            None => bincode::deserialize::<legacy::DocumentV0>(&bytes).and_then(|document| {
+                convert_legacy_document(legacy::DocumentV2::from(legacy::DocumentV1::from(document)).into())
+            }),
+        };
+
+        match decoded {
+            Ok(document) => StorableDocument(document),
+            Err(e) => {
+                let data_preview = format!("{:?}", &bytes[..std::cmp::min(100, bytes.len())]);
+                log_deserialization_error("Document", &e, &data_preview);
+                StorableDocument(Document::default())
+            }
+        }
+    }
+
+    const BOUND: Bound = Bound::Unbounded;
+}
+
+// Float amounts of records before version 4 that have no decimal equivalent make the record unreadable
+fn convert_legacy_document(document: legacy::DocumentV3) -> bincode::Result<Document> {
+    Document::try_from(document).map_err(|e| Box::new(bincode::ErrorKind::Custom(e)))
+}
+
+// Serde adapter storing an embedded Document in the versioned record format,
+// so records that contain documents (e.g. upload sessions) survive Document changes
+pub mod document_record {
+    use super::*;
+    use serde::{Deserialize, Deserializer, Serializer};
+
+    pub fn serialize<S: Serializer>(document: &Document, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.serialize_bytes(&StorableDocument(document.clone()).to_bytes())
+    }
+
+    pub fn deserialize<'de, D: Deserializer<'de>>(deserializer: D) -> Result<Document, D::Error> {
+        let bytes = Vec::<u8>::deserialize(deserializer)?;
+        Ok(StorableDocument::from_bytes(Cow::Owned(bytes)).0)
+    }
+}
 
 // Implement Storable for Institution wrapper using macro
 impl_storable_with_logging!(Institution, StorableInstitution, StorableInstitution, StorableInstitution(crate::types::Institution::default()));
@@ -102,12 +198,116 @@ impl_storable_with_logging!(
     })
 );
 
+// Implement Storable for UploadSession wrapper using macro
+impl_storable_with_logging!(UploadSession, StorableUploadSession, StorableUploadSession, StorableUploadSession(UploadSession::default()));
+
+// Implement Storable for FileBlobRecord wrapper using macro
+impl_storable_with_logging!(FileBlobRecord, StorableFileBlobRecord, StorableFileBlobRecord, StorableFileBlobRecord(FileBlobRecord::default()));
+
+// Implement Storable for XbrlReconciliation wrapper using macro
+impl_storable_with_logging!(XbrlReconciliation, StorableXbrlReconciliation, StorableXbrlReconciliation, StorableXbrlReconciliation(XbrlReconciliation::default()));
+
+// Implement Storable for the spreadsheet mapping wrapper using macro
+impl_storable_with_logging!(Vec<SpreadsheetFieldMapping>, StorableSpreadsheetMapping, StorableSpreadsheetMapping, StorableSpreadsheetMapping(Vec::new()));
+
+// PDF metadata is stored as PDF_METADATA_RECORD_MAGIC, a layout version byte and the bincode payload;
+// records without the header were written before signatures were recorded
+const PDF_METADATA_RECORD_MAGIC: &[u8] = b"CNPDF";
+const PDF_METADATA_RECORD_VERSION: u8 = 1;
+
+impl Storable for StorablePdfMetadata {
+    fn to_bytes(&self) -> Cow<'_, [u8]> {
+        match bincode::serialize(&self.0) {
+            Ok(payload) => {
+                let mut bytes = Vec::with_capacity(PDF_METADATA_RECORD_MAGIC.len() + 1 + payload.len());
+                bytes.extend_from_slice(PDF_METADATA_RECORD_MAGIC);
+                bytes.push(PDF_METADATA_RECORD_VERSION);
+                bytes.extend_from_slice(&payload);
+                Cow::Owned(bytes)
+            }
+            Err(e) => {
+                log_serialization_error("PdfMetadata", &e);
+                Cow::Owned(Vec::new())
+            }
+        }
+    }
+
+    fn from_bytes(bytes: Cow<[u8]>) -> Self {
+        let decoded = match bytes.strip_prefix(PDF_METADATA_RECORD_MAGIC) {
+            Some([PDF_METADATA_RECORD_VERSION, payload @ ..]) => bincode::deserialize::<PdfMetadata>(payload),
+            Some(_) => Err(Box::new(bincode::ErrorKind::Custom("Unknown PDF metadata record version".to_string()))),
+            None => bincode::deserialize::<legacy::PdfMetadataV0>(&bytes).map(PdfMetadata::from),
+        };
+
+        match decoded {
+            Ok(metadata) => StorablePdfMetadata(metadata),
+            Err(e) => {
+                let data_preview = format!("{:?}", &bytes[..std::cmp::min(100, bytes.len())]);
+                log_deserialization_error("PdfMetadata", &e, &data_preview);
+                StorablePdfMetadata(PdfMetadata::default())
+            }
+        }
+    }
+
+    const BOUND: Bound = Bound::Unbounded;
+}
+
+// Implement Storable for DocumentText wrapper using macro
+impl_storable_with_logging!(DocumentText, StorableDocumentText, StorableDocumentText, StorableDocumentText(DocumentText::default()));
+
+// Wrapper type for raw binary data (stored as-is, without bincode framing)
+#[derive(Clone)]
+pub struct StorableBlob(pub Vec<u8>);
+
+impl Storable for StorableBlob {
+    fn to_bytes(&self) -> Cow<'_, [u8]> {
+        Cow::Borrowed(&self.0)
+    }
+
+    fn from_bytes(bytes: Cow<[u8]>) -> Self {
+        StorableBlob(bytes.into_owned())
+    }
+
+    const BOUND: Bound = Bound::Unbounded;
+}
+
+// Stamped copies are stored as the hex file hash followed by the PDF bytes, avoiding a bincode pass over the file
+const STAMPED_FILE_HASH_LENGTH: usize = 64;
+
+#[derive(Clone)]
+pub struct StorableStampedFile(pub StampedFile);
+
+impl Storable for StorableStampedFile {
+    fn to_bytes(&self) -> Cow<'_, [u8]> {
+        let mut bytes = Vec::with_capacity(STAMPED_FILE_HASH_LENGTH + self.0.data.len());
+        bytes.extend_from_slice(self.0.file_hash.as_bytes());
+        bytes.extend_from_slice(&self.0.data);
+        Cow::Owned(bytes)
+    }
+
+    fn from_bytes(bytes: Cow<[u8]>) -> Self {
+        if bytes.len() < STAMPED_FILE_HASH_LENGTH {
+            let logger = get_logger("storage");
+            let severity = get_severity_for_event_type("CORRUPTED_DATA");
+            logger.log(severity, "CORRUPTED_DATA", "Stamped file record is shorter than its hash - returning default StampedFile", None);
+            return StorableStampedFile(StampedFile::default());
+        }
+        let (file_hash, data) = bytes.split_at(STAMPED_FILE_HASH_LENGTH);
+        StorableStampedFile(StampedFile {
+            file_hash: String::from_utf8_lossy(file_hash).into_owned(),
+            data: data.to_vec(),
+        })
+    }
+
+    const BOUND: Bound = Bound::Unbounded;
+}
+
 // Wrapper type for String keys
 #[derive(Clone, PartialEq, Eq, PartialOrd, Ord)]
 pub struct StorableString(pub String);
 
 impl Storable for StorableString {
-    fn to_bytes(&self) -> Cow<[u8]> {
+    fn to_bytes(&self) -> Cow<'_, [u8]> {
         Cow::Borrowed(self.0.as_bytes())
     }
 
@@ -135,7 +335,7 @@ impl Storable for StorableString {
 pub struct StorablePrincipal(pub Principal);
 
 impl Storable for StorablePrincipal {
-    fn to_bytes(&self) -> Cow<[u8]> {
+    fn to_bytes(&self) -> Cow<'_, [u8]> {
         Cow::Borrowed(self.0.as_slice())
     }
 
@@ -162,7 +362,7 @@ thread_local! {
     static MEMORY_MANAGER: RefCell<MemoryManager<DefaultMemoryImpl>> =
         RefCell::new(MemoryManager::init(DefaultMemoryImpl::default()));
 
-    // Store complete documents using proper Storable types
+    // Store document metadata using proper Storable types (file data lives in FILE_BLOBS)
     pub static DOCUMENTS: RefCell<StableBTreeMap<StorableString, StorableDocument, Memory>> = RefCell::new(
         init_stable_map(MemoryId::new(0))
     );
@@ -176,6 +376,86 @@ thread_local! {
     pub static USER_PROFILES: RefCell<StableBTreeMap<StorablePrincipal, StorableUserProfile, Memory>> = RefCell::new(
         init_stable_map(MemoryId::new(2))
     );
+
+    // Store in-progress chunked upload sessions
+    pub static UPLOAD_SESSIONS: RefCell<StableBTreeMap<StorableString, StorableUploadSession, Memory>> = RefCell::new(
+        init_stable_map(MemoryId::new(3))
+    );
+
+    // Store uploaded chunks keyed by "<session_id>:<chunk_index>"
+    pub static UPLOAD_CHUNKS: RefCell<StableBTreeMap<StorableString, StorableBlob, Memory>> = RefCell::new(
+        init_stable_map(MemoryId::new(4))
+    );
+
+    // Store file contents separately from document metadata, in pieces keyed by "<file_hash>:<chunk_index>"
+    pub static FILE_BLOBS: RefCell<StableBTreeMap<StorableString, StorableBlob, Memory>> = RefCell::new(
+        init_stable_map(MemoryId::new(5))
+    );
+
+    // IDs of documents stored in an older layout that still have to be rewritten in the current one
+    pub static DOCUMENT_REWRITES: RefCell<StableBTreeMap<StorableString, (), Memory>> = RefCell::new(
+        init_stable_map(MemoryId::new(7))
+    );
+
+    // Reference counts of FILE_BLOBS entries, keyed by SHA-256 file hash
+    pub static FILE_BLOB_REFS: RefCell<StableBTreeMap<StorableString, StorableFileBlobRecord, Memory>> = RefCell::new(
+        init_stable_map(MemoryId::new(8))
+    );
+
+    // Index of documents by file hash, keyed by "<file_hash>:<document_id>"
+    pub static HASH_INDEX: RefCell<StableBTreeMap<StorableString, (), Memory>> = RefCell::new(
+        init_stable_map(MemoryId::new(9))
+    );
+
+    // Amendment chain index: superseded document ID -> ID of the amendment that replaced it
+    pub static SUPERSEDED_BY: RefCell<StableBTreeMap<StorableString, StorableString, Memory>> = RefCell::new(
+        init_stable_map(MemoryId::new(10))
+    );
+
+    // Monotonic counter for generated IDs (see utils::id_generator)
+    pub static ID_COUNTER: RefCell<StableCell<u64, Memory>> = RefCell::new(
+        init_stable_cell(MemoryId::new(11), 0)
+    );
+
+    // XBRL provenance: document ID -> how its structured data was derived from the filing
+    pub static XBRL_RECONCILIATIONS: RefCell<StableBTreeMap<StorableString, StorableXbrlReconciliation, Memory>> = RefCell::new(
+        init_stable_map(MemoryId::new(12))
+    );
+
+    // Excel earnings pack layouts: institution ID -> where each earning release field is read from
+    pub static SPREADSHEET_MAPPINGS: RefCell<StableBTreeMap<StorableString, StorableSpreadsheetMapping, Memory>> = RefCell::new(
+        init_stable_map(MemoryId::new(13))
+    );
+
+    // PDF structure read at upload: document ID -> page count, version, Info fields, encryption, attachments
+    pub static PDF_METADATA: RefCell<StableBTreeMap<StorableString, StorablePdfMetadata, Memory>> = RefCell::new(
+        init_stable_map(MemoryId::new(14))
+    );
+
+    // Extracted file text: document ID -> text read once at publication
+    pub static DOCUMENT_TEXTS: RefCell<StableBTreeMap<StorableString, StorableDocumentText, Memory>> = RefCell::new(
+        init_stable_map(MemoryId::new(15))
+    );
+
+    // Base URL of the frontend linked from certificate QR codes; empty until an admin sets it
+    pub static VERIFICATION_BASE_URL: RefCell<StableCell<StorableString, Memory>> = RefCell::new(
+        init_stable_cell(MemoryId::new(16), StorableString(String::new()))
+    );
+
+    // Stamped PDF copies: document ID -> copy with the notarization footer, removed when the document is retracted
+    pub static STAMPED_FILES: RefCell<StableBTreeMap<StorableString, StorableStampedFile, Memory>> = RefCell::new(
+        init_stable_map(MemoryId::new(17))
+    );
+
+    // IDs of documents whose PDF metadata predates signature checking and still has to be read again
+    pub static PDF_SIGNATURE_BACKFILL: RefCell<StableBTreeMap<StorableString, (), Memory>> = RefCell::new(
+        init_stable_map(MemoryId::new(18))
+    );
+
+    // Storage schema version, used to run one-time migrations after upgrades
+    pub static SCHEMA_VERSION: RefCell<StableCell<u32, Memory>> = RefCell::new(
+        init_stable_cell(MemoryId::new(6), 0)
+    );
 }
 
 // Helper function to safely initialize stable maps
@@ -196,7 +476,17 @@ where
     }
 }
 
-// Helper function to safely get and deserialize a document
+// Helper function to initialize stable cells (loads the existing value if present)
+fn init_stable_cell<T>(memory_id: MemoryId, default_value: T) -> StableCell<T, Memory>
+where
+    T: Storable,
+{
+    let memory = MEMORY_MANAGER.with(|m| m.borrow().get(memory_id));
+    StableCell::init(memory, default_value)
+        .unwrap_or_else(|e| ic_cdk::trap(format!("Failed to initialize stable cell {:?}: {:?}", memory_id, e)))
+}
+
+// Helper function to safely get and deserialize document metadata (file_data is left empty)
 pub fn get_document_safe(document_id: &str) -> Option<Document> {
     DOCUMENTS.with(|storage| {
         storage.borrow().get(&StorableString(document_id.to_string()))
@@ -204,6 +494,103 @@ pub fn get_document_safe(document_id: &str) -> Option<Document> {
     })
 }
 
+// Helper function to get a document together with its file data
+pub fn get_document_with_file_safe(document_id: &str) -> Option<Document> {
+    get_document_safe(document_id).map(attach_file_data)
+}
+
+// Helper function to get only the file data of a document
+pub fn get_document_file_safe(document_id: &str) -> Option<Vec<u8>> {
+    get_document_with_file_safe(document_id).map(|document| document.file_data)
+}
+
+// Fill in file_data of a metadata record from blob storage (left empty if the file was purged)
+// Records not yet rewritten by the v1 migration still hold their file data inline
+pub fn attach_file_data(mut document: Document) -> Document {
+    if references_file_blob(&document) && document.file_data.is_empty() {
+        document.file_data = get_file_blob_safe(&document.file_hash).unwrap_or_default();
+    }
+    document
+}
+
+// Helper function to get file contents by SHA-256 hash
+pub fn get_file_blob_safe(file_hash: &str) -> Option<Vec<u8>> {
+    let keys = file_blob_chunk_keys(file_hash);
+    if keys.is_empty() {
+        return None;
+    }
+    FILE_BLOBS.with(|blobs| {
+        let blobs = blobs.borrow();
+        let mut file_data = Vec::new();
+        for key in keys {
+            file_data.extend_from_slice(&blobs.get(&key)?.0);
+        }
+        Some(file_data)
+    })
+}
+
+/// Read bytes start..end of a stored file, loading only the pieces that cover them
+pub fn read_file_blob_range(file_hash: &str, start: u64, end: u64) -> Option<Vec<u8>> {
+    if end < start {
+        return None;
+    }
+
+    let chunk_size = FILE_BLOB_CHUNK_SIZE_BYTES as u64;
+    let mut data = Vec::with_capacity((end - start) as usize);
+    FILE_BLOBS.with(|blobs| {
+        let blobs = blobs.borrow();
+        let mut chunk_index = start / chunk_size;
+        while chunk_index * chunk_size < end {
+            let chunk = blobs.get(&file_blob_chunk_key(file_hash, chunk_index))?;
+            let chunk_start = chunk_index * chunk_size;
+            let from = start.saturating_sub(chunk_start) as usize;
+            let to = std::cmp::min(end - chunk_start, chunk.0.len() as u64) as usize;
+            data.extend_from_slice(chunk.0.get(from..to)?);
+            chunk_index += 1;
+        }
+        Some(())
+    })?;
+
+    (data.len() as u64 == end - start).then_some(data)
+}
+
+// Chunk keys are zero-padded so that lexicographic order matches file order
+fn file_blob_chunk_key(file_hash: &str, chunk_index: u64) -> StorableString {
+    StorableString(format!("{}:{:010}", file_hash, chunk_index))
+}
+
+fn file_blob_chunk_keys(file_hash: &str) -> Vec<StorableString> {
+    let prefix = format!("{}:", file_hash);
+    FILE_BLOBS.with(|blobs| {
+        blobs.borrow().keys_range(StorableString(prefix.clone())..)
+            .take_while(|key| key.0.starts_with(&prefix))
+            .collect()
+    })
+}
+
+fn file_blob_exists(file_hash: &str) -> bool {
+    FILE_BLOBS.with(|blobs| blobs.borrow().contains_key(&file_blob_chunk_key(file_hash, 0)))
+}
+
+fn store_file_blob_chunks(file_hash: &str, file_data: &[u8]) {
+    FILE_BLOBS.with(|blobs| {
+        let mut blobs = blobs.borrow_mut();
+        for (chunk_index, chunk) in file_data.chunks(FILE_BLOB_CHUNK_SIZE_BYTES).enumerate() {
+            blobs.insert(file_blob_chunk_key(file_hash, chunk_index as u64), StorableBlob(chunk.to_vec()));
+        }
+    });
+}
+
+fn remove_file_blob(file_hash: &str) {
+    let keys = file_blob_chunk_keys(file_hash);
+    FILE_BLOBS.with(|blobs| {
+        let mut blobs = blobs.borrow_mut();
+        for key in keys {
+            blobs.remove(&key);
+        }
+    });
+}
+
 // Helper function to safely get and deserialize an institution
 pub fn get_institution_safe(institution_id: &str) -> Option<Institution> {
     INSTITUTIONS.with(|storage| {
@@ -212,6 +599,17 @@ pub fn get_institution_safe(institution_id: &str) -> Option<Institution> {
     })
 }
 
+// Helper function to store a new institution; never overwrites an existing one
+pub fn insert_institution_safe(institution_id: &str, institution: &Institution) -> Result<(), String> {
+    let exists = INSTITUTIONS.with(|storage| {
+        storage.borrow().contains_key(&StorableString(institution_id.to_string()))
+    });
+    if exists {
+        return Err(format!("Institution {} already exists", institution_id));
+    }
+    update_institution_safe(institution_id, institution)
+}
+
 // Helper function to safely update an institution
 pub fn update_institution_safe(institution_id: &str, institution: &Institution) -> Result<(), String> {
     INSTITUTIONS.with(|storage| {
@@ -220,6 +618,17 @@ pub fn update_institution_safe(institution_id: &str, institution: &Institution)
     Ok(())
 }
 
+// Helper function to store a newly published document; never overwrites an existing one
+pub fn insert_document_safe(document_id: &str, document: &Document) -> Result<(), String> {
+    let exists = DOCUMENTS.with(|storage| {
+        storage.borrow().contains_key(&StorableString(document_id.to_string()))
+    });
+    if exists {
+        return Err(format!("Document {} already exists", document_id));
+    }
+    store_document_safe(document_id, document)
+}
+
 // Helper function to safely store a document
 pub fn store_document_safe(document_id: &str, document: &Document) -> Result<(), String> {
     // Validate document before storing
@@ -234,8 +643,33 @@ pub fn store_document_safe(document_id: &str, document: &Document) -> Result<(),
     // Log storage operation for memory wipe tracking
     let before_count = DOCUMENTS.with(|storage| storage.borrow().len());
     
+    // File contents are stored once per hash; the document record only keeps metadata
+    let mut metadata = document.clone();
+    let file_data = std::mem::take(&mut metadata.file_data);
+    
+    let previous_hash = get_document_safe(document_id).map(|previous| previous.file_hash);
+    if previous_hash.as_deref() != Some(metadata.file_hash.as_str()) {
+        retain_file_blob(&metadata.file_hash, file_data)?;
+        index_document_hash(&metadata.file_hash, document_id);
+        if let Some(previous_hash) = previous_hash {
+            release_file_blob(&previous_hash);
+            unindex_document_hash(&previous_hash, document_id);
+        }
+    } else if !file_data.is_empty() && references_file_blob(&metadata) {
+        // Same file stored again (e.g. legacy inline records during migration)
+        store_file_blob_if_missing(&metadata.file_hash, file_data);
+    }
+    
+    if let Some(superseded_id) = &metadata.supersedes {
+        SUPERSEDED_BY.with(|index| {
+            index.borrow_mut().insert(StorableString(superseded_id.clone()), StorableString(document_id.to_string()));
+        });
+    }
+    
+    crate::certification::certify_document(&metadata);
+    
     DOCUMENTS.with(|storage| {
-        storage.borrow_mut().insert(StorableString(document_id.to_string()), StorableDocument(document.clone()));
+        storage.borrow_mut().insert(StorableString(document_id.to_string()), StorableDocument(metadata));
     });
     
     let after_count = DOCUMENTS.with(|storage| storage.borrow().len());
@@ -250,6 +684,288 @@ pub fn store_document_safe(document_id: &str, document: &Document) -> Result<(),
     Ok(())
 }
 
+// Helper function to retract a published document; the record stays in DOCUMENTS
+pub fn retract_document_safe(document_id: &str, retraction: DocumentRetraction) -> Result<Document, String> {
+    let mut document = get_document_safe(document_id).ok_or("Document not found")?;
+
+    if document.retraction.is_some() {
+        return Err("Document has already been retracted".to_string());
+    }
+
+    // Drop this document's reference; the bytes are freed once no other document shares them
+    // The extracted text is a copy of the contents and goes with them
+    if retraction.file_purged {
+        release_file_blob(&document.file_hash);
+        DOCUMENT_TEXTS.with(|texts| {
+            texts.borrow_mut().remove(&StorableString(document_id.to_string()));
+        });
+    }
+
+    // The footer of a stamped copy shows the retraction, so any cached copy is outdated
+    remove_stamped_file_safe(document_id);
+
+    // Storing the record re-certifies it, so receipts from now on carry the retraction
+    document.retraction = Some(retraction);
+    store_document_safe(document_id, &document)?;
+
+    Ok(document)
+}
+
+// Whether a document still references its file blob (purged retractions do not)
+fn references_file_blob(document: &Document) -> bool {
+    !document.retraction.as_ref().is_some_and(|retraction| retraction.file_purged)
+}
+
+// Get the ID of the amendment that superseded a document, if any
+pub fn get_superseding_document_id(document_id: &str) -> Option<String> {
+    SUPERSEDED_BY.with(|index| {
+        index.borrow().get(&StorableString(document_id.to_string()))
+            .map(|superseding_id| superseding_id.0)
+    })
+}
+
+// Store how the data of a document was derived from its XBRL filing
+pub fn store_xbrl_reconciliation_safe(document_id: &str, reconciliation: &XbrlReconciliation) {
+    XBRL_RECONCILIATIONS.with(|reconciliations| {
+        reconciliations.borrow_mut().insert(StorableString(document_id.to_string()), StorableXbrlReconciliation(reconciliation.clone()));
+    });
+}
+
+pub fn get_xbrl_reconciliation_safe(document_id: &str) -> Option<XbrlReconciliation> {
+    XBRL_RECONCILIATIONS.with(|reconciliations| {
+        reconciliations.borrow().get(&StorableString(document_id.to_string()))
+            .map(|storable_reconciliation| storable_reconciliation.0)
+    })
+}
+
+// Store the structural metadata of a published PDF
+pub fn store_pdf_metadata_safe(metadata: &PdfMetadata) {
+    PDF_METADATA.with(|entries| {
+        entries.borrow_mut().insert(StorableString(metadata.document_id.clone()), StorablePdfMetadata(metadata.clone()));
+    });
+}
+
+pub fn get_pdf_metadata_safe(document_id: &str) -> Option<PdfMetadata> {
+    PDF_METADATA.with(|entries| {
+        entries.borrow().get(&StorableString(document_id.to_string()))
+            .map(|storable_metadata| storable_metadata.0)
+    })
+}
+
+// Store the extracted text of a document
+pub fn store_document_text_safe(document_text: &DocumentText) {
+    DOCUMENT_TEXTS.with(|texts| {
+        texts.borrow_mut().insert(StorableString(document_text.document_id.clone()), StorableDocumentText(document_text.clone()));
+    });
+}
+
+pub fn get_document_text_safe(document_id: &str) -> Option<DocumentText> {
+    DOCUMENT_TEXTS.with(|texts| {
+        texts.borrow().get(&StorableString(document_id.to_string()))
+            .map(|storable_text| storable_text.0)
+    })
+}
+
+// Store the stamped copy of a PDF document
+pub fn store_stamped_file_safe(document_id: &str, stamped_file: &StampedFile) {
+    STAMPED_FILES.with(|files| {
+        files.borrow_mut().insert(StorableString(document_id.to_string()), StorableStampedFile(stamped_file.clone()));
+    });
+}
+
+pub fn get_stamped_file_safe(document_id: &str) -> Option<StampedFile> {
+    STAMPED_FILES.with(|files| {
+        files.borrow().get(&StorableString(document_id.to_string()))
+            .map(|storable_file| storable_file.0)
+    })
+}
+
+pub fn remove_stamped_file_safe(document_id: &str) {
+    STAMPED_FILES.with(|files| {
+        files.borrow_mut().remove(&StorableString(document_id.to_string()));
+    });
+}
+
+// Verification base URL set by an admin (None: the built-in default applies)
+pub fn get_verification_base_url_safe() -> Option<String> {
+    VERIFICATION_BASE_URL.with(|cell| {
+        let url = cell.borrow().get().0.clone();
+        (!url.is_empty()).then_some(url)
+    })
+}
+
+pub fn set_verification_base_url_safe(url: &str) -> Result<(), String> {
+    VERIFICATION_BASE_URL.with(|cell| {
+        cell.borrow_mut().set(StorableString(url.to_string()))
+            .map(|_| ())
+            .map_err(|e| format!("Failed to store verification base URL: {:?}", e))
+    })
+}
+
+// Spreadsheet mapping configured by an institution (None: the default mapping applies)
+pub fn get_spreadsheet_mapping_safe(institution_id: &str) -> Option<Vec<SpreadsheetFieldMapping>> {
+    SPREADSHEET_MAPPINGS.with(|mappings| {
+        mappings.borrow().get(&StorableString(institution_id.to_string()))
+            .map(|storable_mapping| storable_mapping.0)
+    })
+}
+
+// Store an institution's spreadsheet mapping; an empty mapping restores the default
+pub fn update_spreadsheet_mapping_safe(institution_id: &str, mapping: &[SpreadsheetFieldMapping]) {
+    SPREADSHEET_MAPPINGS.with(|mappings| {
+        let key = StorableString(institution_id.to_string());
+        if mapping.is_empty() {
+            mappings.borrow_mut().remove(&key);
+        } else {
+            mappings.borrow_mut().insert(key, StorableSpreadsheetMapping(mapping.to_vec()));
+        }
+    });
+}
+
+// Follow the amendment chain forward to its latest version
+pub fn get_latest_document_id(document_id: &str) -> String {
+    let mut latest_id = document_id.to_string();
+    while let Some(next_id) = get_superseding_document_id(&latest_id) {
+        latest_id = next_id;
+    }
+    latest_id
+}
+
+fn hash_index_key(file_hash: &str, document_id: &str) -> StorableString {
+    StorableString(format!("{}:{}", file_hash, document_id))
+}
+
+fn index_document_hash(file_hash: &str, document_id: &str) {
+    HASH_INDEX.with(|index| {
+        index.borrow_mut().insert(hash_index_key(file_hash, document_id), ());
+    });
+}
+
+fn unindex_document_hash(file_hash: &str, document_id: &str) {
+    HASH_INDEX.with(|index| {
+        index.borrow_mut().remove(&hash_index_key(file_hash, document_id));
+    });
+}
+
+// Get the IDs of all documents published with the given file hash
+pub fn get_document_ids_by_hash(file_hash: &str) -> Vec<String> {
+    let prefix = format!("{}:", file_hash);
+    HASH_INDEX.with(|index| {
+        index.borrow().range(StorableString(prefix.clone())..)
+            .take_while(|(key, _)| key.0.starts_with(&prefix))
+            .map(|(key, _)| key.0[prefix.len()..].to_string())
+            .collect()
+    })
+}
+
+// Recompute HASH_INDEX from the stored documents
+pub fn rebuild_hash_index() -> u64 {
+    HASH_INDEX.with(|index| {
+        let mut index = index.borrow_mut();
+        let keys: Vec<StorableString> = index.iter().map(|(key, _)| key).collect();
+        for key in keys {
+            index.remove(&key);
+        }
+    });
+
+    let entries: Vec<(String, String)> = DOCUMENTS.with(|storage| {
+        storage.borrow().iter()
+            .map(|(key, doc)| (doc.0.file_hash, key.0))
+            .collect()
+    });
+
+    for (file_hash, document_id) in &entries {
+        index_document_hash(file_hash, document_id);
+    }
+
+    entries.len() as u64
+}
+
+// Add a reference to a file blob, storing the data if this hash is new
+// Identical files uploaded by different documents share a single blob
+fn retain_file_blob(file_hash: &str, file_data: Vec<u8>) -> Result<(), String> {
+    if file_hash.is_empty() {
+        return Err("File hash is required to store file data".to_string());
+    }
+
+    let key = StorableString(file_hash.to_string());
+    let mut record = FILE_BLOB_REFS.with(|refs| refs.borrow().get(&key).map(|record| record.0))
+        .unwrap_or_default();
+
+    if !file_blob_exists(file_hash) && file_data.is_empty() {
+        return Err("File data is required for a new file hash".to_string());
+    }
+    if let Some(size_bytes) = store_file_blob_if_missing(file_hash, file_data) {
+        record.size_bytes = size_bytes;
+    }
+
+    record.ref_count += 1;
+    FILE_BLOB_REFS.with(|refs| {
+        refs.borrow_mut().insert(key, StorableFileBlobRecord(record));
+    });
+
+    Ok(())
+}
+
+// Store file data under its hash unless a blob already exists; returns the stored size
+fn store_file_blob_if_missing(file_hash: &str, file_data: Vec<u8>) -> Option<u64> {
+    if file_data.is_empty() || file_blob_exists(file_hash) {
+        return None;
+    }
+    store_file_blob_chunks(file_hash, &file_data);
+    Some(file_data.len() as u64)
+}
+
+// Drop a reference to a file blob, freeing the data when the last reference is removed
+fn release_file_blob(file_hash: &str) {
+    let key = StorableString(file_hash.to_string());
+    let Some(mut record) = FILE_BLOB_REFS.with(|refs| refs.borrow().get(&key).map(|record| record.0)) else {
+        return;
+    };
+
+    record.ref_count = record.ref_count.saturating_sub(1);
+    if record.ref_count == 0 {
+        FILE_BLOB_REFS.with(|refs| {
+            refs.borrow_mut().remove(&key);
+        });
+        remove_file_blob(file_hash);
+    } else {
+        FILE_BLOB_REFS.with(|refs| {
+            refs.borrow_mut().insert(key, StorableFileBlobRecord(record));
+        });
+    }
+}
+
+// Recompute FILE_BLOB_REFS from the documents that reference each blob
+pub fn rebuild_file_blob_refs() -> u64 {
+    let mut counts: std::collections::BTreeMap<String, u64> = std::collections::BTreeMap::new();
+    DOCUMENTS.with(|storage| {
+        for (_, doc) in storage.borrow().iter() {
+            if references_file_blob(&doc.0) {
+                *counts.entry(doc.0.file_hash).or_default() += 1;
+            }
+        }
+    });
+
+    FILE_BLOB_REFS.with(|refs| {
+        let mut refs = refs.borrow_mut();
+        let keys: Vec<StorableString> = refs.iter().map(|(key, _)| key).collect();
+        for key in keys {
+            refs.remove(&key);
+        }
+
+        for (file_hash, ref_count) in &counts {
+            let key = StorableString(file_hash.clone());
+            let size_bytes = get_file_blob_safe(file_hash).map(|file_data| file_data.len() as u64);
+            if let Some(size_bytes) = size_bytes {
+                refs.insert(key, StorableFileBlobRecord(FileBlobRecord { ref_count: *ref_count, size_bytes }));
+            }
+        }
+    });
+
+    counts.len() as u64
+}
+
 // User profile helper functions
 pub fn get_user_profile_safe(user_identity: &Principal) -> Option<UserProfile> {
     USER_PROFILES.with(|profiles| {
@@ -269,6 +985,100 @@ pub fn update_user_profile_safe(user_identity: &Principal, profile: &UserProfile
 }
 
 
+// Upload session helper functions
+pub fn get_upload_session_safe(session_id: &str) -> Option<UploadSession> {
+    UPLOAD_SESSIONS.with(|sessions| {
+        sessions.borrow().get(&StorableString(session_id.to_string()))
+            .map(|storable_session| storable_session.0)
+    })
+}
+
+// Store a new upload session; never overwrites an existing one
+pub fn insert_upload_session_safe(session_id: &str, session: &UploadSession) -> Result<(), String> {
+    if get_upload_session_safe(session_id).is_some() {
+        return Err(format!("Upload session {} already exists", session_id));
+    }
+    update_upload_session_safe(session_id, session)
+}
+
+pub fn update_upload_session_safe(session_id: &str, session: &UploadSession) -> Result<(), String> {
+    if session_id.is_empty() {
+        return Err("Upload session ID cannot be empty".to_string());
+    }
+
+    UPLOAD_SESSIONS.with(|sessions| {
+        sessions.borrow_mut().insert(StorableString(session_id.to_string()), StorableUploadSession(session.clone()));
+    });
+    Ok(())
+}
+
+// Chunk keys are zero-padded so that lexicographic order matches upload order
+fn upload_chunk_key(session_id: &str, chunk_index: u32) -> StorableString {
+    StorableString(format!("{}:{:010}", session_id, chunk_index))
+}
+
+fn upload_chunk_keys(session_id: &str) -> Vec<StorableString> {
+    let prefix = format!("{}:", session_id);
+    UPLOAD_CHUNKS.with(|chunks| {
+        chunks.borrow().range(StorableString(prefix.clone())..)
+            .take_while(|(key, _)| key.0.starts_with(&prefix))
+            .map(|(key, _)| key)
+            .collect()
+    })
+}
+
+pub fn store_upload_chunk(session_id: &str, chunk_index: u32, chunk: Vec<u8>) {
+    UPLOAD_CHUNKS.with(|chunks| {
+        chunks.borrow_mut().insert(upload_chunk_key(session_id, chunk_index), StorableBlob(chunk));
+    });
+}
+
+// Reassemble all chunks of a session in upload order
+pub fn assemble_upload_chunks(session_id: &str) -> Vec<u8> {
+    let keys = upload_chunk_keys(session_id);
+    UPLOAD_CHUNKS.with(|chunks| {
+        let chunks = chunks.borrow();
+        let mut file_data = Vec::new();
+        for key in keys {
+            if let Some(chunk) = chunks.get(&key) {
+                file_data.extend_from_slice(&chunk.0);
+            }
+        }
+        file_data
+    })
+}
+
+// Remove an upload session together with all of its chunks
+pub fn remove_upload_session(session_id: &str) -> Option<UploadSession> {
+    let keys = upload_chunk_keys(session_id);
+    UPLOAD_CHUNKS.with(|chunks| {
+        let mut chunks = chunks.borrow_mut();
+        for key in keys {
+            chunks.remove(&key);
+        }
+    });
+
+    UPLOAD_SESSIONS.with(|sessions| {
+        sessions.borrow_mut().remove(&StorableString(session_id.to_string()))
+            .map(|storable_session| storable_session.0)
+    })
+}
+
+// Remove sessions with no activity since `cutoff` (nanoseconds); returns number removed
+pub fn remove_upload_sessions_inactive_since(cutoff: u64) -> usize {
+    let expired_ids: Vec<String> = UPLOAD_SESSIONS.with(|sessions| {
+        sessions.borrow().iter()
+            .filter(|(_, session)| session.0.last_activity < cutoff)
+            .map(|(key, _)| key.0)
+            .collect()
+    });
+
+    for session_id in &expired_ids {
+        remove_upload_session(session_id);
+    }
+
+    expired_ids.len()
+}
 
 // Function to get storage statistics for monitoring
 pub fn get_storage_stats() -> StorageStats {
@@ -281,15 +1091,53 @@ pub fn get_storage_stats() -> StorageStats {
         let mut total_size = 0;
         for (_, doc) in storage.borrow().iter() {
             count += 1;
-            total_size += doc.0.file_data.len() as u64;
+            total_size += doc.0.file_size;
         }
         (count, total_size)
     });
     
+    // Physical size counts each distinct file once
+    let (unique_file_count, physical_file_size) = FILE_BLOB_REFS.with(|refs| {
+        let refs = refs.borrow();
+        let total_size = refs.iter().map(|(_, record)| record.0.size_bytes).sum();
+        (refs.len(), total_size)
+    });
+    
     StorageStats {
         document_count: document_count as u64,
-        institution_count: institution_count as u64,
-        user_profile_count: user_profile_count as u64,
+        institution_count,
+        user_profile_count,
         total_file_size_bytes: total_file_size,
+        physical_file_size_bytes: physical_file_size,
+        unique_file_count,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const CHUNK: usize = FILE_BLOB_CHUNK_SIZE_BYTES;
+
+    fn file_data(size: usize) -> Vec<u8> {
+        (0..size).map(|i| (i % 251) as u8).collect()
+    }
+
+    #[test]
+    fn ranged_reads_span_piece_boundaries() {
+        let data = file_data(2 * CHUNK + 123);
+        assert_eq!(store_file_blob_if_missing("range", data.clone()), Some(data.len() as u64));
+        assert_eq!(file_blob_chunk_keys("range").len(), 3);
+
+        for (start, end) in [(0, 10), (CHUNK - 5, CHUNK + 5), (10, 2 * CHUNK + 100), (2 * CHUNK, data.len()), (data.len(), data.len())] {
+            assert_eq!(read_file_blob_range("range", start as u64, end as u64).unwrap(), data[start..end]);
+        }
+        assert_eq!(read_file_blob_range("range", 0, data.len() as u64 + 1), None);
+        assert_eq!(read_file_blob_range("range", 10, 5), None);
+        assert_eq!(get_file_blob_safe("range").unwrap(), data);
+
+        remove_file_blob("range");
+        assert!(!file_blob_exists("range"));
+        assert_eq!(read_file_blob_range("range", 0, 1), None);
     }
-}
\ No newline at end of file
+}
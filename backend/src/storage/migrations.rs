@@ -0,0 +1,219 @@
+// One-time storage migrations
+// Each migration runs once after an upgrade and bumps SCHEMA_VERSION when complete;
+// work that does not fit in the upgrade is queued and finished from a timer
+
+use std::time::Duration;
+use super::memory::{DOCUMENTS, DOCUMENT_REWRITES, PDF_METADATA, PDF_SIGNATURE_BACKFILL, SCHEMA_VERSION, StorableString, get_document_file_safe, get_document_safe, store_document_safe, store_pdf_metadata_safe, rebuild_file_blob_refs, rebuild_hash_index, remove_upload_sessions_inactive_since};
+use crate::utils::helpers::calculate_file_hash;
+use crate::logging::{get_logger, get_severity_for_event_type};
+
+// A migration tick stops taking documents once it has used this many instructions;
+// well below the per-message limit, so the document being processed when it is crossed still fits
+const MIGRATION_INSTRUCTION_BUDGET: u64 = 10_000_000_000;
+
+// Schema version written by the current code
+// 1: file data moved out of DOCUMENTS into FILE_BLOBS
+// 2: reference counts for shared FILE_BLOBS entries
+// 3: file hash to document index
+// 4: versioned document records (adds retraction status)
+// 5: document amendments; upload sessions embed versioned document records
+// 6: financial statement amounts as exact decimals with currency and scale
+// 7: versioned PDF metadata records with embedded signatures
+pub const CURRENT_SCHEMA_VERSION: u32 = 7;
+
+// Helper function for logging migration events
+fn log_migration_event(message: &str) {
+    let logger = get_logger("migrations");
+    let severity = get_severity_for_event_type("STORAGE_MIGRATION");
+    logger.log(severity, "STORAGE_MIGRATION", message, None);
+}
+
+pub fn get_schema_version() -> u32 {
+    SCHEMA_VERSION.with(|version| *version.borrow().get())
+}
+
+fn set_schema_version(version: u32) {
+    SCHEMA_VERSION.with(|cell| {
+        if let Err(e) = cell.borrow_mut().set(version) {
+            log_migration_event(&format!("Failed to persist schema version {}: {:?}", version, e));
+        }
+    });
+}
+
+// Fresh canisters start at the current schema and need no migrations
+pub fn mark_schema_current() {
+    set_schema_version(CURRENT_SCHEMA_VERSION);
+}
+
+// Run every migration newer than the stored schema version
+pub fn run_pending_migrations() {
+    let version = get_schema_version();
+
+    if version < 1 {
+        // Copying every file does not fit in the upgrade; records keep their file data inline
+        // until start_document_rewrites stores them again
+        let queued = queue_document_rewrites();
+        log_migration_event(&format!("Queued {} documents for moving their file data to blob storage", queued));
+        set_schema_version(1);
+    }
+
+    if version < 2 {
+        // Files still held inline are counted once the rewrite queue is empty
+        let unique_files = rebuild_file_blob_refs();
+        log_migration_event(&format!("Rebuilt reference counts for {} unique files", unique_files));
+        set_schema_version(2);
+    }
+
+    if version < 3 {
+        let indexed = rebuild_hash_index();
+        log_migration_event(&format!("Indexed file hashes of {} documents", indexed));
+        set_schema_version(3);
+    }
+
+    if version < 4 {
+        // Unversioned documents are still readable; in-flight upload sessions embed the old
+        // document layout and cannot be resumed, so their uploads have to be restarted
+        let dropped = remove_upload_sessions_inactive_since(u64::MAX);
+        log_migration_event(&format!("Dropped {} upload sessions started before document record versioning", dropped));
+        set_schema_version(4);
+    }
+
+    if version < 5 {
+        // Sessions from here on store their metadata as a versioned document record,
+        // so later Document changes no longer invalidate in-flight uploads
+        let dropped = remove_upload_sessions_inactive_since(u64::MAX);
+        log_migration_event(&format!("Dropped {} upload sessions with embedded unversioned metadata", dropped));
+        set_schema_version(5);
+    }
+
+    if version < 6 {
+        // Older records still decode, but converting their float amounts on every read would let
+        // the result drift with the conversion code; rewrite them once in the current layout.
+        // Their currency was never captured and stays empty until the publisher amends them
+        let queued = queue_document_rewrites();
+        log_migration_event(&format!("Queued {} documents for rewriting with decimal financial amounts", queued));
+        set_schema_version(6);
+    }
+
+    if version < 7 {
+        // Metadata in the previous layout still decodes, without signatures; reading them means parsing
+        // every PDF again, which does not fit in the upgrade, so it is queued for start_pdf_signature_backfill
+        let queued = queue_pdf_signature_backfill();
+        log_migration_event(&format!("Queued {} PDF documents for signature backfill", queued));
+        set_schema_version(7);
+    }
+}
+
+// v1: older canisters stored file_data inline in each DOCUMENTS record;
+// storing a record again moves its file data to FILE_BLOBS
+// v6: records decode through the legacy layouts and are stored again in the current one
+fn queue_document_rewrites() -> u64 {
+    let document_ids: Vec<StorableString> = DOCUMENTS.with(|storage| storage.borrow().keys().collect());
+    DOCUMENT_REWRITES.with(|queue| {
+        let mut queue = queue.borrow_mut();
+        for document_id in &document_ids {
+            queue.insert(document_id.clone(), ());
+        }
+    });
+    document_ids.len() as u64
+}
+
+/// Store queued documents again from a timer, one batch per tick, until the queue is empty
+pub fn start_document_rewrites() {
+    if DOCUMENT_REWRITES.with(|queue| queue.borrow().is_empty()) {
+        return;
+    }
+    ic_cdk_timers::set_timer(Duration::ZERO, || {
+        let rewritten = rewrite_queued_documents();
+        log_migration_event(&format!("Rewrote {} documents in the current layout", rewritten));
+        if DOCUMENT_REWRITES.with(|queue| queue.borrow().is_empty()) {
+            // Files moved out of inline records were stored without a reference count
+            let unique_files = rebuild_file_blob_refs();
+            log_migration_event(&format!("Rebuilt reference counts for {} unique files", unique_files));
+        }
+        start_document_rewrites();
+    });
+}
+
+fn rewrite_queued_documents() -> u64 {
+    let mut rewritten = 0;
+    while ic_cdk::api::instruction_counter() < MIGRATION_INSTRUCTION_BUDGET {
+        let Some(document_id) = DOCUMENT_REWRITES.with(|queue| queue.borrow_mut().pop_first()).map(|(key, _)| key.0) else {
+            break;
+        };
+
+        match rewrite_document(&document_id) {
+            Ok(()) => rewritten += 1,
+            Err(e) => log_migration_event(&format!("Failed to rewrite document {}: {}", document_id, e)),
+        }
+    }
+
+    rewritten
+}
+
+fn rewrite_document(document_id: &str) -> Result<(), String> {
+    let mut document = get_document_safe(document_id).ok_or("Document not found")?;
+    // Records whose amounts have no decimal equivalent decode as an empty document;
+    // they are left in their old layout rather than overwritten
+    if document.document_id != document_id {
+        return Err("its amounts are not finite or out of range".to_string());
+    }
+
+    if !document.file_data.is_empty() {
+        if document.file_hash.is_empty() {
+            document.file_hash = calculate_file_hash(&document.file_data);
+        }
+        document.file_size = document.file_data.len() as u64;
+    }
+
+    store_document_safe(document_id, &document)
+}
+
+// v7: PdfMetadata gained the signatures of the file; every record stored so far lacks them
+fn queue_pdf_signature_backfill() -> u64 {
+    let document_ids: Vec<StorableString> = PDF_METADATA.with(|entries| entries.borrow().keys().collect());
+    PDF_SIGNATURE_BACKFILL.with(|queue| {
+        let mut queue = queue.borrow_mut();
+        for document_id in &document_ids {
+            queue.insert(document_id.clone(), ());
+        }
+    });
+    document_ids.len() as u64
+}
+
+/// Read the signatures of queued PDFs from a timer, one batch per tick, until the queue is empty
+pub fn start_pdf_signature_backfill() {
+    if PDF_SIGNATURE_BACKFILL.with(|queue| queue.borrow().is_empty()) {
+        return;
+    }
+    ic_cdk_timers::set_timer(Duration::ZERO, || {
+        let rebuilt = backfill_pdf_signatures();
+        log_migration_event(&format!("Backfilled PDF signatures for {} documents", rebuilt));
+        start_pdf_signature_backfill();
+    });
+}
+
+fn backfill_pdf_signatures() -> u64 {
+    let mut rebuilt = 0;
+    while ic_cdk::api::instruction_counter() < MIGRATION_INSTRUCTION_BUDGET {
+        let Some(document_id) = PDF_SIGNATURE_BACKFILL.with(|queue| queue.borrow_mut().pop_first()).map(|(key, _)| key.0) else {
+            break;
+        };
+
+        // Files purged by a retraction cannot be read again; their entry keeps its other fields
+        let metadata = get_document_file_safe(&document_id)
+            .ok_or_else(|| "file data is not available".to_string())
+            .and_then(|file_data| crate::pdf::read_pdf_metadata(&file_data));
+        match metadata {
+            Ok(mut metadata) => {
+                metadata.document_id = document_id;
+                store_pdf_metadata_safe(&metadata);
+                rebuilt += 1;
+            }
+            Err(e) => log_migration_event(&format!("Kept PDF metadata of document {} without signatures: {}", document_id, e)),
+        }
+    }
+
+    rebuilt
+}
+
@@ -1,3 +1,6 @@
 pub mod memory;
+pub mod legacy;
+pub mod migrations;
 
-pub use memory::*; 
\ No newline at end of file
+pub use memory::*;
+pub use migrations::*;
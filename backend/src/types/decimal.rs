@@ -0,0 +1,232 @@
+use candid::CandidType;
+use serde::{Deserialize, Deserializer, Serialize, Serializer};
+use std::fmt;
+use std::str::FromStr;
+
+/// Exact decimal amount with four fractional digits, stored as ten-thousandths (1.5 is 15000)
+// Candid exposes the scaled integer; JSON shows the decimal text, bincode stores the integer
+#[derive(CandidType, Clone, Copy, Debug, Default, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct Decimal(pub i64);
+
+impl Decimal {
+    pub const DECIMAL_PLACES: u32 = 4;
+    const SCALE: i64 = 10_i64.pow(Self::DECIMAL_PLACES);
+
+    pub const ZERO: Decimal = Decimal(0);
+    pub const ONE: Decimal = Decimal(Self::SCALE);
+
+    /// Nearest representable amount; NaN, infinity and out-of-range values are rejected
+    pub fn from_f64(value: f64) -> Result<Self, String> {
+        let scaled = (value * Self::SCALE as f64).round();
+        if !scaled.is_finite() {
+            return Err(format!("Amount {} is not a finite number", value));
+        }
+        // i64::MAX is not exactly representable; the nearest f64 above it is 2^63
+        if scaled < i64::MIN as f64 || scaled >= i64::MAX as f64 {
+            return Err(format!("Amount {} is out of range", value));
+        }
+        Ok(Decimal(scaled as i64))
+    }
+
+    pub fn to_f64(self) -> f64 {
+        self.0 as f64 / Self::SCALE as f64
+    }
+
+    pub fn is_zero(self) -> bool {
+        self.0 == 0
+    }
+
+    pub fn is_negative(self) -> bool {
+        self.0 < 0
+    }
+
+    pub fn checked_add(self, other: Decimal) -> Option<Decimal> {
+        self.0.checked_add(other.0).map(Decimal)
+    }
+
+    pub fn checked_sub(self, other: Decimal) -> Option<Decimal> {
+        self.0.checked_sub(other.0).map(Decimal)
+    }
+
+    pub fn checked_mul(self, factor: i64) -> Option<Decimal> {
+        self.0.checked_mul(factor).map(Decimal)
+    }
+
+    /// Divide, rounding half away from zero to the nearest representable amount
+    pub fn div_rounded(self, divisor: i64) -> Option<Decimal> {
+        if divisor == 0 {
+            return None;
+        }
+        let (value, divisor) = (self.0 as i128, divisor as i128);
+        let quotient = value / divisor;
+        let remainder = value % divisor;
+        let rounded = if 2 * remainder.abs() >= divisor.abs() {
+            quotient + if (value < 0) == (divisor < 0) { 1 } else { -1 }
+        } else {
+            quotient
+        };
+        i64::try_from(rounded).ok().map(Decimal)
+    }
+
+    /// Parse decimal notation, rounding extra fractional digits half away from zero
+    pub fn parse_rounded(text: &str) -> Result<Decimal, String> {
+        let trimmed = text.trim();
+        let places = Self::DECIMAL_PLACES as usize;
+        let Some((integer_part, fraction_digits)) = trimmed.split_once('.') else {
+            return trimmed.parse();
+        };
+        if fraction_digits.len() <= places || !fraction_digits.bytes().all(|b| b.is_ascii_digit()) {
+            return trimmed.parse();
+        }
+
+        let truncated: Decimal = format!("{}.{}", integer_part, &fraction_digits[..places]).parse()?;
+        if fraction_digits.as_bytes()[places] < b'5' {
+            return Ok(truncated);
+        }
+        let step = if trimmed.starts_with('-') { -1 } else { 1 };
+        truncated.0.checked_add(step).map(Decimal).ok_or_else(|| format!("Amount '{}' is out of range", text))
+    }
+}
+
+impl fmt::Display for Decimal {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let sign = if self.0 < 0 { "-" } else { "" };
+        let magnitude = self.0.unsigned_abs();
+        let scale = Self::SCALE as u64;
+        let integer = magnitude / scale;
+        let fraction = magnitude % scale;
+
+        if fraction == 0 {
+            return write!(f, "{}{}", sign, integer);
+        }
+
+        let digits = format!("{:0width$}", fraction, width = Self::DECIMAL_PLACES as usize);
+        write!(f, "{}{}.{}", sign, integer, digits.trim_end_matches('0'))
+    }
+}
+
+impl FromStr for Decimal {
+    type Err = String;
+
+    /// Parse plain decimal notation ("-1250.5"); extra fractional digits are only accepted if zero
+    fn from_str(text: &str) -> Result<Self, Self::Err> {
+        let invalid = || format!("Invalid decimal amount: '{}'", text);
+        let trimmed = text.trim();
+
+        let (negative, unsigned) = match trimmed.as_bytes().first() {
+            Some(b'-') => (true, &trimmed[1..]),
+            Some(b'+') => (false, &trimmed[1..]),
+            _ => (false, trimmed),
+        };
+
+        let (integer_digits, fraction_digits) = unsigned.split_once('.').unwrap_or((unsigned, ""));
+        if integer_digits.is_empty() && fraction_digits.is_empty() {
+            return Err(invalid());
+        }
+        if !integer_digits.bytes().all(|b| b.is_ascii_digit()) || !fraction_digits.bytes().all(|b| b.is_ascii_digit()) {
+            return Err(invalid());
+        }
+
+        let places = Self::DECIMAL_PLACES as usize;
+        if fraction_digits.len() > places && fraction_digits[places..].bytes().any(|b| b != b'0') {
+            return Err(format!("Amount '{}' has more than {} decimal places", text, places));
+        }
+
+        let mut scaled: i64 = 0;
+        let fraction_padded = fraction_digits.bytes().chain(std::iter::repeat(b'0')).take(places);
+        for digit in integer_digits.bytes().chain(fraction_padded) {
+            scaled = scaled
+                .checked_mul(10)
+                .and_then(|value| value.checked_add((digit - b'0') as i64))
+                .ok_or_else(|| format!("Amount '{}' is out of range", text))?;
+        }
+
+        Ok(Decimal(if negative { -scaled } else { scaled }))
+    }
+}
+
+impl Serialize for Decimal {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        if serializer.is_human_readable() {
+            serializer.collect_str(self)
+        } else {
+            serializer.serialize_i64(self.0)
+        }
+    }
+}
+
+impl<'de> Deserialize<'de> for Decimal {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        if deserializer.is_human_readable() {
+            let text = String::deserialize(deserializer)?;
+            text.parse().map_err(serde::de::Error::custom)
+        } else {
+            i64::deserialize(deserializer).map(Decimal)
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parse_rounded_rounds_half_away_from_zero() {
+        assert_eq!(Decimal::parse_rounded("1.23445"), Ok(Decimal(12_345)));
+        assert_eq!(Decimal::parse_rounded("1.23444999"), Ok(Decimal(12_344)));
+        assert_eq!(Decimal::parse_rounded("-1.23445"), Ok(Decimal(-12_345)));
+        assert_eq!(Decimal::parse_rounded("-0.00005"), Ok(Decimal(-1)));
+        assert_eq!(Decimal::parse_rounded("0.99995"), Ok(Decimal::ONE));
+    }
+
+    #[test]
+    fn parse_rounded_keeps_exact_amounts() {
+        assert_eq!(Decimal::parse_rounded(" 1250.5 "), Ok(Decimal(12_505_000)));
+        assert_eq!(Decimal::parse_rounded("-42"), Ok(Decimal(-420_000)));
+        assert_eq!(Decimal::parse_rounded("+0.1234"), Ok(Decimal(1_234)));
+    }
+
+    #[test]
+    fn parse_rounded_rejects_invalid_and_out_of_range_amounts() {
+        assert!(Decimal::parse_rounded("").is_err());
+        assert!(Decimal::parse_rounded("1.2.3").is_err());
+        assert!(Decimal::parse_rounded("1,000").is_err());
+        assert!(Decimal::parse_rounded("1e6").is_err());
+        assert!(Decimal::parse_rounded("1.23456x").is_err());
+        assert!(Decimal::parse_rounded("922337203685477.5808").is_err());
+        // Rounding up past the largest amount overflows
+        assert!(Decimal::parse_rounded("922337203685477.58075").is_err());
+        assert_eq!(Decimal::parse_rounded("922337203685477.58074"), Ok(Decimal(i64::MAX)));
+    }
+
+    #[test]
+    fn div_rounded_rounds_half_away_from_zero() {
+        assert_eq!(Decimal(15).div_rounded(10), Some(Decimal(2)));
+        assert_eq!(Decimal(14).div_rounded(10), Some(Decimal(1)));
+        assert_eq!(Decimal(-15).div_rounded(10), Some(Decimal(-2)));
+        assert_eq!(Decimal(15).div_rounded(-10), Some(Decimal(-2)));
+        assert_eq!(Decimal(-15).div_rounded(-10), Some(Decimal(2)));
+        assert_eq!(Decimal(-14).div_rounded(10), Some(Decimal(-1)));
+        assert_eq!(Decimal(1_234_567_890).div_rounded(1_000), Some(Decimal(1_234_568)));
+    }
+
+    #[test]
+    fn div_rounded_handles_zero_and_extreme_divisors() {
+        assert_eq!(Decimal(15).div_rounded(0), None);
+        assert_eq!(Decimal(i64::MIN).div_rounded(-1), None);
+        assert_eq!(Decimal(i64::MIN).div_rounded(1), Some(Decimal(i64::MIN)));
+        assert_eq!(Decimal(i64::MAX).div_rounded(i64::MAX), Some(Decimal(1)));
+        assert_eq!(Decimal(i64::MAX).div_rounded(i64::MIN), Some(Decimal(-1)));
+    }
+
+    #[test]
+    fn from_f64_rejects_non_finite_and_out_of_range_values() {
+        assert_eq!(Decimal::from_f64(1.23456), Ok(Decimal(12_346)));
+        assert_eq!(Decimal::from_f64(-0.00005), Ok(Decimal(-1)));
+        assert!(Decimal::from_f64(f64::NAN).is_err());
+        assert!(Decimal::from_f64(f64::INFINITY).is_err());
+        assert!(Decimal::from_f64(f64::NEG_INFINITY).is_err());
+        assert!(Decimal::from_f64(1e16).is_err());
+        assert!(Decimal::from_f64(-1e16).is_err());
+    }
+}
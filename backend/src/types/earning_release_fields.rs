@@ -0,0 +1,152 @@
+// Earning release fields filled from uploaded files, shared by the XBRL and spreadsheet readers
+
+use super::{ConsolidatedBalanceSheetData, ConsolidatedCashFlowData, ConsolidatedIncomeData, Decimal, PerShareData};
+
+/// Where a field's value goes; cash flows are reported in the scale of the income statement
+#[derive(Clone, Copy)]
+pub enum FieldTarget {
+    Income(fn(&mut ConsolidatedIncomeData) -> &mut Decimal),
+    Balance(fn(&mut ConsolidatedBalanceSheetData) -> &mut Decimal),
+    CashFlow(fn(&mut ConsolidatedCashFlowData) -> &mut f64),
+    PerShare(fn(&mut PerShareData) -> &mut f64),
+}
+
+pub struct EarningReleaseField {
+    pub name: &'static str,                     // dotted path, e.g. "consolidated_income_data.revenue"
+    pub target: FieldTarget,
+    pub xbrl_concepts: &'static [&'static str], // local names in priority order, US GAAP and IFRS
+    pub sheet_labels: &'static [&'static str],  // default row labels, lowercase
+}
+
+impl EarningReleaseField {
+    /// Balance sheet figures are reported at a point in time, everything else over a period
+    pub fn is_instant(&self) -> bool {
+        matches!(self.target, FieldTarget::Balance(_))
+    }
+}
+
+// EBITDA is not a taxonomy concept and has no XBRL source
+pub const EARNING_RELEASE_FIELDS: &[EarningReleaseField] = &[
+    EarningReleaseField {
+        name: "consolidated_income_data.revenue",
+        target: FieldTarget::Income(|income| &mut income.revenue),
+        xbrl_concepts: &["Revenues", "RevenueFromContractWithCustomerExcludingAssessedTax", "SalesRevenueNet", "Revenue", "RevenueFromContractsWithCustomers"],
+        sheet_labels: &["revenue", "revenues", "total revenue", "total revenues", "net sales", "sales"],
+    },
+    EarningReleaseField {
+        name: "consolidated_income_data.cost_of_sales",
+        target: FieldTarget::Income(|income| &mut income.cost_of_sales),
+        xbrl_concepts: &["CostOfRevenue", "CostOfGoodsAndServicesSold", "CostOfGoodsSold", "CostOfSales"],
+        sheet_labels: &["cost of sales", "cost of revenue", "cost of revenues", "cost of goods sold"],
+    },
+    EarningReleaseField {
+        name: "consolidated_income_data.gross_profit",
+        target: FieldTarget::Income(|income| &mut income.gross_profit),
+        xbrl_concepts: &["GrossProfit"],
+        sheet_labels: &["gross profit"],
+    },
+    EarningReleaseField {
+        name: "consolidated_income_data.operating_profit",
+        target: FieldTarget::Income(|income| &mut income.operating_profit),
+        xbrl_concepts: &["OperatingIncomeLoss", "ProfitLossFromOperatingActivities"],
+        sheet_labels: &["operating profit", "operating income", "profit from operations"],
+    },
+    EarningReleaseField {
+        name: "consolidated_income_data.ebitda",
+        target: FieldTarget::Income(|income| &mut income.ebitda),
+        xbrl_concepts: &[],
+        sheet_labels: &["ebitda"],
+    },
+    EarningReleaseField {
+        name: "consolidated_income_data.profit_before_tax",
+        target: FieldTarget::Income(|income| &mut income.profit_before_tax),
+        xbrl_concepts: &[
+            "IncomeLossFromContinuingOperationsBeforeIncomeTaxesExtraordinaryItemsNoncontrollingInterest",
+            "IncomeLossFromContinuingOperationsBeforeIncomeTaxesMinorityInterestAndIncomeLossFromEquityMethodInvestments",
+            "ProfitLossBeforeTax",
+        ],
+        sheet_labels: &["profit before tax", "profit before income tax", "income before tax", "income before income taxes"],
+    },
+    EarningReleaseField {
+        name: "consolidated_income_data.net_profit",
+        target: FieldTarget::Income(|income| &mut income.net_profit),
+        xbrl_concepts: &["NetIncomeLoss", "ProfitLoss", "ProfitLossAttributableToOwnersOfParent"],
+        sheet_labels: &["net profit", "net income", "net profit for the period", "profit for the period"],
+    },
+    EarningReleaseField {
+        name: "consolidated_balance_sheet_data.total_assets",
+        target: FieldTarget::Balance(|balance| &mut balance.total_assets),
+        xbrl_concepts: &["Assets"],
+        sheet_labels: &["total assets"],
+    },
+    EarningReleaseField {
+        name: "consolidated_balance_sheet_data.total_liabilities",
+        target: FieldTarget::Balance(|balance| &mut balance.total_liabilities),
+        xbrl_concepts: &["Liabilities"],
+        sheet_labels: &["total liabilities"],
+    },
+    EarningReleaseField {
+        name: "consolidated_balance_sheet_data.total_equity",
+        target: FieldTarget::Balance(|balance| &mut balance.total_equity),
+        xbrl_concepts: &["StockholdersEquityIncludingPortionAttributableToNoncontrollingInterest", "StockholdersEquity", "Equity"],
+        sheet_labels: &["total equity", "total shareholders' equity", "total stockholders' equity"],
+    },
+    EarningReleaseField {
+        name: "consolidated_balance_sheet_data.total_liabilities_and_equity",
+        target: FieldTarget::Balance(|balance| &mut balance.total_liabilities_and_equity),
+        xbrl_concepts: &["LiabilitiesAndStockholdersEquity", "EquityAndLiabilities"],
+        sheet_labels: &["total liabilities and equity", "total equity and liabilities", "total liabilities and shareholders' equity"],
+    },
+    EarningReleaseField {
+        name: "consolidated_cash_flow_data.operating_cash_flow",
+        target: FieldTarget::CashFlow(|cash_flow| &mut cash_flow.operating_cash_flow),
+        xbrl_concepts: &["NetCashProvidedByUsedInOperatingActivities", "CashFlowsFromUsedInOperatingActivities"],
+        sheet_labels: &["net cash from operating activities", "net cash provided by operating activities", "operating cash flow"],
+    },
+    EarningReleaseField {
+        name: "consolidated_cash_flow_data.investing_cash_flow",
+        target: FieldTarget::CashFlow(|cash_flow| &mut cash_flow.investing_cash_flow),
+        xbrl_concepts: &["NetCashProvidedByUsedInInvestingActivities", "CashFlowsFromUsedInInvestingActivities"],
+        sheet_labels: &["net cash from investing activities", "net cash used in investing activities", "investing cash flow"],
+    },
+    EarningReleaseField {
+        name: "consolidated_cash_flow_data.financing_cash_flow",
+        target: FieldTarget::CashFlow(|cash_flow| &mut cash_flow.financing_cash_flow),
+        xbrl_concepts: &["NetCashProvidedByUsedInFinancingActivities", "CashFlowsFromUsedInFinancingActivities"],
+        sheet_labels: &["net cash from financing activities", "net cash used in financing activities", "financing cash flow"],
+    },
+    EarningReleaseField {
+        name: "consolidated_cash_flow_data.capital_expenditure",
+        target: FieldTarget::CashFlow(|cash_flow| &mut cash_flow.capital_expenditure),
+        xbrl_concepts: &["PaymentsToAcquirePropertyPlantAndEquipment", "PurchaseOfPropertyPlantAndEquipmentClassifiedAsInvestingActivities"],
+        sheet_labels: &["capital expenditure", "capital expenditures", "capex"],
+    },
+    EarningReleaseField {
+        name: "per_share_data.eps_basic",
+        target: FieldTarget::PerShare(|per_share| &mut per_share.eps_basic),
+        xbrl_concepts: &["EarningsPerShareBasic", "BasicEarningsLossPerShare"],
+        sheet_labels: &["basic earnings per share", "earnings per share - basic", "basic eps", "eps basic"],
+    },
+    EarningReleaseField {
+        name: "per_share_data.eps_diluted",
+        target: FieldTarget::PerShare(|per_share| &mut per_share.eps_diluted),
+        xbrl_concepts: &["EarningsPerShareDiluted", "DilutedEarningsLossPerShare"],
+        sheet_labels: &["diluted earnings per share", "earnings per share - diluted", "diluted eps", "eps diluted"],
+    },
+    EarningReleaseField {
+        name: "per_share_data.shares_outstanding",
+        target: FieldTarget::PerShare(|per_share| &mut per_share.shares_outstanding),
+        xbrl_concepts: &["WeightedAverageNumberOfSharesOutstandingBasic", "WeightedAverageShares"],
+        sheet_labels: &["shares outstanding", "weighted average shares outstanding", "number of shares"],
+    },
+    EarningReleaseField {
+        name: "per_share_data.dividends_per_share",
+        target: FieldTarget::PerShare(|per_share| &mut per_share.dividends_per_share),
+        xbrl_concepts: &["CommonStockDividendsPerShareDeclared", "DividendsRecognisedAsDistributionsToOwnersPerShare"],
+        sheet_labels: &["dividends per share", "dividend per share"],
+    },
+];
+
+pub fn find_field(name: &str) -> Option<&'static EarningReleaseField> {
+    EARNING_RELEASE_FIELDS.iter().find(|field| field.name == name)
+}
@@ -1,3 +1,6 @@
+pub mod decimal;
+pub mod earning_release_fields;
 pub mod models;
 
-pub use models::*; 
\ No newline at end of file
+pub use decimal::Decimal;
+pub use models::*;
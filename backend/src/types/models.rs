@@ -1,5 +1,6 @@
 use candid::{CandidType, Deserialize, Principal};
 use serde::Serialize;
+use super::Decimal;
 
 #[derive(CandidType, Serialize, Deserialize, Clone, Debug)]
 pub struct Institution {
@@ -22,9 +23,15 @@ impl Default for Institution {
     }
 }
 
-#[derive(CandidType, Serialize, Deserialize, Clone, Debug, PartialEq)]
+// Stored with bincode, which encodes the variant index: only append new variants
+#[derive(CandidType, Serialize, Deserialize, Clone, Debug, PartialEq, Default)]
 pub enum CollectionCategory {
+    #[default]
     EarningRelease,
+    AnnualReport,
+    DividendAnnouncement,
+    BoardResolution,
+    Prospectus,
 }
 
 #[derive(CandidType, Serialize, Deserialize, Clone, Debug, PartialEq)]
@@ -45,12 +52,6 @@ pub struct UserProfile {
     pub last_login: u64,
 }
 
-impl Default for CollectionCategory {
-    fn default() -> Self {
-        CollectionCategory::EarningRelease
-    }
-}
-
 #[derive(CandidType, Deserialize, Clone, Serialize, Debug)]
 pub struct Document {
     pub institution_id: String, 
@@ -66,6 +67,9 @@ pub struct Document {
     pub file_type: String,    
     pub file_data: Vec<u8>,
     pub publication_date: u64,
+    pub retraction: Option<DocumentRetraction>,
+    pub supersedes: Option<String>, // document_id of the previous version for amendments
+    pub version: u32,               // 1 for original publications, incremented by each amendment
 }
 
 impl Default for Document {
@@ -84,13 +88,31 @@ impl Default for Document {
             file_type: String::default(),
             file_data: Vec::default(),
             publication_date: 0,
+            retraction: None,
+            supersedes: None,
+            version: 1,
         }
     }
 }
 
+// Published documents are never deleted; a retraction keeps the notarized record
+// and states who withdrew it, when and why
+#[derive(CandidType, Deserialize, Clone, Serialize, Debug)]
+pub struct DocumentRetraction {
+    pub retracted_by: Principal,
+    pub retracted_at: u64,
+    pub reason: String,
+    pub file_purged: bool,
+}
+
+// Stored with bincode, which encodes the variant index: only append new variants
 #[derive(CandidType, Serialize, Deserialize, Clone, Debug)]
 pub enum DocumentType {
     EarningRelease(EarningReleaseData),
+    AnnualReport(AnnualReportData),
+    DividendAnnouncement(DividendAnnouncementData),
+    BoardResolution(BoardResolutionData),
+    Prospectus(ProspectusData),
 }
 
 impl Default for DocumentType {
@@ -99,6 +121,30 @@ impl Default for DocumentType {
     }
 }
 
+impl DocumentType {
+    /// Variant name, as used by the document_type filter of query_documents
+    pub fn type_name(&self) -> &'static str {
+        match self {
+            DocumentType::EarningRelease(_) => "EarningRelease",
+            DocumentType::AnnualReport(_) => "AnnualReport",
+            DocumentType::DividendAnnouncement(_) => "DividendAnnouncement",
+            DocumentType::BoardResolution(_) => "BoardResolution",
+            DocumentType::Prospectus(_) => "Prospectus",
+        }
+    }
+
+    /// Collection category a document with this payload belongs to
+    pub fn category(&self) -> CollectionCategory {
+        match self {
+            DocumentType::EarningRelease(_) => CollectionCategory::EarningRelease,
+            DocumentType::AnnualReport(_) => CollectionCategory::AnnualReport,
+            DocumentType::DividendAnnouncement(_) => CollectionCategory::DividendAnnouncement,
+            DocumentType::BoardResolution(_) => CollectionCategory::BoardResolution,
+            DocumentType::Prospectus(_) => CollectionCategory::Prospectus,
+        }
+    }
+}
+
 #[derive(CandidType, Deserialize, Clone, Serialize, Debug, Default)]
 pub struct EarningReleaseData {
     pub earning_release_id: String,
@@ -106,23 +152,124 @@ pub struct EarningReleaseData {
     pub year: u16,
     pub consolidated_income_data: ConsolidatedIncomeData,
     pub consolidated_balance_sheet_data: ConsolidatedBalanceSheetData,
+    pub consolidated_cash_flow_data: ConsolidatedCashFlowData,
+    pub per_share_data: PerShareData,
+}
+
+#[derive(CandidType, Deserialize, Clone, Serialize, Debug, Default)]
+pub struct AnnualReportData {
+    pub annual_report_id: String,
+    pub fiscal_year: u16,
+    pub auditor_name: String,
+    pub audit_opinion: AuditOpinion,
+    pub consolidated_income_data: ConsolidatedIncomeData,
+    pub consolidated_balance_sheet_data: ConsolidatedBalanceSheetData,
+}
+
+#[derive(CandidType, Serialize, Deserialize, Clone, Debug, PartialEq, Default)]
+pub enum AuditOpinion {
+    #[default]
+    Unqualified,
+    Qualified,
+    Adverse,
+    Disclaimer,
+}
+
+#[derive(CandidType, Deserialize, Clone, Serialize, Debug, Default)]
+pub struct DividendAnnouncementData {
+    pub dividend_announcement_id: String,
+    pub fiscal_year: u16,
+    pub dividend_per_share: f64,
+    pub ex_dividend_date: u64, // Dates are nanosecond timestamps, like publication_date
+    pub record_date: u64,
+    pub payment_date: u64,
+}
+
+#[derive(CandidType, Deserialize, Clone, Serialize, Debug, Default)]
+pub struct BoardResolutionData {
+    pub board_resolution_id: String,
+    pub resolution_number: String,
+    pub meeting_date: u64,
+    pub subject: String,
+    pub resolutions: Vec<String>,
+}
+
+#[derive(CandidType, Deserialize, Clone, Serialize, Debug, Default)]
+pub struct ProspectusData {
+    pub prospectus_id: String,
+    pub security_type: String, // e.g. "Ordinary shares", "Bonds"
+    pub offer_price: f64,
+    pub shares_offered: u64,
+    pub offering_amount: f64,
+    pub offering_start_date: u64,
+    pub offering_end_date: u64,
 }
 
 #[derive(CandidType, Serialize, Deserialize, Clone, Debug, Default)]
 pub struct ConsolidatedIncomeData {
-    pub gross_profit: f64,
-    pub operating_profit: f64,
-    pub ebitda: f64,
-    pub profit_before_tax: f64,
-    pub net_profit: f64,
+    pub currency: String, // ISO 4217 code, e.g. "EGP"
+    pub scale: ReportingScale,
+    pub revenue: Decimal,
+    pub cost_of_sales: Decimal,
+    pub gross_profit: Decimal,
+    pub operating_profit: Decimal,
+    pub ebitda: Decimal,
+    pub profit_before_tax: Decimal,
+    pub net_profit: Decimal,
 }
 
 #[derive(CandidType, Serialize, Deserialize, Clone, Debug, Default)]
 pub struct ConsolidatedBalanceSheetData {
-    pub total_assets: f64,
-    pub total_equity: f64,
-    pub total_liabilities: f64,
-    pub total_liabilities_and_equity: f64,
+    pub currency: String,
+    pub scale: ReportingScale,
+    pub total_assets: Decimal,
+    pub total_equity: Decimal,
+    pub total_liabilities: Decimal,
+    pub total_liabilities_and_equity: Decimal,
+}
+
+// Unit the statement amounts are reported in
+#[derive(CandidType, Serialize, Deserialize, Clone, Copy, Debug, PartialEq, Default)]
+pub enum ReportingScale {
+    #[default]
+    Units,
+    Thousands,
+    Millions,
+}
+
+impl ReportingScale {
+    /// Number of units in one reported amount
+    pub fn multiplier(&self) -> i64 {
+        match self {
+            ReportingScale::Units => 1,
+            ReportingScale::Thousands => 1_000,
+            ReportingScale::Millions => 1_000_000,
+        }
+    }
+
+    pub fn label(&self) -> &'static str {
+        match self {
+            ReportingScale::Units => "units",
+            ReportingScale::Thousands => "thousands",
+            ReportingScale::Millions => "millions",
+        }
+    }
+}
+
+#[derive(CandidType, Serialize, Deserialize, Clone, Debug, Default)]
+pub struct ConsolidatedCashFlowData {
+    pub operating_cash_flow: f64,
+    pub investing_cash_flow: f64,
+    pub financing_cash_flow: f64,
+    pub capital_expenditure: f64, // Reported as a positive outflow
+}
+
+#[derive(CandidType, Serialize, Deserialize, Clone, Debug, Default)]
+pub struct PerShareData {
+    pub eps_basic: f64,
+    pub eps_diluted: f64,
+    pub shares_outstanding: f64, // Weighted average, may be fractional
+    pub dividends_per_share: f64,
 }
 
 // Response type
@@ -134,6 +281,16 @@ pub struct DocumentResponse {
     pub file_hash: String,
 }
 
+// Byte range of a document file, returned by chunked downloads
+#[derive(CandidType, Deserialize, Clone, Serialize, Debug, Default)]
+pub struct DocumentFileChunk {
+    pub document_id: String,
+    pub offset: u64,
+    pub total_size: u64,
+    pub chunk: Vec<u8>,
+    pub file_hash: String,
+}
+
 // Document summary for listing documents by owner
 #[derive(CandidType, Deserialize, Clone, Serialize, Debug, Default)]
 pub struct DocumentSummary {
@@ -143,6 +300,226 @@ pub struct DocumentSummary {
     pub publication_date: Option<u64>,
 }
 
+// Changed field between two versions of an earning release (values rendered as text)
+#[derive(CandidType, Deserialize, Clone, Serialize, Debug)]
+pub struct EarningReleaseFieldChange {
+    pub field: String, // dotted path, e.g. "consolidated_income_data.net_profit"
+    pub previous_value: String,
+    pub new_value: String,
+}
+
+// XBRL fact an earning release field was derived from
+#[derive(CandidType, Deserialize, Clone, Serialize, Debug)]
+pub struct XbrlFactSource {
+    pub field: String,      // dotted path, e.g. "consolidated_income_data.net_profit"
+    pub concept: String,    // e.g. "us-gaap:NetIncomeLoss"
+    pub context_id: String,
+    pub unit: String,       // e.g. "iso4217:EGP", empty for non-numeric facts
+    pub value: String,      // value as filed
+}
+
+// Client-supplied value that differed from the filed XBRL (the XBRL value was stored)
+#[derive(CandidType, Deserialize, Clone, Serialize, Debug)]
+pub struct XbrlMismatch {
+    pub field: String,
+    pub client_value: String,
+    pub xbrl_value: String,
+}
+
+// How the structured data of a document published with an XBRL filing was derived
+#[derive(CandidType, Deserialize, Clone, Serialize, Debug, Default)]
+pub struct XbrlReconciliation {
+    pub entity_identifier: String,
+    pub period_end_date: String, // YYYY-MM-DD
+    pub sources: Vec<XbrlFactSource>,
+    pub mismatches: Vec<XbrlMismatch>,
+}
+
+// Where the spreadsheet value of an earning release field is read from (per institution)
+// A cell reference wins over labels; a label matches the text of a row and the value is the first number to its right
+#[derive(CandidType, Deserialize, Clone, Serialize, Debug, Default)]
+pub struct SpreadsheetFieldMapping {
+    pub field: String,                 // dotted path, e.g. "consolidated_income_data.revenue"
+    pub sheet: Option<String>,         // None searches every sheet in workbook order
+    pub cell: Option<String>,          // A1 reference, e.g. "C12"
+    pub labels: Vec<String>,           // row labels, matched case-insensitively
+    pub scale: Option<ReportingScale>, // scale of the sheet figure; None: that of the submitted statements
+}
+
+// Structure of a published PDF, read when it is uploaded
+#[derive(CandidType, Deserialize, Clone, Serialize, Debug, Default)]
+pub struct PdfMetadata {
+    pub document_id: String,
+    pub pdf_version: String,                // header version, e.g. "1.7"
+    pub page_count: u32,
+    pub title: Option<String>,              // Info dictionary fields
+    pub author: Option<String>,
+    pub producer: Option<String>,
+    pub creation_date: Option<u64>,         // nanoseconds since epoch (UTC)
+    pub modification_date: Option<u64>,
+    pub encrypted: bool,
+    pub embedded_files: Vec<String>,        // names in the EmbeddedFiles name tree
+    pub signatures: Vec<PdfSignature>,      // embedded digital signatures in file order
+}
+
+// Outcome of checking an embedded PDF signature
+// Stored with bincode, which encodes the variant index: only append new variants
+#[derive(CandidType, Serialize, Deserialize, Clone, Debug, PartialEq, Eq)]
+pub enum PdfSignatureStatus {
+    IntegrityVerified, // signed bytes are unchanged and the signature verifies with the embedded signer certificate
+    DigestMismatch,    // signed bytes were modified after signing
+    InvalidSignature,  // signature does not verify with the signer certificate
+    Unsupported,       // signature format or algorithm is not supported
+    Malformed,         // ByteRange or CMS structure could not be read
+    OutsideCertificateValidity, // signing time is outside the validity period of the signer certificate
+}
+
+// Embedded PDF signature (CMS/PAdES); the signature and the signer certificate's validity period are checked,
+// the certificate is not validated against trusted roots or revocation lists, so any signer can be named
+#[derive(CandidType, Deserialize, Clone, Serialize, Debug)]
+pub struct PdfSignature {
+    pub field_name: Option<String>,
+    pub sub_filter: Option<String>,         // e.g. "ETSI.CAdES.detached" or "adbe.pkcs7.detached"
+    pub signer_subject: Option<String>,     // RFC 4514 distinguished name of the signer certificate
+    pub signer_issuer: Option<String>,
+    pub signing_time: Option<u64>,          // nanoseconds since epoch; CMS signing time, else the M entry
+    pub digest_algorithm: Option<String>,   // e.g. "SHA-256"
+    pub covers_whole_document: bool,        // false when the file was changed or extended after signing
+    pub status: PdfSignatureStatus,
+    pub status_detail: String,              // reason for any status other than IntegrityVerified
+}
+
+// Filters for searching PDF metadata; unset filters match every document
+#[derive(CandidType, Deserialize, Clone, Serialize, Debug, Default)]
+pub struct PdfMetadataFilter {
+    pub title: Option<String>,              // case-insensitive partial matches
+    pub author: Option<String>,
+    pub producer: Option<String>,
+    pub encrypted: Option<bool>,
+    pub has_embedded_files: Option<bool>,
+    pub min_pages: Option<u32>,
+    pub max_pages: Option<u32>,
+    pub has_signatures: Option<bool>,
+    pub signatures_verified: Option<bool>,  // true: signed and every signature is IntegrityVerified; false: some signature is not
+}
+
+// Text extracted from a document's file when it was published
+#[derive(CandidType, Deserialize, Clone, Serialize, Debug, Default)]
+pub struct DocumentText {
+    pub document_id: String,
+    pub source_format: String, // "PDF", "HTML", "SPREADSHEET" or "TEXT"
+    pub text: String,
+    pub truncated: bool,       // longer texts are cut to a size that fits a query reply
+    pub extracted_at: u64,
+}
+
+// Copy of a published PDF with the notarization footer, built once and served in chunks
+#[derive(Clone, Debug, Default)]
+pub struct StampedFile {
+    pub file_hash: String, // SHA-256 of the stamped copy, not of the original
+    pub data: Vec<u8>,
+}
+
+// Single finding of a data validation
+#[derive(CandidType, Deserialize, Clone, Serialize, Debug)]
+pub struct ValidationIssue {
+    pub code: String,  // stable machine-readable code, e.g. "BALANCE_SHEET_UNBALANCED"
+    pub field: String, // dotted path of the offending field
+    pub message: String,
+}
+
+// Outcome of validating document data; errors block publication, warnings do not
+#[derive(CandidType, Deserialize, Clone, Serialize, Debug, Default)]
+pub struct ValidationReport {
+    pub valid: bool,
+    pub errors: Vec<ValidationIssue>,
+    pub warnings: Vec<ValidationIssue>,
+}
+
+// Chunked upload session (metadata is kept without file data; chunks are stored separately)
+#[derive(CandidType, Serialize, Deserialize, Clone, Debug)]
+pub struct UploadSession {
+    pub session_id: String,
+    pub owner: Principal,
+    #[serde(with = "crate::storage::document_record")]
+    pub metadata: Document,
+    pub chunk_count: u32,
+    pub received_bytes: u64,
+    pub created_at: u64,
+    pub last_activity: u64,
+}
+
+impl Default for UploadSession {
+    fn default() -> Self {
+        Self {
+            session_id: String::default(),
+            owner: Principal::anonymous(),
+            metadata: Document::default(),
+            chunk_count: 0,
+            received_bytes: 0,
+            created_at: 0,
+            last_activity: 0,
+        }
+    }
+}
+
+// Document metadata with an IC data certificate and a Merkle witness for its certified fields
+#[derive(CandidType, Deserialize, Clone, Serialize, Debug, Default)]
+pub struct CertifiedDocumentResponse {
+    pub document: Document,
+    pub certificate: Vec<u8>, // CBOR-encoded IC certificate
+    pub witness: Vec<u8>,     // CBOR-encoded hash tree rooted at the certified data
+}
+
+// Self-contained proof of publication that can be verified offline
+#[derive(CandidType, Deserialize, Clone, Serialize, Debug)]
+pub struct NotarizationReceipt {
+    pub document_id: String,
+    pub file_hash: String,
+    pub publication_date: u64,
+    pub owner: Principal,
+    pub canister_id: Principal,
+    pub retraction: Option<ReceiptRetraction>,
+    pub certificate: Vec<u8>, // CBOR-encoded IC certificate
+    pub witness: Vec<u8>,     // CBOR-encoded hash tree rooted at the certified data
+}
+
+// Certified retraction state of a document (the leaf hash commits to the SHA-256 of the reason)
+#[derive(CandidType, Deserialize, Clone, Serialize, Debug)]
+pub struct ReceiptRetraction {
+    pub retracted_at: u64,
+    pub reason: String,
+    pub file_purged: bool,
+}
+
+// Publication record of a document matching a verified file hash
+#[derive(CandidType, Deserialize, Clone, Serialize, Debug)]
+pub struct HashVerificationRecord {
+    pub document_id: String,
+    pub document_name: String,
+    pub owner: Principal,
+    pub institution_id: String,
+    pub institution_name: String,
+    pub company_name: String,
+    pub publication_date: u64,
+    pub retraction: Option<DocumentRetraction>,
+}
+
+// Result of checking whether a file hash was published
+#[derive(CandidType, Deserialize, Clone, Serialize, Debug, Default)]
+pub struct HashVerificationResult {
+    pub file_hash: String,
+    pub verified: bool,
+    pub documents: Vec<HashVerificationRecord>,
+}
+
+// Reference count and size of a content-addressed file blob
+#[derive(CandidType, Serialize, Deserialize, Clone, Debug, Default)]
+pub struct FileBlobRecord {
+    pub ref_count: u64,
+    pub size_bytes: u64,
+}
+
 // Cycle monitoring data structure
 #[derive(CandidType, Serialize, Deserialize, Clone, Debug)]
 pub struct CycleMonitoringData {
@@ -159,5 +536,7 @@ pub struct StorageStats {
     pub document_count: u64,
     pub institution_count: u64,
     pub user_profile_count: u64,
-    pub total_file_size_bytes: u64,
+    pub total_file_size_bytes: u64,    // Logical size: sum of all documents' file sizes
+    pub physical_file_size_bytes: u64, // Physical size: identical files are stored once
+    pub unique_file_count: u64,
 }
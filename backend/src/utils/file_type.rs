@@ -0,0 +1,449 @@
+// Server-side file type detection from content (magic bytes)
+// The client-declared MIME type is only trusted when it matches what the bytes contain
+
+// MIME types of the supported formats
+pub const MIME_PDF: &str = "application/pdf";
+pub const MIME_PNG: &str = "image/png";
+pub const MIME_JPEG: &str = "image/jpeg";
+pub const MIME_PLAIN_TEXT: &str = "text/plain";
+pub const MIME_XML: &str = "application/xml";
+pub const MIME_TEXT_XML: &str = "text/xml";
+pub const MIME_HTML: &str = "text/html";
+pub const MIME_XHTML: &str = "application/xhtml+xml";
+pub const MIME_XLS: &str = "application/vnd.ms-excel";
+pub const MIME_XLSX: &str = "application/vnd.openxmlformats-officedocument.spreadsheetml.sheet";
+pub const MIME_XLSM: &str = "application/vnd.ms-excel.sheet.macroEnabled.12";
+pub const MIME_XLTM: &str = "application/vnd.ms-excel.template.macroEnabled.12";
+pub const MIME_XLAM: &str = "application/vnd.ms-excel.addin.macroEnabled.12";
+pub const MIME_XLSB: &str = "application/vnd.ms-excel.sheet.binary.macroEnabled.12";
+
+const PDF_MAGIC: &[u8] = b"%PDF-";
+const PNG_MAGIC: &[u8] = b"\x89PNG\r\n\x1a\n";
+const JPEG_MAGIC: &[u8] = b"\xFF\xD8\xFF";
+const ZIP_LOCAL_HEADER_MAGIC: &[u8] = b"PK\x03\x04";
+const ZIP_CENTRAL_HEADER_MAGIC: &[u8] = b"PK\x01\x02";
+const ZIP_END_OF_CENTRAL_DIRECTORY_MAGIC: &[u8] = b"PK\x05\x06";
+const ZIP64_END_OF_CENTRAL_DIRECTORY_MAGIC: &[u8] = b"PK\x06\x06";
+const ZIP64_END_OF_CENTRAL_DIRECTORY_LOCATOR_MAGIC: &[u8] = b"PK\x06\x07";
+const OLE_MAGIC: &[u8] = b"\xD0\xCF\x11\xE0\xA1\xB1\x1A\xE1";
+
+// PDF readers accept the header anywhere in the first 1024 bytes
+const PDF_HEADER_SEARCH_LENGTH: usize = 1024;
+
+// The html root element follows the XML declaration, doctype and comments near the start
+const HTML_ROOT_SEARCH_LENGTH: usize = 1024;
+
+// End of central directory record: 22 bytes plus a comment of up to 65535 bytes
+const ZIP_EOCD_MIN_LENGTH: usize = 22;
+const ZIP_EOCD_SEARCH_LENGTH: usize = ZIP_EOCD_MIN_LENGTH + u16::MAX as usize;
+// The ZIP64 locator sits right before the end of central directory record
+const ZIP64_EOCD_LOCATOR_LENGTH: usize = 20;
+const ZIP_CENTRAL_HEADER_LENGTH: usize = 46;
+
+/// File formats recognised from content
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum DetectedFileType {
+    Pdf,
+    Png,
+    Jpeg,
+    Xlsx,           // OOXML workbook
+    XlsxMacro,      // OOXML workbook with a VBA project (xlsm, xltm or xlam)
+    Xlsb,           // OOXML binary workbook
+    Xls,            // Legacy OLE compound workbook
+    Xml,            // Text starting with an XML declaration or element, e.g. XBRL instances
+    Html,           // HTML or XHTML markup, e.g. inline XBRL filings
+    PlainText,
+}
+
+impl DetectedFileType {
+    /// Canonical MIME type of the detected format
+    pub fn mime_type(&self) -> &'static str {
+        match self {
+            DetectedFileType::Pdf => MIME_PDF,
+            DetectedFileType::Png => MIME_PNG,
+            DetectedFileType::Jpeg => MIME_JPEG,
+            DetectedFileType::Xlsx => MIME_XLSX,
+            DetectedFileType::XlsxMacro => MIME_XLSM,
+            DetectedFileType::Xlsb => MIME_XLSB,
+            DetectedFileType::Xls => MIME_XLS,
+            DetectedFileType::Xml => MIME_XML,
+            DetectedFileType::Html => MIME_HTML,
+            DetectedFileType::PlainText => MIME_PLAIN_TEXT,
+        }
+    }
+
+    /// Declared MIME types consistent with this content
+    /// Macro-enabled workbooks, templates and add-ins share a container and cannot be told apart;
+    /// XML is also plain text, and XHTML is also XML
+    pub fn accepted_mime_types(&self) -> &'static [&'static str] {
+        match self {
+            DetectedFileType::Pdf => &[MIME_PDF],
+            DetectedFileType::Png => &[MIME_PNG],
+            DetectedFileType::Jpeg => &[MIME_JPEG],
+            DetectedFileType::Xlsx => &[MIME_XLSX],
+            DetectedFileType::XlsxMacro => &[MIME_XLSM, MIME_XLTM, MIME_XLAM],
+            DetectedFileType::Xlsb => &[MIME_XLSB],
+            DetectedFileType::Xls => &[MIME_XLS],
+            DetectedFileType::Xml => &[MIME_XML, MIME_TEXT_XML, MIME_PLAIN_TEXT],
+            DetectedFileType::Html => &[MIME_HTML, MIME_XHTML, MIME_XML, MIME_TEXT_XML, MIME_PLAIN_TEXT],
+            DetectedFileType::PlainText => &[MIME_PLAIN_TEXT],
+        }
+    }
+}
+
+/// Detect the file format from its content
+pub fn detect_file_type(data: &[u8]) -> Option<DetectedFileType> {
+    if data.is_empty() {
+        return None;
+    }
+
+    // Fixed signatures first, so an image or workbook containing "%PDF-" near its start is not taken for a PDF
+    if data.starts_with(PNG_MAGIC) {
+        return Some(DetectedFileType::Png);
+    }
+
+    if data.starts_with(JPEG_MAGIC) {
+        return Some(DetectedFileType::Jpeg);
+    }
+
+    if data.starts_with(ZIP_LOCAL_HEADER_MAGIC) {
+        return detect_ooxml_workbook(data);
+    }
+
+    if data.starts_with(OLE_MAGIC) {
+        return detect_ole_workbook(data);
+    }
+
+    let header = &data[..std::cmp::min(data.len(), PDF_HEADER_SEARCH_LENGTH)];
+    if header.windows(PDF_MAGIC.len()).any(|window| window == PDF_MAGIC) {
+        return Some(DetectedFileType::Pdf);
+    }
+
+    if is_plain_text(data) {
+        return Some(match is_xml(data) {
+            true if is_html(data) => DetectedFileType::Html,
+            true => DetectedFileType::Xml,
+            false => DetectedFileType::PlainText,
+        });
+    }
+
+    None
+}
+
+/// Check the declared MIME type against the content
+/// Returns the MIME type to store: the canonical type of the detected format, or plain text when
+/// text content such as markup was declared as plain text and is to be kept as text
+pub fn resolve_file_type(declared: &str, data: &[u8]) -> Result<String, String> {
+    let declared = declared.trim();
+    let detected = detect_file_type(data)
+        .ok_or("Unable to recognise the file contents. Supported formats: PDF, PNG, JPEG, Excel workbooks, XML, HTML and plain text.")?;
+
+    if !detected.accepted_mime_types().contains(&declared) {
+        return Err(format!(
+            "Declared file type '{}' does not match the file contents (detected {})",
+            declared,
+            detected.mime_type()
+        ));
+    }
+
+    if declared == MIME_PLAIN_TEXT {
+        return Ok(MIME_PLAIN_TEXT.to_string());
+    }
+    Ok(detected.mime_type().to_string())
+}
+
+// OOXML packages are zip archives; the workbook part names identify the spreadsheet flavour
+fn detect_ooxml_workbook(data: &[u8]) -> Option<DetectedFileType> {
+    let entries = zip_entry_names(data)?;
+    let has_entry = |name: &str| entries.iter().any(|entry| entry.eq_ignore_ascii_case(name));
+
+    if has_entry("xl/workbook.bin") {
+        Some(DetectedFileType::Xlsb)
+    } else if has_entry("xl/workbook.xml") && has_entry("xl/vbaProject.bin") {
+        Some(DetectedFileType::XlsxMacro)
+    } else if has_entry("xl/workbook.xml") {
+        Some(DetectedFileType::Xlsx)
+    } else {
+        None
+    }
+}
+
+/// Names of the entries in a zip archive, read from its central directory
+pub fn zip_entry_names(data: &[u8]) -> Option<Vec<String>> {
+    if data.len() < ZIP_EOCD_MIN_LENGTH {
+        return None;
+    }
+
+    // Locate the end of central directory record, scanning backwards past any archive comment
+    let search_start = data.len().saturating_sub(ZIP_EOCD_SEARCH_LENGTH);
+    let eocd_offset = (search_start..=data.len() - ZIP_EOCD_MIN_LENGTH)
+        .rev()
+        .find(|&offset| data[offset..].starts_with(ZIP_END_OF_CENTRAL_DIRECTORY_MAGIC))?;
+    let eocd = &data[eocd_offset..];
+
+    let mut entry_count = read_u16(eocd, 10)? as u64;
+    let mut offset = read_u32(eocd, 16)? as u64;
+    // Archives too large for these fields set them to all ones and keep the values in a ZIP64 record
+    if entry_count == u16::MAX as u64 || offset == u32::MAX as u64 {
+        let zip64_eocd = zip64_end_of_central_directory(data, eocd_offset)?;
+        entry_count = read_u64(zip64_eocd, 32)?;
+        offset = read_u64(zip64_eocd, 48)?;
+    }
+
+    // Every entry takes at least a header, which bounds the count of a corrupt directory
+    let mut names = Vec::with_capacity(std::cmp::min(entry_count, (data.len() / ZIP_CENTRAL_HEADER_LENGTH) as u64) as usize);
+    let mut offset = usize::try_from(offset).ok()?;
+    for _ in 0..entry_count {
+        let header = data.get(offset..)?;
+        if !header.starts_with(ZIP_CENTRAL_HEADER_MAGIC) {
+            return None;
+        }
+
+        let name_length = read_u16(header, 28)? as usize;
+        let extra_length = read_u16(header, 30)? as usize;
+        let comment_length = read_u16(header, 32)? as usize;
+        let name = header.get(ZIP_CENTRAL_HEADER_LENGTH..ZIP_CENTRAL_HEADER_LENGTH + name_length)?;
+        names.push(String::from_utf8_lossy(name).into_owned());
+
+        offset += ZIP_CENTRAL_HEADER_LENGTH + name_length + extra_length + comment_length;
+    }
+
+    Some(names)
+}
+
+// The ZIP64 end of central directory record, found through the locator before the classic record
+fn zip64_end_of_central_directory(data: &[u8], eocd_offset: usize) -> Option<&[u8]> {
+    let locator = data.get(eocd_offset.checked_sub(ZIP64_EOCD_LOCATOR_LENGTH)?..eocd_offset)?;
+    if !locator.starts_with(ZIP64_END_OF_CENTRAL_DIRECTORY_LOCATOR_MAGIC) {
+        return None;
+    }
+    let record = data.get(usize::try_from(read_u64(locator, 8)?).ok()?..)?;
+    record.starts_with(ZIP64_END_OF_CENTRAL_DIRECTORY_MAGIC).then_some(record)
+}
+
+// Legacy Excel files are OLE compound documents containing a "Workbook" (or "Book") stream;
+// directory entry names are stored as UTF-16LE
+fn detect_ole_workbook(data: &[u8]) -> Option<DetectedFileType> {
+    let contains_utf16 = |name: &str| {
+        let encoded: Vec<u8> = name.encode_utf16().flat_map(|unit| unit.to_le_bytes()).collect();
+        data.windows(encoded.len()).any(|window| window == encoded.as_slice())
+    };
+
+    if contains_utf16("Workbook") || contains_utf16("Book") {
+        Some(DetectedFileType::Xls)
+    } else {
+        None
+    }
+}
+
+// Valid UTF-8 without control characters other than common whitespace
+fn is_plain_text(data: &[u8]) -> bool {
+    let Ok(text) = std::str::from_utf8(data) else {
+        return false;
+    };
+
+    text.chars().all(|c| !c.is_control() || matches!(c, '\t' | '\n' | '\r' | '\x0C'))
+}
+
+// XML documents start with a declaration or an element, after an optional byte order mark
+fn is_xml(data: &[u8]) -> bool {
+    let data = data.strip_prefix(b"\xEF\xBB\xBF").unwrap_or(data);
+    data.iter().find(|byte| !byte.is_ascii_whitespace()) == Some(&b'<')
+}
+
+// HTML documents open an html element near the start (case-insensitive, prefix allowed)
+fn is_html(data: &[u8]) -> bool {
+    let header = &data[..std::cmp::min(data.len(), HTML_ROOT_SEARCH_LENGTH)];
+    let header = String::from_utf8_lossy(header).to_ascii_lowercase();
+    header.contains("<html") || header.contains(":html") || header.contains("<!doctype html")
+}
+
+fn read_u16(bytes: &[u8], offset: usize) -> Option<u16> {
+    bytes.get(offset..offset + 2).map(|b| u16::from_le_bytes([b[0], b[1]]))
+}
+
+fn read_u32(bytes: &[u8], offset: usize) -> Option<u32> {
+    bytes.get(offset..offset + 4).map(|b| u32::from_le_bytes([b[0], b[1], b[2], b[3]]))
+}
+
+fn read_u64(bytes: &[u8], offset: usize) -> Option<u64> {
+    bytes.get(offset..offset + 8).map(|b| u64::from_le_bytes([b[0], b[1], b[2], b[3], b[4], b[5], b[6], b[7]]))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    // Stored entries without contents; the detector only reads the central directory
+    fn zip_archive(names: &[&str], comment: &[u8], zip64: bool) -> Vec<u8> {
+        let mut data = Vec::new();
+        let mut central_directory = Vec::new();
+        for name in names {
+            let local_offset = data.len() as u32;
+            data.extend_from_slice(ZIP_LOCAL_HEADER_MAGIC);
+            data.extend_from_slice(&[0; 22]);
+            data.extend_from_slice(&(name.len() as u16).to_le_bytes());
+            data.extend_from_slice(&[0; 2]);
+            data.extend_from_slice(name.as_bytes());
+
+            central_directory.extend_from_slice(ZIP_CENTRAL_HEADER_MAGIC);
+            central_directory.extend_from_slice(&[0; 24]);
+            central_directory.extend_from_slice(&(name.len() as u16).to_le_bytes());
+            central_directory.extend_from_slice(&[0; 12]);
+            central_directory.extend_from_slice(&local_offset.to_le_bytes());
+            central_directory.extend_from_slice(name.as_bytes());
+        }
+
+        let directory_offset = data.len() as u64;
+        let directory_size = central_directory.len() as u64;
+        data.extend_from_slice(&central_directory);
+
+        let (entry_count, eocd_directory_offset) = if zip64 {
+            let record_offset = data.len() as u64;
+            data.extend_from_slice(ZIP64_END_OF_CENTRAL_DIRECTORY_MAGIC);
+            data.extend_from_slice(&44u64.to_le_bytes());
+            data.extend_from_slice(&[0; 12]);
+            data.extend_from_slice(&(names.len() as u64).to_le_bytes());
+            data.extend_from_slice(&(names.len() as u64).to_le_bytes());
+            data.extend_from_slice(&directory_size.to_le_bytes());
+            data.extend_from_slice(&directory_offset.to_le_bytes());
+
+            data.extend_from_slice(ZIP64_END_OF_CENTRAL_DIRECTORY_LOCATOR_MAGIC);
+            data.extend_from_slice(&[0; 4]);
+            data.extend_from_slice(&record_offset.to_le_bytes());
+            data.extend_from_slice(&1u32.to_le_bytes());
+            (u16::MAX, u32::MAX)
+        } else {
+            (names.len() as u16, directory_offset as u32)
+        };
+
+        data.extend_from_slice(ZIP_END_OF_CENTRAL_DIRECTORY_MAGIC);
+        data.extend_from_slice(&[0; 4]);
+        data.extend_from_slice(&entry_count.to_le_bytes());
+        data.extend_from_slice(&entry_count.to_le_bytes());
+        data.extend_from_slice(&(directory_size as u32).to_le_bytes());
+        data.extend_from_slice(&eocd_directory_offset.to_le_bytes());
+        data.extend_from_slice(&(comment.len() as u16).to_le_bytes());
+        data.extend_from_slice(comment);
+        data
+    }
+
+    fn workbook(names: &[&str]) -> Vec<u8> {
+        zip_archive(names, b"", false)
+    }
+
+    fn ole_file(stream_name: &str) -> Vec<u8> {
+        let mut data = OLE_MAGIC.to_vec();
+        data.extend_from_slice(&[0; 64]);
+        data.extend(stream_name.encode_utf16().flat_map(|unit| unit.to_le_bytes()));
+        data
+    }
+
+    #[test]
+    fn every_format_is_detected() {
+        let cases: Vec<(Vec<u8>, DetectedFileType)> = vec![
+            (b"%PDF-1.7\n%\xE2\xE3\xCF\xD3\n".to_vec(), DetectedFileType::Pdf),
+            ([PNG_MAGIC, b"\0\0\0\rIHDR"].concat(), DetectedFileType::Png),
+            ([JPEG_MAGIC, b"\xE0\0\x10JFIF"].concat(), DetectedFileType::Jpeg),
+            (workbook(&["[Content_Types].xml", "xl/workbook.xml"]), DetectedFileType::Xlsx),
+            (workbook(&["xl/workbook.xml", "xl/vbaProject.bin"]), DetectedFileType::XlsxMacro),
+            (workbook(&["xl/workbook.bin", "xl/vbaProject.bin"]), DetectedFileType::Xlsb),
+            (ole_file("Workbook"), DetectedFileType::Xls),
+            (ole_file("Book"), DetectedFileType::Xls),
+            (b"\xEF\xBB\xBF<?xml version=\"1.0\"?><xbrli:xbrl/>".to_vec(), DetectedFileType::Xml),
+            (b"<?xml version=\"1.0\"?>\n<html xmlns=\"http://www.w3.org/1999/xhtml\"></html>".to_vec(), DetectedFileType::Html),
+            (b"<!DOCTYPE html><html></html>".to_vec(), DetectedFileType::Html),
+            (b"Revenue\t100\r\nProfit\t20\n".to_vec(), DetectedFileType::PlainText),
+        ];
+        for (data, expected) in cases {
+            assert_eq!(detect_file_type(&data), Some(expected), "{:?}", String::from_utf8_lossy(&data[..8.min(data.len())]));
+        }
+    }
+
+    #[test]
+    fn unrecognised_content_is_not_detected() {
+        assert_eq!(detect_file_type(b""), None);
+        assert_eq!(detect_file_type(b"\x00\x01\x02binary"), None);
+        assert_eq!(detect_file_type(&workbook(&["word/document.xml"])), None);
+        assert_eq!(detect_file_type(&ole_file("WordDocument")), None);
+    }
+
+    #[test]
+    fn signatures_win_over_an_embedded_pdf_header() {
+        let data = [PNG_MAGIC, b"%PDF-1.4"].concat();
+        assert_eq!(detect_file_type(&data), Some(DetectedFileType::Png));
+    }
+
+    #[test]
+    fn entries_are_read_past_an_archive_comment() {
+        let data = zip_archive(&["xl/workbook.xml"], &[b'x'; 300], false);
+        assert_eq!(zip_entry_names(&data).unwrap(), vec!["xl/workbook.xml"]);
+    }
+
+    #[test]
+    fn zip64_archives_are_read() {
+        let data = zip_archive(&["[Content_Types].xml", "xl/workbook.xml"], b"", true);
+        assert_eq!(zip_entry_names(&data).unwrap(), vec!["[Content_Types].xml", "xl/workbook.xml"]);
+        assert_eq!(detect_file_type(&data), Some(DetectedFileType::Xlsx));
+
+        // Without the ZIP64 record the all-ones fields cannot be resolved
+        let mut data = data;
+        let locator = data.windows(4).rposition(|window| window == ZIP64_END_OF_CENTRAL_DIRECTORY_LOCATOR_MAGIC).unwrap();
+        data[locator] = b'X';
+        assert_eq!(zip_entry_names(&data), None);
+    }
+
+    #[test]
+    fn truncated_or_corrupt_central_directories_are_rejected() {
+        let data = workbook(&["xl/workbook.xml"]);
+        let eocd = data.len() - ZIP_EOCD_MIN_LENGTH;
+
+        // Cut before the end of central directory record
+        assert_eq!(zip_entry_names(&data[..eocd]), None);
+        assert_eq!(zip_entry_names(&data[..10]), None);
+
+        // Directory offset pointing past the end or at a local header
+        let mut corrupt = data.clone();
+        corrupt[eocd + 16..eocd + 20].copy_from_slice(&(data.len() as u32 + 100).to_le_bytes());
+        assert_eq!(zip_entry_names(&corrupt), None);
+        let mut corrupt = data.clone();
+        corrupt[eocd + 16..eocd + 20].copy_from_slice(&0u32.to_le_bytes());
+        assert_eq!(zip_entry_names(&corrupt), None);
+
+        // More entries than the directory holds
+        let mut corrupt = data.clone();
+        corrupt[eocd + 10..eocd + 12].copy_from_slice(&2u16.to_le_bytes());
+        assert_eq!(zip_entry_names(&corrupt), None);
+
+        // Name length running past the end of the file
+        let mut corrupt = data.clone();
+        let header = data.windows(4).position(|window| window == ZIP_CENTRAL_HEADER_MAGIC).unwrap();
+        corrupt[header + 28..header + 30].copy_from_slice(&u16::MAX.to_le_bytes());
+        assert_eq!(zip_entry_names(&corrupt), None);
+        assert_eq!(detect_file_type(&corrupt), None);
+    }
+
+    #[test]
+    fn declared_types_must_match_the_content() {
+        let pdf = b"%PDF-1.7\n".to_vec();
+        assert_eq!(resolve_file_type(" application/pdf ", &pdf).unwrap(), MIME_PDF);
+        let error = resolve_file_type(MIME_PNG, &pdf).unwrap_err();
+        assert!(error.contains("'image/png'") && error.contains(MIME_PDF), "{}", error);
+
+        // Workbooks with macros may be declared as any of the macro-enabled types, but not as plain xlsx
+        let macro_workbook = workbook(&["xl/workbook.xml", "xl/vbaProject.bin"]);
+        for declared in [MIME_XLSM, MIME_XLTM, MIME_XLAM] {
+            assert_eq!(resolve_file_type(declared, &macro_workbook).unwrap(), MIME_XLSM);
+        }
+        assert!(resolve_file_type(MIME_XLSX, &macro_workbook).is_err());
+        assert!(resolve_file_type(MIME_XLSM, &workbook(&["xl/workbook.xml"])).is_err());
+
+        // Markup declared as text is kept as text; text cannot be declared as markup
+        let html = b"<html><body>Filing</body></html>".to_vec();
+        assert_eq!(resolve_file_type(MIME_XHTML, &html).unwrap(), MIME_HTML);
+        assert_eq!(resolve_file_type(MIME_PLAIN_TEXT, &html).unwrap(), MIME_PLAIN_TEXT);
+        assert!(resolve_file_type(MIME_HTML, b"plain words").is_err());
+
+        assert!(resolve_file_type(MIME_PDF, b"\x00\x01").unwrap_err().starts_with("Unable to recognise"));
+    }
+}
@@ -13,20 +13,20 @@ pub fn calculate_file_hash(file_data: &[u8]) -> String {
     hex::encode(hasher.finalize())
 }
 
-/// Generate unique token ID using timestamp
+/// Generate unique, time-sortable document ID
 pub fn generate_document_id() -> String {
-    // Use timestamp and a simple counter for unique IDs
-    let timestamp = get_current_timestamp();
-    format!("document_{}", timestamp)
+    super::id_generator::generate_id("document_")
 }
 
-/// Generate unique institution ID using timestamp
+/// Generate unique, time-sortable institution ID
 pub fn generate_institution_id() -> String {
-    // Use timestamp for unique institution IDs
-    let timestamp = get_current_timestamp();
-    format!("INST_{}", timestamp)
+    super::id_generator::generate_id("INST_")
 }
 
+/// Generate unique, time-sortable upload session ID
+pub fn generate_upload_session_id() -> String {
+    super::id_generator::generate_id("upload_")
+}
 
 /// Validate string length with min and max bounds (after trimming whitespace)
 pub fn validate_string_length(value: &str, min: usize, max: usize, field_name: &str) -> Result<(), String> {
@@ -79,7 +79,7 @@ pub fn require_authenticated_user() -> Result<Principal, String> {
 
 /// Get current canister cycles balance
 pub fn get_canister_cycles_balance() -> u128 {
-    ic_cdk::api::canister_balance() as u128
+    ic_cdk::api::canister_cycle_balance()
 }
 
 /// Get cycles status based on balance
@@ -115,6 +115,17 @@ pub fn format_cycles_balance(cycles: u128) -> String {
     }
 }
 
+/// Days since 1970-01-01 of a calendar date (proleptic Gregorian calendar)
+pub fn days_from_civil(year: i64, month: u32, day: u32) -> i64 {
+    let (month, day) = (month as i64, day as i64);
+    let year = if month <= 2 { year - 1 } else { year };
+    let era = year.div_euclid(400);
+    let year_of_era = year - era * 400;
+    let day_of_year = (153 * ((month + 9) % 12) + 2) / 5 + day - 1;
+    let day_of_era = year_of_era * 365 + year_of_era / 4 - year_of_era / 100 + day_of_year;
+    era * 146_097 + day_of_era - 719_468
+}
+
 /// Format timestamp (nanoseconds) to human-readable date and time
 /// Returns format like "Sunday, 21 September 2025 3:08 AM"
 pub fn format_timestamp_to_human_readable(timestamp_nanos: u64) -> String {
@@ -122,7 +133,6 @@ pub fn format_timestamp_to_human_readable(timestamp_nanos: u64) -> String {
     let timestamp_seconds = timestamp_nanos / 1_000_000_000;
     
     // Unix epoch: January 1, 1970, 00:00:00 UTC
-    const UNIX_EPOCH: u64 = 0;
     const SECONDS_PER_DAY: u64 = 86400;
     const SECONDS_PER_HOUR: u64 = 3600;
     const SECONDS_PER_MINUTE: u64 = 60;
@@ -134,7 +144,7 @@ pub fn format_timestamp_to_human_readable(timestamp_nanos: u64) -> String {
     let mut year = 1970;
     let mut remaining_days = days_since_epoch;
     
-    while remaining_days > 365 {
+    while remaining_days >= 365 {
         let is_leap = (year % 4 == 0 && year % 100 != 0) || (year % 400 == 0);
         let days_in_year = if is_leap { 366 } else { 365 };
         if remaining_days >= days_in_year {
@@ -169,7 +179,6 @@ pub fn format_timestamp_to_human_readable(timestamp_nanos: u64) -> String {
     let seconds_in_day = timestamp_seconds % SECONDS_PER_DAY;
     let hour = seconds_in_day / SECONDS_PER_HOUR;
     let minute = (seconds_in_day % SECONDS_PER_HOUR) / SECONDS_PER_MINUTE;
-    let second = seconds_in_day % SECONDS_PER_MINUTE;
     
     // Calculate day of week (Zeller's congruence)
     let mut y = year;
@@ -206,4 +215,38 @@ pub fn format_timestamp_to_human_readable(timestamp_nanos: u64) -> String {
     
     format!("{}, {} {} {} {}:{:02} {}", 
             day_name, day, month_name, year, display_hour, minute, period)
-} 
\ No newline at end of file
+} 
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const NANOS_PER_SECOND: u64 = 1_000_000_000;
+
+    #[test]
+    fn human_readable_dates() {
+        let cases = [
+            (1_758_424_080, "Sunday, 21 September 2025 3:08 AM"),
+            (1_709_220_600, "Thursday, 29 February 2024 3:30 PM"),
+            (1_735_689_600, "Wednesday, 1 January 2025 12:00 AM"),
+        ];
+        for (seconds, expected) in cases {
+            assert_eq!(format_timestamp_to_human_readable(seconds * NANOS_PER_SECOND), expected);
+        }
+    }
+
+    // 365 days into a non-leap year is 1 January of the next; it used to run past December and panic
+    #[test]
+    fn turn_of_the_year() {
+        let cases = [
+            (31_449_600, "Thursday, 31 December 1970 12:00 AM"),
+            (31_536_000, "Friday, 1 January 1971 12:00 AM"),
+            (1_704_024_000, "Sunday, 31 December 2023 12:00 PM"),
+            (1_704_067_200, "Monday, 1 January 2024 12:00 AM"),
+            (1_735_689_540, "Tuesday, 31 December 2024 11:59 PM"),
+        ];
+        for (seconds, expected) in cases {
+            assert_eq!(format_timestamp_to_human_readable(seconds * NANOS_PER_SECOND), expected);
+        }
+    }
+}
@@ -0,0 +1,92 @@
+// Collision-free, time-sortable identifiers
+// IDs are 128-bit values rendered as 26 Crockford base32 characters (like ULIDs):
+// 48 bits of milliseconds since the epoch, 32 bits of a persistent monotonic counter
+// and 48 random bits derived from a raw_rand seed
+
+use std::cell::RefCell;
+use std::time::Duration;
+use sha2::{Digest, Sha256};
+use crate::storage::ID_COUNTER;
+use crate::logging::{get_logger, get_severity_for_event_type};
+
+const CROCKFORD_ALPHABET: &[u8; 32] = b"0123456789ABCDEFGHJKMNPQRSTVWXYZ";
+const ENCODED_ID_LENGTH: usize = 26;
+
+thread_local! {
+    // Seed for the random component; refreshed from raw_rand after init and every upgrade
+    static ID_SEED: RefCell<Option<[u8; 32]>> = const { RefCell::new(None) };
+}
+
+// Advance the persistent counter and return its new value
+fn next_counter() -> u64 {
+    ID_COUNTER.with(|cell| {
+        let mut cell = cell.borrow_mut();
+        let next = cell.get().wrapping_add(1);
+        if let Err(e) = cell.set(next) {
+            ic_cdk::trap(format!("Failed to persist ID counter: {:?}", e));
+        }
+        next
+    })
+}
+
+// Random bits for one ID; until raw_rand has answered, the seed falls back to the
+// canister ID and time, which keeps IDs unique (the counter) but less unpredictable
+fn random_bits(counter: u64) -> u64 {
+    let seed = ID_SEED.with(|seed| *seed.borrow()).unwrap_or_else(|| {
+        let mut hasher = Sha256::new();
+        hasher.update(ic_cdk::api::canister_self().as_slice());
+        hasher.update(ic_cdk::api::time().to_be_bytes());
+        hasher.finalize().into()
+    });
+
+    let mut hasher = Sha256::new();
+    hasher.update(seed);
+    hasher.update(counter.to_be_bytes());
+    let digest = hasher.finalize();
+
+    let mut bytes = [0u8; 8];
+    bytes[2..].copy_from_slice(&digest[..6]);
+    u64::from_be_bytes(bytes)
+}
+
+fn encode_crockford(value: u128) -> String {
+    (0..ENCODED_ID_LENGTH)
+        .rev()
+        .map(|index| CROCKFORD_ALPHABET[((value >> (index * 5)) & 0x1f) as usize] as char)
+        .collect()
+}
+
+/// Generate a new identifier with the given prefix, e.g. "document_01K7..."
+pub fn generate_id(prefix: &str) -> String {
+    let millis = (ic_cdk::api::time() / 1_000_000) as u128 & 0xFFFF_FFFF_FFFF;
+    let counter = next_counter();
+    let random = random_bits(counter) as u128;
+
+    let value = (millis << 80) | (((counter & 0xFFFF_FFFF) as u128) << 48) | random;
+    format!("{}{}", prefix, encode_crockford(value))
+}
+
+async fn refresh_id_seed() {
+    match ic_cdk::management_canister::raw_rand().await {
+        Ok(bytes) if bytes.len() >= 32 => {
+            let mut seed = [0u8; 32];
+            seed.copy_from_slice(&bytes[..32]);
+            ID_SEED.with(|cell| *cell.borrow_mut() = Some(seed));
+        }
+        Ok(_) => log_seed_failure("raw_rand returned fewer than 32 bytes"),
+        Err(e) => log_seed_failure(&format!("raw_rand failed: {:?}", e)),
+    }
+}
+
+fn log_seed_failure(message: &str) {
+    let logger = get_logger("id_generator");
+    let severity = get_severity_for_event_type("ID_SEED_FAILURE");
+    logger.log(severity, "ID_SEED_FAILURE", message, None);
+}
+
+// raw_rand is an inter-canister call, so seeding runs from a timer after init/upgrade
+pub fn start_id_seed_timer() {
+    ic_cdk_timers::set_timer(Duration::ZERO, || {
+        ic_cdk::futures::spawn(refresh_id_seed());
+    });
+}
@@ -1,3 +1,5 @@
 pub mod helpers;
+pub mod id_generator;
+pub mod file_type;
 
 pub use helpers::*; 
\ No newline at end of file
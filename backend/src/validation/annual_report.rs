@@ -0,0 +1,28 @@
+// Consistency checks for annual report data
+
+use crate::types::{AnnualReportData, AuditOpinion, ValidationReport};
+use super::ValidationCollector;
+use super::financial_statements::{validate_financial_statements, validate_year};
+
+/// Validate an annual report; errors make the data unpublishable
+pub fn validate_annual_report_data(data: &AnnualReportData) -> ValidationReport {
+    let mut report = ValidationCollector::default();
+
+    validate_year(&mut report, "fiscal_year", data.fiscal_year);
+
+    if data.auditor_name.trim().is_empty() {
+        report.error("AUDITOR_MISSING", "auditor_name", "Annual reports must name the auditor".to_string());
+    }
+
+    if data.audit_opinion != AuditOpinion::Unqualified {
+        report.warning(
+            "MODIFIED_AUDIT_OPINION",
+            "audit_opinion",
+            format!("The auditor issued a modified opinion ({:?})", data.audit_opinion),
+        );
+    }
+
+    validate_financial_statements(&mut report, &data.consolidated_income_data, &data.consolidated_balance_sheet_data);
+
+    report.finish()
+}
@@ -0,0 +1,29 @@
+// Consistency checks for board resolution data
+
+use crate::types::{BoardResolutionData, ValidationReport};
+use super::ValidationCollector;
+
+/// Validate a board resolution; errors make the data unpublishable
+pub fn validate_board_resolution_data(data: &BoardResolutionData) -> ValidationReport {
+    let mut report = ValidationCollector::default();
+
+    if data.resolution_number.trim().is_empty() {
+        report.error("RESOLUTION_NUMBER_MISSING", "resolution_number", "Resolution number is required".to_string());
+    }
+
+    if data.subject.trim().is_empty() {
+        report.error("SUBJECT_MISSING", "subject", "Resolution subject is required".to_string());
+    }
+
+    if data.meeting_date == 0 {
+        report.error("DATE_MISSING", "meeting_date", "meeting_date is required".to_string());
+    } else if data.meeting_date > crate::utils::get_current_timestamp() {
+        report.error("MEETING_DATE_IN_FUTURE", "meeting_date", "Board meeting date cannot be in the future".to_string());
+    }
+
+    if data.resolutions.iter().all(|resolution| resolution.trim().is_empty()) {
+        report.warning("NO_RESOLUTIONS", "resolutions", "No resolution text was provided".to_string());
+    }
+
+    report.finish()
+}
@@ -0,0 +1,49 @@
+// Consistency checks for dividend announcement data
+
+use crate::types::{DividendAnnouncementData, ValidationReport};
+use super::ValidationCollector;
+use super::financial_statements::{validate_finite, validate_year};
+
+/// Validate a dividend announcement; errors make the data unpublishable
+pub fn validate_dividend_announcement_data(data: &DividendAnnouncementData) -> ValidationReport {
+    let mut report = ValidationCollector::default();
+
+    validate_year(&mut report, "fiscal_year", data.fiscal_year);
+
+    if validate_finite(&mut report, &[("dividend_per_share", data.dividend_per_share)]) && data.dividend_per_share <= 0.0 {
+        report.error(
+            "DIVIDEND_NOT_POSITIVE",
+            "dividend_per_share",
+            format!("Dividend per share must be positive (got {:.4})", data.dividend_per_share),
+        );
+    }
+
+    let dates = [
+        ("ex_dividend_date", data.ex_dividend_date),
+        ("record_date", data.record_date),
+        ("payment_date", data.payment_date),
+    ];
+    for (field, date) in dates {
+        if date == 0 {
+            report.error("DATE_MISSING", field, format!("{} is required", field));
+        }
+    }
+
+    if data.record_date != 0 && data.payment_date != 0 && data.record_date > data.payment_date {
+        report.error(
+            "PAYMENT_BEFORE_RECORD_DATE",
+            "payment_date",
+            "Payment date cannot be before the record date".to_string(),
+        );
+    }
+
+    if data.ex_dividend_date != 0 && data.record_date != 0 && data.ex_dividend_date > data.record_date {
+        report.warning(
+            "EX_DIVIDEND_AFTER_RECORD_DATE",
+            "ex_dividend_date",
+            "Ex-dividend date is usually on or before the record date".to_string(),
+        );
+    }
+
+    report.finish()
+}
@@ -0,0 +1,22 @@
+// Accounting consistency checks for earning release data
+
+use crate::types::{EarningReleaseData, ValidationReport};
+use super::ValidationCollector;
+use super::financial_statements::{validate_cash_flow, validate_financial_statements, validate_per_share, validate_year};
+
+/// Validate an earning release; errors make the data unpublishable
+pub fn validate_earning_release_data(data: &EarningReleaseData) -> ValidationReport {
+    let mut report = ValidationCollector::default();
+
+    // Reporting period
+    if !(1..=4).contains(&data.quarter) {
+        report.error("QUARTER_OUT_OF_RANGE", "quarter", format!("Quarter must be between 1 and 4 (got {})", data.quarter));
+    }
+    validate_year(&mut report, "year", data.year);
+
+    validate_financial_statements(&mut report, &data.consolidated_income_data, &data.consolidated_balance_sheet_data);
+    validate_cash_flow(&mut report, &data.consolidated_cash_flow_data);
+    validate_per_share(&mut report, &data.per_share_data);
+
+    report.finish()
+}
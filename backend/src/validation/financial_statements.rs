@@ -0,0 +1,278 @@
+// Checks shared by documents that carry consolidated financial statements
+
+use crate::types::{ConsolidatedBalanceSheetData, ConsolidatedCashFlowData, ConsolidatedIncomeData, Decimal, PerShareData};
+use super::ValidationCollector;
+
+// Earliest fiscal year accepted for financial documents
+const MIN_PLAUSIBLE_YEAR: u16 = 1900;
+
+// Average Gregorian year in seconds, used to derive the current year from IC time
+const SECONDS_PER_YEAR: u64 = 31_556_952;
+
+// Totals may differ by rounding: 0.05% of the larger amount, and at least one currency unit
+const RELATIVE_TOLERANCE: f64 = 0.0005;
+const ABSOLUTE_TOLERANCE: f64 = 1.0;
+
+// Per-share figures are published with at most a few decimals
+const PER_SHARE_TOLERANCE: f64 = 0.005;
+
+pub fn current_year() -> u16 {
+    let seconds = crate::utils::get_current_timestamp() / 1_000_000_000;
+    (1970 + seconds / SECONDS_PER_YEAR) as u16
+}
+
+pub fn amounts_match(a: f64, b: f64) -> bool {
+    let tolerance = f64::max(ABSOLUTE_TOLERANCE, RELATIVE_TOLERANCE * f64::max(a.abs(), b.abs()));
+    (a - b).abs() <= tolerance
+}
+
+pub fn per_share_amounts_match(a: f64, b: f64) -> bool {
+    (a - b).abs() <= PER_SHARE_TOLERANCE
+}
+
+// Statement amounts are exact decimals, so totals must add up exactly
+fn sum_matches(a: Decimal, b: Decimal, total: Decimal) -> bool {
+    a.checked_add(b) == Some(total)
+}
+
+/// Fiscal years must be between 1900 and next year
+pub fn validate_year(report: &mut ValidationCollector, field: &str, year: u16) {
+    let latest_year = current_year() + 1;
+    if year < MIN_PLAUSIBLE_YEAR || year > latest_year {
+        report.error(
+            "YEAR_OUT_OF_RANGE",
+            field,
+            format!("Year must be between {} and {} (got {})", MIN_PLAUSIBLE_YEAR, latest_year, year),
+        );
+    }
+}
+
+/// Report non-finite amounts; returns false if any were found
+pub fn validate_finite(report: &mut ValidationCollector, amounts: &[(&str, f64)]) -> bool {
+    let mut all_finite = true;
+    for (field, value) in amounts {
+        if !value.is_finite() {
+            report.error("AMOUNT_NOT_FINITE", field, format!("{} must be a finite number", field));
+            all_finite = false;
+        }
+    }
+    all_finite
+}
+
+/// ISO 4217 currency codes are three uppercase letters
+pub fn validate_currency(report: &mut ValidationCollector, field: &str, currency: &str) {
+    if currency.len() != 3 || !currency.bytes().all(|b| b.is_ascii_uppercase()) {
+        report.error(
+            "INVALID_CURRENCY_CODE",
+            field,
+            format!("Currency must be an ISO 4217 code such as EGP or USD (got '{}')", currency),
+        );
+    }
+}
+
+/// Reporting units, balance sheet identities and income statement plausibility
+pub fn validate_financial_statements(
+    report: &mut ValidationCollector,
+    income: &ConsolidatedIncomeData,
+    balance: &ConsolidatedBalanceSheetData,
+) {
+    validate_currency(report, "consolidated_income_data.currency", &income.currency);
+    validate_currency(report, "consolidated_balance_sheet_data.currency", &balance.currency);
+    if income.currency != balance.currency {
+        report.error(
+            "CURRENCY_MISMATCH",
+            "consolidated_balance_sheet_data.currency",
+            format!(
+                "Income statement ({}) and balance sheet ({}) must be reported in the same currency",
+                income.currency, balance.currency
+            ),
+        );
+    }
+
+    validate_balance_sheet(report, balance);
+    validate_income_statement(report, income);
+}
+
+fn validate_balance_sheet(report: &mut ValidationCollector, balance: &ConsolidatedBalanceSheetData) {
+    if balance.total_assets.is_negative() {
+        report.error(
+            "NEGATIVE_TOTAL_ASSETS",
+            "consolidated_balance_sheet_data.total_assets",
+            format!("Total assets cannot be negative (got {})", balance.total_assets),
+        );
+    }
+
+    if balance.total_liabilities.is_negative() {
+        report.error(
+            "NEGATIVE_TOTAL_LIABILITIES",
+            "consolidated_balance_sheet_data.total_liabilities",
+            format!("Total liabilities cannot be negative (got {})", balance.total_liabilities),
+        );
+    }
+
+    if balance.total_assets != balance.total_liabilities_and_equity {
+        report.error(
+            "BALANCE_SHEET_UNBALANCED",
+            "consolidated_balance_sheet_data.total_liabilities_and_equity",
+            format!(
+                "Total assets ({}) must equal total liabilities and equity ({})",
+                balance.total_assets, balance.total_liabilities_and_equity
+            ),
+        );
+    }
+
+    if !sum_matches(balance.total_liabilities, balance.total_equity, balance.total_liabilities_and_equity) {
+        report.error(
+            "LIABILITIES_EQUITY_SUM_MISMATCH",
+            "consolidated_balance_sheet_data.total_liabilities_and_equity",
+            format!(
+                "Total liabilities ({}) plus total equity ({}) must equal total liabilities and equity ({})",
+                balance.total_liabilities, balance.total_equity, balance.total_liabilities_and_equity
+            ),
+        );
+    }
+
+    if balance.total_assets.is_zero() && balance.total_liabilities_and_equity.is_zero() {
+        report.warning(
+            "BALANCE_SHEET_EMPTY",
+            "consolidated_balance_sheet_data",
+            "Balance sheet totals are all zero".to_string(),
+        );
+    }
+}
+
+// Legitimate in rare cases (e.g. large non-operating gains), so only warnings
+fn validate_income_statement(report: &mut ValidationCollector, income: &ConsolidatedIncomeData) {
+    validate_revenue(report, income);
+
+    if income.net_profit > income.gross_profit {
+        report.warning(
+            "NET_PROFIT_EXCEEDS_GROSS_PROFIT",
+            "consolidated_income_data.net_profit",
+            format!("Net profit ({}) is higher than gross profit ({})", income.net_profit, income.gross_profit),
+        );
+    }
+
+    if income.operating_profit > income.gross_profit {
+        report.warning(
+            "OPERATING_PROFIT_EXCEEDS_GROSS_PROFIT",
+            "consolidated_income_data.operating_profit",
+            format!("Operating profit ({}) is higher than gross profit ({})", income.operating_profit, income.gross_profit),
+        );
+    }
+
+    if income.ebitda < income.operating_profit {
+        report.warning(
+            "EBITDA_BELOW_OPERATING_PROFIT",
+            "consolidated_income_data.ebitda",
+            format!("EBITDA ({}) is lower than operating profit ({})", income.ebitda, income.operating_profit),
+        );
+    }
+
+    if income.net_profit > income.profit_before_tax {
+        report.warning(
+            "NET_PROFIT_EXCEEDS_PROFIT_BEFORE_TAX",
+            "consolidated_income_data.net_profit",
+            format!("Net profit ({}) is higher than profit before tax ({})", income.net_profit, income.profit_before_tax),
+        );
+    }
+}
+
+// Revenue and cost of sales are zero on records published before they were reported
+fn validate_revenue(report: &mut ValidationCollector, income: &ConsolidatedIncomeData) {
+    if income.revenue.is_zero() && income.cost_of_sales.is_zero() {
+        return;
+    }
+
+    if income.revenue.is_negative() {
+        report.error(
+            "NEGATIVE_REVENUE",
+            "consolidated_income_data.revenue",
+            format!("Revenue cannot be negative (got {})", income.revenue),
+        );
+    }
+
+    if !sum_matches(income.gross_profit, income.cost_of_sales, income.revenue) {
+        report.error(
+            "GROSS_PROFIT_MISMATCH",
+            "consolidated_income_data.gross_profit",
+            format!(
+                "Revenue ({}) minus cost of sales ({}) must equal gross profit ({})",
+                income.revenue, income.cost_of_sales, income.gross_profit
+            ),
+        );
+    }
+}
+
+/// Cash flow statement plausibility
+pub fn validate_cash_flow(report: &mut ValidationCollector, cash_flow: &ConsolidatedCashFlowData) {
+    let amounts = [
+        ("consolidated_cash_flow_data.operating_cash_flow", cash_flow.operating_cash_flow),
+        ("consolidated_cash_flow_data.investing_cash_flow", cash_flow.investing_cash_flow),
+        ("consolidated_cash_flow_data.financing_cash_flow", cash_flow.financing_cash_flow),
+        ("consolidated_cash_flow_data.capital_expenditure", cash_flow.capital_expenditure),
+    ];
+    if !validate_finite(report, &amounts) {
+        return;
+    }
+
+    if cash_flow.capital_expenditure < 0.0 {
+        report.warning(
+            "NEGATIVE_CAPITAL_EXPENDITURE",
+            "consolidated_cash_flow_data.capital_expenditure",
+            format!(
+                "Capital expenditure should be reported as a positive outflow (got {:.2})",
+                cash_flow.capital_expenditure
+            ),
+        );
+    }
+
+    if amounts.iter().all(|(_, value)| *value == 0.0) {
+        report.warning(
+            "CASH_FLOW_EMPTY",
+            "consolidated_cash_flow_data",
+            "Cash flow statement amounts are all zero".to_string(),
+        );
+    }
+}
+
+/// Per-share figures must be non-negative where that is an accounting rule
+pub fn validate_per_share(report: &mut ValidationCollector, per_share: &PerShareData) {
+    let amounts = [
+        ("per_share_data.eps_basic", per_share.eps_basic),
+        ("per_share_data.eps_diluted", per_share.eps_diluted),
+        ("per_share_data.shares_outstanding", per_share.shares_outstanding),
+        ("per_share_data.dividends_per_share", per_share.dividends_per_share),
+    ];
+    if !validate_finite(report, &amounts) {
+        return;
+    }
+
+    if per_share.shares_outstanding < 0.0 {
+        report.error(
+            "NEGATIVE_SHARES_OUTSTANDING",
+            "per_share_data.shares_outstanding",
+            format!("Shares outstanding cannot be negative (got {:.2})", per_share.shares_outstanding),
+        );
+    }
+
+    if per_share.dividends_per_share < 0.0 {
+        report.error(
+            "NEGATIVE_DIVIDENDS_PER_SHARE",
+            "per_share_data.dividends_per_share",
+            format!("Dividends per share cannot be negative (got {:.4})", per_share.dividends_per_share),
+        );
+    }
+
+    // Dilution can only lower earnings per share while the company is profitable
+    if per_share.eps_basic > 0.0 && per_share.eps_diluted > per_share.eps_basic {
+        report.warning(
+            "DILUTED_EPS_EXCEEDS_BASIC",
+            "per_share_data.eps_diluted",
+            format!(
+                "Diluted EPS ({:.4}) is higher than basic EPS ({:.4})",
+                per_share.eps_diluted, per_share.eps_basic
+            ),
+        );
+    }
+}
@@ -0,0 +1,63 @@
+// Business validation of structured document data
+// Errors block publication; warnings are reported but do not
+
+pub mod financial_statements;
+pub mod earning_release;
+pub mod annual_report;
+pub mod dividend_announcement;
+pub mod board_resolution;
+pub mod prospectus;
+
+pub use earning_release::validate_earning_release_data;
+pub use annual_report::validate_annual_report_data;
+pub use dividend_announcement::validate_dividend_announcement_data;
+pub use board_resolution::validate_board_resolution_data;
+pub use prospectus::validate_prospectus_data;
+
+use crate::types::{DocumentType, ValidationIssue, ValidationReport};
+
+/// Validate the structured payload of any document type
+pub fn validate_document_data(data: &DocumentType) -> ValidationReport {
+    match data {
+        DocumentType::EarningRelease(data) => validate_earning_release_data(data),
+        DocumentType::AnnualReport(data) => validate_annual_report_data(data),
+        DocumentType::DividendAnnouncement(data) => validate_dividend_announcement_data(data),
+        DocumentType::BoardResolution(data) => validate_board_resolution_data(data),
+        DocumentType::Prospectus(data) => validate_prospectus_data(data),
+    }
+}
+
+// Collects issues while a validator runs
+#[derive(Default)]
+pub struct ValidationCollector {
+    errors: Vec<ValidationIssue>,
+    warnings: Vec<ValidationIssue>,
+}
+
+impl ValidationCollector {
+    pub fn error(&mut self, code: &str, field: &str, message: String) {
+        self.errors.push(ValidationIssue { code: code.to_string(), field: field.to_string(), message });
+    }
+
+    pub fn warning(&mut self, code: &str, field: &str, message: String) {
+        self.warnings.push(ValidationIssue { code: code.to_string(), field: field.to_string(), message });
+    }
+
+    pub fn finish(self) -> ValidationReport {
+        ValidationReport {
+            valid: self.errors.is_empty(),
+            errors: self.errors,
+            warnings: self.warnings,
+        }
+    }
+}
+
+/// Turn a report into a publication error if it contains errors
+pub fn ensure_valid(report: &ValidationReport) -> Result<(), String> {
+    if report.valid {
+        return Ok(());
+    }
+
+    let messages: Vec<&str> = report.errors.iter().map(|issue| issue.message.as_str()).collect();
+    Err(format!("Validation failed: {}", messages.join("; ")))
+}
@@ -0,0 +1,47 @@
+// Consistency checks for prospectus data
+
+use crate::types::{ProspectusData, ValidationReport};
+use super::ValidationCollector;
+use super::financial_statements::{amounts_match, validate_finite};
+
+/// Validate a prospectus; errors make the data unpublishable
+pub fn validate_prospectus_data(data: &ProspectusData) -> ValidationReport {
+    let mut report = ValidationCollector::default();
+
+    if data.security_type.trim().is_empty() {
+        report.error("SECURITY_TYPE_MISSING", "security_type", "Security type is required".to_string());
+    }
+
+    let amounts = [("offer_price", data.offer_price), ("offering_amount", data.offering_amount)];
+    if validate_finite(&mut report, &amounts) {
+        for (field, value) in amounts {
+            if value <= 0.0 {
+                report.error("AMOUNT_NOT_POSITIVE", field, format!("{} must be positive (got {:.2})", field, value));
+            }
+        }
+
+        let implied_amount = data.offer_price * data.shares_offered as f64;
+        if data.shares_offered > 0 && !amounts_match(implied_amount, data.offering_amount) {
+            report.warning(
+                "OFFERING_AMOUNT_MISMATCH",
+                "offering_amount",
+                format!(
+                    "Offering amount ({:.2}) differs from offer price x shares offered ({:.2})",
+                    data.offering_amount, implied_amount
+                ),
+            );
+        }
+    }
+
+    if data.shares_offered == 0 {
+        report.error("SHARES_OFFERED_MISSING", "shares_offered", "Number of shares offered must be positive".to_string());
+    }
+
+    if data.offering_start_date == 0 || data.offering_end_date == 0 {
+        report.error("DATE_MISSING", "offering_start_date", "Offering start and end dates are required".to_string());
+    } else if data.offering_start_date > data.offering_end_date {
+        report.error("OFFERING_PERIOD_INVALID", "offering_end_date", "Offering end date cannot be before the start date".to_string());
+    }
+
+    report.finish()
+}
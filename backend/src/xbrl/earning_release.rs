@@ -0,0 +1,463 @@
+// Derive earning release data from the US GAAP and IFRS facts of an XBRL instance
+
+use super::{XbrlContext, XbrlDate, XbrlFact, XbrlInstance, XbrlPeriod};
+use crate::types::earning_release_fields::{EarningReleaseField, FieldTarget, EARNING_RELEASE_FIELDS};
+use crate::types::{Decimal, EarningReleaseData, ReportingScale, XbrlFactSource, XbrlMismatch, XbrlReconciliation};
+use crate::validation::financial_statements::{amounts_match, per_share_amounts_match};
+
+/// Figures derived from an XBRL instance; monetary amounts are in units of the filing currency
+pub struct XbrlEarningRelease {
+    pub quarter: u8,
+    pub year: u16,
+    pub currency: Option<String>,
+    pub period_end: XbrlDate,
+    pub entity_identifier: String,
+    values: Vec<DerivedValue>,
+}
+
+struct DerivedValue {
+    mapping: &'static EarningReleaseField,
+    amount: Decimal,
+    source: XbrlFactSource,
+}
+
+// The year in taxonomy namespace URIs changes with every release
+fn is_financial_taxonomy(namespace: &str) -> bool {
+    namespace.contains("fasb.org/us-gaap/") || namespace.contains("xbrl.ifrs.org/taxonomy/")
+}
+
+fn is_dei_taxonomy(namespace: &str) -> bool {
+    namespace.contains("xbrl.sec.gov/dei/")
+}
+
+/// Derive the reporting period and financial figures of an XBRL instance
+pub fn derive_earning_release(instance: &XbrlInstance) -> Result<XbrlEarningRelease, String> {
+    let (period_end, quarter, year) = detect_reporting_period(instance)?;
+
+    // Inline facts that could not be read only matter where they would fill a field
+    if let Some(unreadable) = instance.unreadable_facts.iter().find(|unreadable| fills_field(instance, &unreadable.fact, period_end)) {
+        return Err(unreadable.error.clone());
+    }
+
+    let mut currency: Option<String> = None;
+    let mut entity_identifier = String::new();
+    let mut values = Vec::new();
+
+    // Fields without taxonomy concepts (EBITDA) always come from the client
+    for mapping in EARNING_RELEASE_FIELDS.iter().filter(|field| !field.xbrl_concepts.is_empty()) {
+        let Some((fact, context)) = select_fact(instance, mapping, period_end) else {
+            continue;
+        };
+
+        let unit = fact.unit_ref.as_ref().and_then(|unit_ref| instance.units.get(unit_ref));
+        if !matches!(mapping.target, FieldTarget::PerShare(_)) {
+            let fact_currency = unit.and_then(|unit| unit.currency()).map(str::to_string);
+            match (&currency, fact_currency) {
+                (None, fact_currency) => currency = fact_currency,
+                (Some(expected), Some(found)) if *expected != found => {
+                    return Err(format!("XBRL facts are reported in more than one currency ({} and {})", expected, found));
+                }
+                _ => {}
+            }
+        }
+
+        let amount = Decimal::parse_rounded(&fact.value)
+            .map_err(|e| format!("Invalid value for {} in context {}: {}", fact.concept(), fact.context_ref, e))?;
+
+        if entity_identifier.is_empty() {
+            entity_identifier = context.entity_identifier.clone();
+        }
+
+        values.push(DerivedValue {
+            mapping,
+            amount,
+            source: XbrlFactSource {
+                field: mapping.name.to_string(),
+                concept: fact.concept(),
+                context_id: fact.context_ref.clone(),
+                unit: unit.map(|unit| unit.to_string()).or_else(|| fact.unit_ref.clone()).unwrap_or_default(),
+                value: fact.value.clone(),
+            },
+        });
+    }
+
+    if values.is_empty() {
+        return Err(format!("No US GAAP or IFRS financial facts found for the period ending {}", period_end));
+    }
+
+    Ok(XbrlEarningRelease { quarter, year, currency, period_end, entity_identifier, values })
+}
+
+/// Replace client-supplied figures with the filed XBRL values and record every difference
+/// Fields the client left blank (zero or empty) are filled in without being flagged
+pub fn reconcile_earning_release(
+    client: &EarningReleaseData,
+    derived: &XbrlEarningRelease,
+) -> Result<(EarningReleaseData, XbrlReconciliation), String> {
+    let mut data = client.clone();
+    let mut mismatches = Vec::new();
+    let mut flag = |field: &str, client_value: String, xbrl_value: String| {
+        mismatches.push(XbrlMismatch { field: field.to_string(), client_value, xbrl_value });
+    };
+
+    if data.quarter != derived.quarter {
+        if data.quarter != 0 {
+            flag("quarter", data.quarter.to_string(), derived.quarter.to_string());
+        }
+        data.quarter = derived.quarter;
+    }
+    if data.year != derived.year {
+        if data.year != 0 {
+            flag("year", data.year.to_string(), derived.year.to_string());
+        }
+        data.year = derived.year;
+    }
+
+    if let Some(currency) = &derived.currency {
+        for (field, client_currency) in [
+            ("consolidated_income_data.currency", &mut data.consolidated_income_data.currency),
+            ("consolidated_balance_sheet_data.currency", &mut data.consolidated_balance_sheet_data.currency),
+        ] {
+            if client_currency != currency {
+                if !client_currency.is_empty() {
+                    flag(field, client_currency.clone(), currency.clone());
+                }
+                *client_currency = currency.clone();
+            }
+        }
+    }
+
+    // Amounts are converted to the scale the client reports in before comparing
+    let income_scale = data.consolidated_income_data.scale;
+    let balance_scale = data.consolidated_balance_sheet_data.scale;
+    for value in &derived.values {
+        let field = value.mapping.name;
+        let in_scale = |scale: ReportingScale| {
+            value.amount.div_rounded(scale.multiplier())
+                .ok_or_else(|| format!("{} ({}) cannot be converted to {}", field, value.amount, scale.label()))
+        };
+        match value.mapping.target {
+            FieldTarget::Income(slot) => {
+                let xbrl_value = in_scale(income_scale)?;
+                let client_value = slot(&mut data.consolidated_income_data);
+                if !client_value.is_zero() && *client_value != xbrl_value {
+                    flag(field, client_value.to_string(), xbrl_value.to_string());
+                }
+                *client_value = xbrl_value;
+            }
+            FieldTarget::Balance(slot) => {
+                let xbrl_value = in_scale(balance_scale)?;
+                let client_value = slot(&mut data.consolidated_balance_sheet_data);
+                if !client_value.is_zero() && *client_value != xbrl_value {
+                    flag(field, client_value.to_string(), xbrl_value.to_string());
+                }
+                *client_value = xbrl_value;
+            }
+            FieldTarget::CashFlow(slot) => {
+                let xbrl_value = in_scale(income_scale)?.to_f64();
+                let client_value = slot(&mut data.consolidated_cash_flow_data);
+                if *client_value != 0.0 && !amounts_match(*client_value, xbrl_value) {
+                    flag(field, client_value.to_string(), xbrl_value.to_string());
+                }
+                *client_value = xbrl_value;
+            }
+            FieldTarget::PerShare(slot) => {
+                let xbrl_value = value.amount.to_f64();
+                let client_value = slot(&mut data.per_share_data);
+                if *client_value != 0.0 && !per_share_amounts_match(*client_value, xbrl_value) {
+                    flag(field, client_value.to_string(), xbrl_value.to_string());
+                }
+                *client_value = xbrl_value;
+            }
+        }
+    }
+
+    let reconciliation = XbrlReconciliation {
+        entity_identifier: derived.entity_identifier.clone(),
+        period_end_date: derived.period_end.to_string(),
+        sources: derived.values.iter().map(|value| value.source.clone()).collect(),
+        mismatches,
+    };
+
+    Ok((data, reconciliation))
+}
+
+/// Reconciled statements with the filed amounts in currency units, for checking totals before rounding
+/// Each converted amount is rounded on its own, so the rounded figures need not add up exactly
+pub fn statements_in_units(data: &EarningReleaseData, derived: &XbrlEarningRelease) -> Result<EarningReleaseData, String> {
+    let mut units = data.clone();
+    let income_multiplier = units.consolidated_income_data.scale.multiplier();
+    let balance_multiplier = units.consolidated_balance_sheet_data.scale.multiplier();
+    units.consolidated_income_data.scale = ReportingScale::Units;
+    units.consolidated_balance_sheet_data.scale = ReportingScale::Units;
+
+    for mapping in EARNING_RELEASE_FIELDS {
+        let filed = derived.values.iter().find(|value| std::ptr::eq(value.mapping, mapping)).map(|value| value.amount);
+        let (amount, multiplier) = match mapping.target {
+            FieldTarget::Income(slot) => (slot(&mut units.consolidated_income_data), income_multiplier),
+            FieldTarget::Balance(slot) => (slot(&mut units.consolidated_balance_sheet_data), balance_multiplier),
+            FieldTarget::CashFlow(_) | FieldTarget::PerShare(_) => continue,
+        };
+        *amount = match filed {
+            Some(filed) => filed,
+            None => amount.checked_mul(multiplier)
+                .ok_or_else(|| format!("{} ({}) is too large to convert to units", mapping.name, amount))?,
+        };
+    }
+    Ok(units)
+}
+
+// Reporting period from the dei cover facts, or else the latest period of the financial facts
+// Without dei facts the calendar quarter of the period end is used
+fn detect_reporting_period(instance: &XbrlInstance) -> Result<(XbrlDate, u8, u16), String> {
+    let dei_value = |name: &str| {
+        instance.facts.iter()
+            .find(|fact| is_dei_taxonomy(&fact.namespace) && fact.name == name && !fact.is_nil)
+            .map(|fact| fact.value.trim().to_string())
+    };
+
+    let period_end = match dei_value("DocumentPeriodEndDate").and_then(|text| XbrlDate::parse(&text)) {
+        Some(date) => date,
+        None => instance.facts.iter()
+            .filter(|fact| is_financial_taxonomy(&fact.namespace))
+            .filter_map(|fact| instance.contexts.get(&fact.context_ref))
+            .filter(|context| !context.has_dimensions)
+            .filter_map(|context| context.period.end_date())
+            .max()
+            .ok_or("XBRL instance has no US GAAP or IFRS facts with a reporting period")?,
+    };
+
+    let quarter = match dei_value("DocumentFiscalPeriodFocus").as_deref() {
+        Some("Q1") => 1,
+        Some("Q2") | Some("H1") => 2,
+        Some("Q3") => 3,
+        Some("Q4") | Some("H2") | Some("FY") => 4,
+        _ => period_end.quarter(),
+    };
+
+    let year = dei_value("DocumentFiscalYearFocus")
+        .and_then(|text| text.parse().ok())
+        .unwrap_or(period_end.year);
+
+    Ok((period_end, quarter, year))
+}
+
+// Consolidated fact of a field's concept for the reporting period
+fn fills_field(instance: &XbrlInstance, fact: &XbrlFact, period_end: XbrlDate) -> bool {
+    is_financial_taxonomy(&fact.namespace)
+        && EARNING_RELEASE_FIELDS.iter().any(|field| field.xbrl_concepts.contains(&fact.name.as_str()))
+        && instance.contexts.get(&fact.context_ref)
+            .is_some_and(|context| !context.has_dimensions && context.period.end_date() == Some(period_end))
+}
+
+// Consolidated fact of the first listed concept reported for the period; for durations the
+// shortest one ending at the period end wins (the quarter rather than the year to date)
+fn select_fact<'a>(
+    instance: &'a XbrlInstance,
+    mapping: &EarningReleaseField,
+    period_end: XbrlDate,
+) -> Option<(&'a XbrlFact, &'a XbrlContext)> {
+    mapping.xbrl_concepts.iter().find_map(|concept| {
+        instance.facts.iter()
+            .filter(|fact| fact.name == *concept && is_financial_taxonomy(&fact.namespace) && !fact.is_nil)
+            .filter_map(|fact| Some((fact, instance.contexts.get(&fact.context_ref)?)))
+            .filter(|(_, context)| !context.has_dimensions)
+            .filter_map(|(fact, context)| match context.period {
+                XbrlPeriod::Instant(date) if mapping.is_instant() && date == period_end => Some((0, fact, context)),
+                XbrlPeriod::Duration { start, end } if !mapping.is_instant() && end == period_end => {
+                    Some((end.days_since_epoch() - start.days_since_epoch(), fact, context))
+                }
+                _ => None,
+            })
+            .min_by_key(|(length, _, _)| *length)
+            .map(|(_, fact, context)| (fact, context))
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::xbrl::parse_instance;
+
+    const US_GAAP: &str = "http://fasb.org/us-gaap/2024";
+    const DEI: &str = "http://xbrl.sec.gov/dei/2024";
+
+    fn instance(cover: &str, facts: &str) -> XbrlInstance {
+        let xml = format!(
+            r#"<xbrli:xbrl xmlns:xbrli="http://www.xbrl.org/2003/instance" xmlns:iso4217="http://www.xbrl.org/2003/iso4217"
+                xmlns:xbrldi="http://xbrl.org/2006/xbrldi" xmlns:us-gaap="{US_GAAP}" xmlns:dei="{DEI}">
+              <xbrli:context id="Q3"><xbrli:entity><xbrli:identifier scheme="http://www.sec.gov/CIK">0000001</xbrli:identifier></xbrli:entity>
+                <xbrli:period><xbrli:startDate>2025-07-01</xbrli:startDate><xbrli:endDate>2025-09-30</xbrli:endDate></xbrli:period></xbrli:context>
+              <xbrli:context id="YTD"><xbrli:entity><xbrli:identifier scheme="http://www.sec.gov/CIK">0000001</xbrli:identifier></xbrli:entity>
+                <xbrli:period><xbrli:startDate>2025-01-01</xbrli:startDate><xbrli:endDate>2025-09-30</xbrli:endDate></xbrli:period></xbrli:context>
+              <xbrli:context id="Q3_PRIOR"><xbrli:entity><xbrli:identifier scheme="http://www.sec.gov/CIK">0000001</xbrli:identifier></xbrli:entity>
+                <xbrli:period><xbrli:startDate>2024-07-01</xbrli:startDate><xbrli:endDate>2024-09-30</xbrli:endDate></xbrli:period></xbrli:context>
+              <xbrli:context id="Q3_SEGMENT"><xbrli:entity><xbrli:identifier scheme="http://www.sec.gov/CIK">0000001</xbrli:identifier>
+                <xbrli:segment><xbrldi:explicitMember dimension="us-gaap:StatementBusinessSegmentsAxis">us-gaap:CorporateMember</xbrldi:explicitMember></xbrli:segment></xbrli:entity>
+                <xbrli:period><xbrli:startDate>2025-07-01</xbrli:startDate><xbrli:endDate>2025-10-31</xbrli:endDate></xbrli:period></xbrli:context>
+              <xbrli:context id="END"><xbrli:entity><xbrli:identifier scheme="http://www.sec.gov/CIK">0000001</xbrli:identifier></xbrli:entity>
+                <xbrli:period><xbrli:instant>2025-09-30</xbrli:instant></xbrli:period></xbrli:context>
+              <xbrli:context id="PRIOR_END"><xbrli:entity><xbrli:identifier scheme="http://www.sec.gov/CIK">0000001</xbrli:identifier></xbrli:entity>
+                <xbrli:period><xbrli:instant>2024-12-31</xbrli:instant></xbrli:period></xbrli:context>
+              <xbrli:unit id="USD"><xbrli:measure>iso4217:USD</xbrli:measure></xbrli:unit>
+              {cover}
+              {facts}
+            </xbrli:xbrl>"#
+        );
+        parse_instance(xml.as_bytes()).unwrap()
+    }
+
+    fn source<'a>(derived: &'a XbrlEarningRelease, field: &str) -> &'a XbrlFactSource {
+        &derived.values.iter().find(|value| value.mapping.name == field).unwrap().source
+    }
+
+    const FACTS: &str = r#"
+        <us-gaap:Revenues contextRef="YTD" unitRef="USD" decimals="-3">9000000</us-gaap:Revenues>
+        <us-gaap:Revenues contextRef="Q3" unitRef="USD" decimals="-3">3000000</us-gaap:Revenues>
+        <us-gaap:Revenues contextRef="Q3_PRIOR" unitRef="USD" decimals="-3">2500000</us-gaap:Revenues>
+        <us-gaap:Revenues contextRef="Q3_SEGMENT" unitRef="USD" decimals="-3">500000</us-gaap:Revenues>
+        <us-gaap:RevenueFromContractWithCustomerExcludingAssessedTax contextRef="Q3" unitRef="USD" decimals="-3">2900000</us-gaap:RevenueFromContractWithCustomerExcludingAssessedTax>
+        <us-gaap:Assets contextRef="PRIOR_END" unitRef="USD" decimals="-3">40000000</us-gaap:Assets>
+        <us-gaap:Assets contextRef="END" unitRef="USD" decimals="-3">42000000</us-gaap:Assets>
+    "#;
+
+    #[test]
+    fn dei_cover_facts_set_the_reporting_period() {
+        let cover = r#"
+            <dei:DocumentPeriodEndDate contextRef="Q3">2025-09-30</dei:DocumentPeriodEndDate>
+            <dei:DocumentFiscalPeriodFocus contextRef="Q3">Q4</dei:DocumentFiscalPeriodFocus>
+            <dei:DocumentFiscalYearFocus contextRef="Q3">2026</dei:DocumentFiscalYearFocus>
+        "#;
+        let (period_end, quarter, year) = detect_reporting_period(&instance(cover, FACTS)).unwrap();
+        assert_eq!(period_end, XbrlDate { year: 2025, month: 9, day: 30 });
+        // Fiscal focus wins over the calendar quarter of the period end
+        assert_eq!((quarter, year), (4, 2026));
+    }
+
+    #[test]
+    fn period_without_cover_facts_is_the_latest_consolidated_period() {
+        // The segment context ends later but reports a breakdown
+        let (period_end, quarter, year) = detect_reporting_period(&instance("", FACTS)).unwrap();
+        assert_eq!(period_end, XbrlDate { year: 2025, month: 9, day: 30 });
+        assert_eq!((quarter, year), (3, 2025));
+    }
+
+    #[test]
+    fn instance_without_financial_facts_has_no_reporting_period() {
+        assert!(detect_reporting_period(&instance("", "")).is_err());
+        assert!(derive_earning_release(&instance("", "")).is_err());
+    }
+
+    #[test]
+    fn quarter_is_selected_over_year_to_date_and_other_contexts() {
+        let derived = derive_earning_release(&instance("", FACTS)).unwrap();
+        let revenue = source(&derived, "consolidated_income_data.revenue");
+        // The first listed concept is used even though a later one is also reported
+        assert_eq!(revenue.concept, "us-gaap:Revenues");
+        assert_eq!(revenue.context_id, "Q3");
+        assert_eq!(revenue.value, "3000000");
+        assert_eq!(revenue.unit, "iso4217:USD");
+        assert_eq!(derived.currency.as_deref(), Some("USD"));
+        assert_eq!(derived.entity_identifier, "0000001");
+    }
+
+    #[test]
+    fn balance_sheet_facts_are_taken_at_the_period_end() {
+        let derived = derive_earning_release(&instance("", FACTS)).unwrap();
+        let assets = source(&derived, "consolidated_balance_sheet_data.total_assets");
+        assert_eq!(assets.context_id, "END");
+        assert_eq!(assets.value, "42000000");
+    }
+
+    #[test]
+    fn nil_facts_are_skipped_for_the_next_concept() {
+        let facts = r#"
+            <us-gaap:Revenues contextRef="Q3" unitRef="USD" xsi:nil="true" xmlns:xsi="http://www.w3.org/2001/XMLSchema-instance"/>
+            <us-gaap:RevenueFromContractWithCustomerExcludingAssessedTax contextRef="Q3" unitRef="USD">2900000</us-gaap:RevenueFromContractWithCustomerExcludingAssessedTax>
+        "#;
+        let derived = derive_earning_release(&instance("", facts)).unwrap();
+        let revenue = source(&derived, "consolidated_income_data.revenue");
+        assert_eq!(revenue.concept, "us-gaap:RevenueFromContractWithCustomerExcludingAssessedTax");
+    }
+
+    #[test]
+    fn facts_in_several_currencies_are_rejected() {
+        let facts = r#"
+            <xbrli:unit id="EUR"><xbrli:measure>iso4217:EUR</xbrli:measure></xbrli:unit>
+            <us-gaap:Revenues contextRef="Q3" unitRef="USD">3000000</us-gaap:Revenues>
+            <us-gaap:Assets contextRef="END" unitRef="EUR">42000000</us-gaap:Assets>
+        "#;
+        assert!(derive_earning_release(&instance("", facts)).is_err());
+    }
+
+    fn statement_errors(data: &EarningReleaseData) -> Vec<String> {
+        let mut report = crate::validation::ValidationCollector::default();
+        crate::validation::financial_statements::validate_financial_statements(
+            &mut report,
+            &data.consolidated_income_data,
+            &data.consolidated_balance_sheet_data,
+        );
+        report.finish().errors.into_iter().map(|issue| issue.code).collect()
+    }
+
+    #[test]
+    fn totals_are_checked_before_rounding_to_the_client_scale() {
+        let facts = r#"
+            <us-gaap:Assets contextRef="END" unitRef="USD">3000001</us-gaap:Assets>
+            <us-gaap:Liabilities contextRef="END" unitRef="USD">1500050</us-gaap:Liabilities>
+            <us-gaap:StockholdersEquity contextRef="END" unitRef="USD">1499951</us-gaap:StockholdersEquity>
+            <us-gaap:LiabilitiesAndStockholdersEquity contextRef="END" unitRef="USD">3000001</us-gaap:LiabilitiesAndStockholdersEquity>
+        "#;
+        let derived = derive_earning_release(&instance("", facts)).unwrap();
+        let mut client = EarningReleaseData::default();
+        client.consolidated_income_data.scale = ReportingScale::Millions;
+        client.consolidated_balance_sheet_data.scale = ReportingScale::Millions;
+
+        let (data, reconciliation) = reconcile_earning_release(&client, &derived).unwrap();
+        assert!(reconciliation.mismatches.is_empty());
+        let balance = &data.consolidated_balance_sheet_data;
+        assert_eq!(
+            [balance.total_assets, balance.total_liabilities, balance.total_equity],
+            [Decimal(30_000), Decimal(15_001), Decimal(15_000)],
+        );
+        // 1.5001 + 1.5000 is not 3.0000, although the filing adds up
+        assert_eq!(statement_errors(&data), ["LIABILITIES_EQUITY_SUM_MISMATCH"]);
+
+        let units = statements_in_units(&data, &derived).unwrap();
+        assert_eq!(units.consolidated_balance_sheet_data.total_liabilities, Decimal(15_000_500_000));
+        assert!(statement_errors(&units).is_empty());
+    }
+
+    #[test]
+    fn client_figures_are_scaled_up_exactly_for_the_unit_check() {
+        let facts = r#"<us-gaap:Revenues contextRef="Q3" unitRef="USD">3000000</us-gaap:Revenues>"#;
+        let derived = derive_earning_release(&instance("", facts)).unwrap();
+        let mut client = EarningReleaseData::default();
+        client.consolidated_income_data.scale = ReportingScale::Thousands;
+        client.consolidated_income_data.cost_of_sales = "1000.5".parse().unwrap();
+        client.consolidated_income_data.gross_profit = "1999.5".parse().unwrap();
+
+        let (data, _) = reconcile_earning_release(&client, &derived).unwrap();
+        assert_eq!(data.consolidated_income_data.revenue, Decimal(30_000_000));
+        let units = statements_in_units(&data, &derived).unwrap().consolidated_income_data;
+        assert_eq!(units.scale, ReportingScale::Units);
+        assert_eq!(units.revenue, Decimal(30_000_000_000));
+        assert_eq!(units.cost_of_sales, Decimal(10_005_000_000));
+
+        client.consolidated_income_data.ebitda = Decimal(i64::MAX / 10);
+        let (data, _) = reconcile_earning_release(&client, &derived).unwrap();
+        assert!(statements_in_units(&data, &derived).is_err());
+    }
+
+    #[test]
+    fn xbrl_dates_parse_with_time_zones_and_give_the_calendar_quarter() {
+        let date = XbrlDate::parse("2025-09-30Z").unwrap();
+        assert_eq!(date, XbrlDate { year: 2025, month: 9, day: 30 });
+        assert_eq!(date.quarter(), 3);
+        assert_eq!(XbrlDate::parse(" 2025-01-01T00:00:00+02:00 ").unwrap().quarter(), 1);
+        assert_eq!(XbrlDate::parse("2025-12-31").unwrap().quarter(), 4);
+        assert_eq!(XbrlDate::parse("2025-13-01"), None);
+        assert_eq!(XbrlDate::parse("2025-9-30"), None);
+        assert_eq!(XbrlPeriod::Forever.end_date(), None);
+    }
+}
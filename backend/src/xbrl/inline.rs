@@ -0,0 +1,422 @@
+// Inline XBRL: facts tagged in XHTML filings, read into the same instance model as XBRL documents
+// Numeric facts are converted to plain decimals here (format, scale and sign), so derivation is shared
+
+use std::collections::{BTreeMap, BTreeSet};
+use roxmltree::Node;
+use super::{parse_context, parse_unit, UnreadableFact, XbrlFact, XbrlInstance, XBRLI_NAMESPACE, XSI_NAMESPACE};
+
+// Inline XBRL 1.1 and 1.0
+const INLINE_XBRL_NAMESPACES: &[&str] = &[
+    "http://www.xbrl.org/2013/inlineXBRL",
+    "http://www.xbrl.org/2008/inlineXBRL",
+];
+
+// Scales are powers of ten; filings use -6 to 9 in practice
+const MAX_SCALE: i32 = 20;
+
+const SMALL_NUMBER_WORDS: [&str; 20] = [
+    "zero", "one", "two", "three", "four", "five", "six", "seven", "eight", "nine", "ten",
+    "eleven", "twelve", "thirteen", "fourteen", "fifteen", "sixteen", "seventeen", "eighteen", "nineteen",
+];
+const TENS_WORDS: [&str; 8] = ["twenty", "thirty", "forty", "fifty", "sixty", "seventy", "eighty", "ninety"];
+
+const MONTH_NAMES: [&str; 12] = [
+    "january", "february", "march", "april", "may", "june",
+    "july", "august", "september", "october", "november", "december",
+];
+
+/// Check whether the file declares the inline XBRL namespace
+pub fn is_inline_xbrl(data: &[u8]) -> bool {
+    INLINE_XBRL_NAMESPACES.iter()
+        .any(|namespace| data.windows(namespace.len()).any(|window| window == namespace.as_bytes()))
+}
+
+/// Parse the contexts, units and tagged facts of an inline XBRL document
+/// Numeric facts whose displayed value cannot be converted are recorded as unreadable, not rejected
+pub fn parse_inline_instance(data: &[u8]) -> Result<XbrlInstance, String> {
+    let text = std::str::from_utf8(data).map_err(|_| "Inline XBRL document is not valid UTF-8".to_string())?;
+    // XHTML filings may carry a doctype; entities are expanded internally and never fetched
+    let options = roxmltree::ParsingOptions { allow_dtd: true, ..Default::default() };
+    let document = roxmltree::Document::parse_with_options(text.trim_start_matches('\u{FEFF}'), options)
+        .map_err(|e| format!("Inline XBRL document is not well-formed XHTML: {}", e))?;
+
+    let continuations: BTreeMap<&str, Node> = document.descendants()
+        .filter(|node| is_inline_element(*node, "continuation"))
+        .filter_map(|node| Some((node.attribute("id")?, node)))
+        .collect();
+
+    let mut instance = XbrlInstance::default();
+    for node in document.descendants().filter(Node::is_element) {
+        let namespace = node.tag_name().namespace().unwrap_or_default();
+        if namespace == XBRLI_NAMESPACE {
+            match node.tag_name().name() {
+                "context" => {
+                    let context = parse_context(node)?;
+                    instance.contexts.insert(context.id.clone(), context);
+                }
+                "unit" => {
+                    let (id, unit) = parse_unit(node)?;
+                    instance.units.insert(id, unit);
+                }
+                _ => {}
+            }
+        } else if INLINE_XBRL_NAMESPACES.contains(&namespace) {
+            let numeric = match node.tag_name().name() {
+                "nonFraction" => true,
+                "nonNumeric" => false,
+                _ => continue,
+            };
+            let (fact, error) = parse_inline_fact(node, numeric, &continuations)?;
+            match error {
+                None => instance.facts.push(fact),
+                Some(error) => instance.unreadable_facts.push(UnreadableFact { fact, error }),
+            }
+        }
+    }
+
+    if instance.facts.is_empty() && instance.unreadable_facts.is_empty() {
+        return Err("Inline XBRL document contains no tagged facts".to_string());
+    }
+
+    Ok(instance)
+}
+
+fn is_inline_element(node: Node, name: &str) -> bool {
+    node.is_element()
+        && node.tag_name().name() == name
+        && INLINE_XBRL_NAMESPACES.contains(&node.tag_name().namespace().unwrap_or_default())
+}
+
+// ix:nonFraction and ix:nonNumeric name the concept in an attribute instead of the element
+// A value that cannot be converted is returned with the displayed text; missing attributes are errors
+fn parse_inline_fact(node: Node, numeric: bool, continuations: &BTreeMap<&str, Node>) -> Result<(XbrlFact, Option<String>), String> {
+    let name = node.attribute("name").ok_or("Inline XBRL fact without a name")?;
+    let (prefix, local_name) = name.split_once(':').unwrap_or(("", name));
+    let namespace = node.lookup_namespace_uri(Some(prefix).filter(|prefix| !prefix.is_empty()))
+        .ok_or_else(|| format!("Inline XBRL fact {} uses an undeclared prefix", name))?;
+    let context_ref = node.attribute("contextRef")
+        .ok_or_else(|| format!("Inline XBRL fact {} has no contextRef", name))?;
+    let is_nil = node.attribute((XSI_NAMESPACE, "nil")) == Some("true");
+
+    let text = inline_text(node, continuations);
+    let format = node.attribute("format");
+    let (value, error) = if is_nil {
+        (String::new(), None)
+    } else if numeric {
+        match numeric_value(&text, format, node.attribute("scale"), node.attribute("sign")) {
+            Ok(value) => (value, None),
+            Err(e) => (text, Some(format!("Invalid value for {} in context {}: {}", name, context_ref, e))),
+        }
+    } else {
+        (format.and_then(|format| transform_date(format, &text)).unwrap_or(text), None)
+    };
+
+    let fact = XbrlFact {
+        namespace: namespace.to_string(),
+        prefix: prefix.to_string(),
+        name: local_name.to_string(),
+        context_ref: context_ref.to_string(),
+        unit_ref: node.attribute("unitRef").map(str::to_string),
+        value,
+        is_nil,
+    };
+    Ok((fact, error))
+}
+
+// Displayed text of a fact without its ix:exclude content, followed by the ix:continuation chain it starts
+fn inline_text(node: Node, continuations: &BTreeMap<&str, Node>) -> String {
+    let mut text = displayed_text(node);
+    let mut seen = BTreeSet::new();
+    let mut next = node.attribute("continuedAt");
+    while let Some(id) = next {
+        // A chain that loops back is cut where it repeats
+        let Some(continuation) = continuations.get(id).filter(|_| seen.insert(id)) else {
+            break;
+        };
+        text.push_str(&displayed_text(*continuation));
+        next = continuation.attribute("continuedAt");
+    }
+    text.trim().to_string()
+}
+
+fn displayed_text(node: Node) -> String {
+    node.descendants()
+        .filter(Node::is_text)
+        .filter(|text| !text.ancestors().take_while(|ancestor| *ancestor != node).any(|ancestor| is_inline_element(ancestor, "exclude")))
+        .filter_map(|text| text.text())
+        .collect()
+}
+
+// Displayed number -> plain decimal: apply the format, then multiply by 10^scale and negate for sign="-"
+fn numeric_value(text: &str, format: Option<&str>, scale: Option<&str>, sign: Option<&str>) -> Result<String, String> {
+    let plain = apply_number_format(text, format)?;
+    let scale = match scale {
+        Some(scale) => scale.trim().parse::<i32>()
+            .ok()
+            .filter(|scale| scale.abs() <= MAX_SCALE)
+            .ok_or_else(|| format!("unsupported scale '{}'", scale))?,
+        None => 0,
+    };
+
+    let scaled = shift_decimal_point(&plain, scale);
+    Ok(if sign == Some("-") && scaled != "0" { format!("-{}", scaled) } else { scaled })
+}
+
+// Transformation registry formats, e.g. ixt:num-dot-decimal ("1,234.5") or ixt:num-comma-decimal ("1.234,5")
+// Displayed numbers are unsigned; the sign attribute carries negation
+fn apply_number_format(text: &str, format: Option<&str>) -> Result<String, String> {
+    let format_name = format.map(|format| format.rsplit(':').next().unwrap_or(format).to_ascii_lowercase());
+    let decimal_separator = match format_name.as_deref() {
+        None => '.',
+        Some(name) if name.contains("zero") => return Ok("0".to_string()), // ixt:fixed-zero, ixt:zerodash
+        Some(name) if name.replace('-', "").starts_with("numword") => return number_words(text), // ixt-sec:numwordsen
+        Some(name) if name.contains("commadecimal") || name.contains("comma-decimal") || name.ends_with("comma") => ',',
+        Some(name) if name.starts_with("num") => '.',
+        Some(name) => return Err(format!("unsupported number format '{}'", name)),
+    };
+
+    let mut plain = String::new();
+    for c in text.chars() {
+        if c.is_ascii_digit() {
+            plain.push(c);
+        } else if c == decimal_separator && !plain.contains('.') {
+            plain.push('.');
+        }
+    }
+
+    if !plain.bytes().any(|b| b.is_ascii_digit()) {
+        return Err(format!("'{}' is not a number", text));
+    }
+    Ok(plain)
+}
+
+// Whole numbers written in English words, e.g. "None", "no", "twenty-one" or "one hundred and five"
+fn number_words(text: &str) -> Result<String, String> {
+    let not_a_number = || format!("'{}' is not a number", text);
+    let words: Vec<String> = text
+        .split(|c: char| c.is_whitespace() || c == '-' || c == ',')
+        .filter(|word| !word.is_empty())
+        .map(str::to_ascii_lowercase)
+        .collect();
+    if let [word] = words.as_slice() {
+        if matches!(word.as_str(), "no" | "none" | "nil") {
+            return Ok("0".to_string());
+        }
+    }
+    if words.is_empty() {
+        return Err(not_a_number());
+    }
+
+    let (mut total, mut group) = (0_u64, 0_u64);
+    for word in &words {
+        let word = word.as_str();
+        if let Some(value) = SMALL_NUMBER_WORDS.iter().position(|name| *name == word) {
+            group = group.checked_add(value as u64).ok_or_else(not_a_number)?;
+        } else if let Some(position) = TENS_WORDS.iter().position(|name| *name == word) {
+            group = group.checked_add((position as u64 + 2) * 10).ok_or_else(not_a_number)?;
+        } else if word == "hundred" {
+            group = group.max(1).checked_mul(100).ok_or_else(not_a_number)?;
+        } else if let Some(multiplier) = match word {
+            "thousand" => Some(1_000_u64),
+            "million" => Some(1_000_000),
+            "billion" => Some(1_000_000_000),
+            _ => None,
+        } {
+            total = group.max(1).checked_mul(multiplier).and_then(|value| total.checked_add(value)).ok_or_else(not_a_number)?;
+            group = 0;
+        } else if word != "and" {
+            return Err(not_a_number());
+        }
+    }
+    total.checked_add(group).map(|value| value.to_string()).ok_or_else(not_a_number)
+}
+
+// Move the decimal point of an unsigned decimal, e.g. ("1234.5", 3) -> "1234500"
+fn shift_decimal_point(plain: &str, scale: i32) -> String {
+    let (integer, fraction) = plain.split_once('.').unwrap_or((plain, ""));
+    let digits = format!("{}{}", integer, fraction);
+    let point = integer.len() as i32 + scale;
+
+    let (integer, fraction) = if point <= 0 {
+        (String::new(), format!("{}{}", "0".repeat(point.unsigned_abs() as usize), digits))
+    } else if point as usize >= digits.len() {
+        (format!("{}{}", digits, "0".repeat(point as usize - digits.len())), String::new())
+    } else {
+        (digits[..point as usize].to_string(), digits[point as usize..].to_string())
+    };
+
+    let integer = integer.trim_start_matches('0');
+    let fraction = fraction.trim_end_matches('0');
+    match (integer.is_empty(), fraction.is_empty()) {
+        (true, true) => "0".to_string(),
+        (true, false) => format!("0.{}", fraction),
+        (false, true) => integer.to_string(),
+        (false, false) => format!("{}.{}", integer, fraction),
+    }
+}
+
+// English month name dates used on cover pages, e.g. "June 30, 2024" or "30 June 2024" -> "2024-06-30"
+fn transform_date(format: &str, text: &str) -> Option<String> {
+    let format_name = format.rsplit(':').next().unwrap_or(format).replace('-', "").to_ascii_lowercase();
+    let words: Vec<String> = text
+        .split(|c: char| c.is_whitespace() || c == ',' || c == '.')
+        .filter(|word| !word.is_empty())
+        .map(str::to_ascii_lowercase)
+        .collect();
+    let [first, second, third] = words.as_slice() else {
+        return None;
+    };
+
+    let (month, day) = match format_name.as_str() {
+        "datemonthnamedayyearen" | "datemonthdayyearen" => (first, second),
+        "datedaymonthnameyearen" | "datedaymonthyearen" => (second, first),
+        _ => return None,
+    };
+    let month = MONTH_NAMES.iter().position(|name| name.starts_with(month.as_str()) && month.len() >= 3)? + 1;
+    let day: u8 = day.parse().ok()?;
+    let year: u16 = third.parse().ok()?;
+
+    Some(format!("{:04}-{:02}-{:02}", year, month, day))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn decimal_point_moves_by_the_scale() {
+        assert_eq!(shift_decimal_point("1234.5", 3), "1234500");
+        assert_eq!(shift_decimal_point("1234.5", 0), "1234.5");
+        assert_eq!(shift_decimal_point("1234.5", 1), "12345");
+        assert_eq!(shift_decimal_point("1234.5", -2), "12.345");
+        assert_eq!(shift_decimal_point("1234.5", -6), "0.0012345");
+        assert_eq!(shift_decimal_point("0.25", 6), "250000");
+        assert_eq!(shift_decimal_point("007.500", 0), "7.5");
+        assert_eq!(shift_decimal_point("0", 9), "0");
+        assert_eq!(shift_decimal_point(".5", 0), "0.5");
+    }
+
+    #[test]
+    fn number_formats_keep_digits_and_the_decimal_separator() {
+        assert_eq!(apply_number_format("1,234.5", Some("ixt:num-dot-decimal")), Ok("1234.5".to_string()));
+        assert_eq!(apply_number_format("1.234,5", Some("ixt:num-comma-decimal")), Ok("1234.5".to_string()));
+        // Inline XBRL 1.0 transformation names
+        assert_eq!(apply_number_format("1 234,5", Some("ixt:numcommadecimal")), Ok("1234.5".to_string()));
+        assert_eq!(apply_number_format("1,234.5", Some("ixt:numdotdecimal")), Ok("1234.5".to_string()));
+        assert_eq!(apply_number_format("(1,234)", None), Ok("1234".to_string()));
+        assert_eq!(apply_number_format("—", Some("ixt:fixed-zero")), Ok("0".to_string()));
+        assert_eq!(apply_number_format("-", Some("ixt:zerodash")), Ok("0".to_string()));
+    }
+
+    #[test]
+    fn unsupported_formats_and_text_are_rejected() {
+        assert!(apply_number_format("June 30, 2025", Some("ixt:date-monthname-day-year-en")).is_err());
+        assert!(apply_number_format("n/a", Some("ixt:num-dot-decimal")).is_err());
+        assert!(apply_number_format("", None).is_err());
+    }
+
+    #[test]
+    fn numeric_values_apply_format_scale_and_sign() {
+        assert_eq!(numeric_value("1,234.5", Some("ixt:num-dot-decimal"), Some("6"), None), Ok("1234500000".to_string()));
+        assert_eq!(numeric_value("1.234,5", Some("ixt:num-comma-decimal"), Some("3"), Some("-")), Ok("-1234500".to_string()));
+        assert_eq!(numeric_value("45", None, Some("-2"), None), Ok("0.45".to_string()));
+        assert_eq!(numeric_value("—", Some("ixt:fixed-zero"), Some("6"), Some("-")), Ok("0".to_string()));
+        assert!(numeric_value("1", None, Some("21"), None).is_err());
+        assert!(numeric_value("1", None, Some("thousands"), None).is_err());
+    }
+
+    #[test]
+    fn numbers_in_words_are_converted() {
+        let words = |text: &str| apply_number_format(text, Some("ixt-sec:numwordsen"));
+        assert_eq!(words("None"), Ok("0".to_string()));
+        assert_eq!(words("no"), Ok("0".to_string()));
+        assert_eq!(words("Zero"), Ok("0".to_string()));
+        assert_eq!(words("twenty-one"), Ok("21".to_string()));
+        assert_eq!(words("one hundred and five"), Ok("105".to_string()));
+        assert_eq!(words("Two Million, Three Hundred Thousand"), Ok("2300000".to_string()));
+        assert!(words("several").is_err());
+        assert!(words("").is_err());
+    }
+
+    // Cover page of an SEC 10-Q: word numbers, excluded page furniture, continued text blocks
+    // and a fact whose displayed value cannot be read, which must not reject the filing
+    const SEC_COVER_PAGE: &str = r#"<html xmlns="http://www.w3.org/1999/xhtml" xmlns:ix="http://www.xbrl.org/2013/inlineXBRL"
+        xmlns:ixt="http://www.xbrl.org/inlineXBRL/transformation/2020-02-12" xmlns:ixt-sec="http://www.sec.gov/inlineXBRL/transformation/2015-08-31"
+        xmlns:xbrli="http://www.xbrl.org/2003/instance" xmlns:us-gaap="http://fasb.org/us-gaap/2024" xmlns:dei="http://xbrl.sec.gov/dei/2024"><body>
+        <div style="display:none"><ix:header><ix:resources>
+          <xbrli:context id="Q3"><xbrli:entity><xbrli:identifier scheme="http://www.sec.gov/CIK">0000001</xbrli:identifier></xbrli:entity>
+            <xbrli:period><xbrli:startDate>2025-07-01</xbrli:startDate><xbrli:endDate>2025-09-30</xbrli:endDate></xbrli:period></xbrli:context>
+          <xbrli:context id="COVER"><xbrli:entity><xbrli:identifier scheme="http://www.sec.gov/CIK">0000001</xbrli:identifier></xbrli:entity>
+            <xbrli:period><xbrli:instant>2025-10-28</xbrli:instant></xbrli:period></xbrli:context>
+          <xbrli:unit id="USD"><xbrli:measure>iso4217:USD</xbrli:measure></xbrli:unit>
+          <xbrli:unit id="shares"><xbrli:measure>xbrli:shares</xbrli:measure></xbrli:unit>
+        </ix:resources></ix:header></div>
+        <p>Form <ix:nonNumeric name="dei:DocumentType" contextRef="Q3">10-Q</ix:nonNumeric></p>
+        <p>For the quarterly period ended <ix:nonNumeric name="dei:DocumentPeriodEndDate" contextRef="Q3" format="ixt:date-monthname-day-year-en">September 30, 2025</ix:nonNumeric></p>
+        <p><ix:nonNumeric name="dei:DocumentFiscalPeriodFocus" contextRef="Q3">Q3</ix:nonNumeric>
+           <ix:nonNumeric name="dei:DocumentFiscalYearFocus" contextRef="Q3">2025</ix:nonNumeric></p>
+        <p>Securities registered pursuant to Section 12(g): <ix:nonFraction name="dei:EntityNumberOfSecuritiesRegistered" contextRef="Q3" unitRef="shares" format="ixt-sec:numwordsen" decimals="INF">None</ix:nonFraction></p>
+        <p>Shares outstanding: <ix:nonFraction name="dei:EntityCommonStockSharesOutstanding" contextRef="COVER" unitRef="shares" decimals="INF" format="ixt:num-dot-decimal">N/A</ix:nonFraction></p>
+        <ix:nonNumeric name="us-gaap:BasisOfPresentationTextBlock" contextRef="Q3" continuedAt="more">Basis of
+          <ix:exclude><span>Page 7 of 40</span></ix:exclude>presentation. </ix:nonNumeric>
+        <table><tr><td>Revenues</td><td>$<ix:nonFraction name="us-gaap:Revenues" contextRef="Q3" unitRef="USD" decimals="-6" scale="6" format="ixt:num-dot-decimal">3,000</ix:nonFraction></td></tr></table>
+        <ix:continuation id="more">Continued text.</ix:continuation>
+    </body></html>"#;
+
+    #[test]
+    fn sec_cover_page_facts_are_read_or_recorded_as_unreadable() {
+        let instance = parse_inline_instance(SEC_COVER_PAGE.as_bytes()).unwrap();
+        let value = |name: &str| instance.facts.iter().find(|fact| fact.name == name).unwrap().value.as_str();
+        assert_eq!(value("DocumentType"), "10-Q");
+        assert_eq!(value("DocumentPeriodEndDate"), "2025-09-30");
+        assert_eq!(value("EntityNumberOfSecuritiesRegistered"), "0");
+        assert_eq!(value("Revenues"), "3000000000");
+        assert_eq!(value("BasisOfPresentationTextBlock"), "Basis of\n          presentation. Continued text.");
+
+        assert_eq!(instance.unreadable_facts.len(), 1);
+        assert_eq!(instance.unreadable_facts[0].fact.name, "EntityCommonStockSharesOutstanding");
+        assert_eq!(instance.unreadable_facts[0].fact.value, "N/A");
+
+        let derived = crate::xbrl::derive_earning_release(&instance).unwrap();
+        assert_eq!((derived.quarter, derived.year), (3, 2025));
+    }
+
+    #[test]
+    fn unreadable_facts_that_fill_a_field_reject_the_filing() {
+        let filing = SEC_COVER_PAGE.replace(">3,000<", ">three thousand<");
+        let instance = parse_inline_instance(filing.as_bytes()).unwrap();
+        let error = crate::xbrl::derive_earning_release(&instance).err().unwrap();
+        assert!(error.contains("us-gaap:Revenues"), "{}", error);
+    }
+
+    #[test]
+    fn continuation_chains_that_loop_are_cut() {
+        let filing = SEC_COVER_PAGE.replace(r#"<ix:continuation id="more">"#, r#"<ix:continuation id="more" continuedAt="more">"#);
+        let instance = parse_inline_instance(filing.as_bytes()).unwrap();
+        let text_block = instance.facts.iter().find(|fact| fact.name == "BasisOfPresentationTextBlock").unwrap();
+        assert!(text_block.value.ends_with("presentation. Continued text."));
+    }
+
+    #[test]
+    fn inline_facts_are_read_as_plain_decimals() {
+        let xhtml = r#"<html xmlns="http://www.w3.org/1999/xhtml" xmlns:ix="http://www.xbrl.org/2013/inlineXBRL"
+            xmlns:ixt="http://www.xbrl.org/inlineXBRL/transformation/2020-02-12" xmlns:xbrli="http://www.xbrl.org/2003/instance"
+            xmlns:us-gaap="http://fasb.org/us-gaap/2024" xmlns:dei="http://xbrl.sec.gov/dei/2024"><body>
+            <ix:header><ix:resources>
+              <xbrli:context id="Q3"><xbrli:entity><xbrli:identifier scheme="http://www.sec.gov/CIK">0000001</xbrli:identifier></xbrli:entity>
+                <xbrli:period><xbrli:startDate>2025-07-01</xbrli:startDate><xbrli:endDate>2025-09-30</xbrli:endDate></xbrli:period></xbrli:context>
+              <xbrli:unit id="USD"><xbrli:measure>iso4217:USD</xbrli:measure></xbrli:unit>
+            </ix:resources></ix:header>
+            <p><ix:nonNumeric name="dei:DocumentPeriodEndDate" contextRef="Q3" format="ixt:date-monthname-day-year-en">September 30, 2025</ix:nonNumeric></p>
+            <td><ix:nonFraction name="us-gaap:Revenues" contextRef="Q3" unitRef="USD" decimals="-5" scale="6" format="ixt:num-dot-decimal">3,000.1</ix:nonFraction></td>
+            <td>(<ix:nonFraction name="us-gaap:NetIncomeLoss" contextRef="Q3" unitRef="USD" decimals="-5" scale="6" sign="-" format="ixt:num-dot-decimal">12.5</ix:nonFraction>)</td>
+        </body></html>"#;
+
+        let instance = parse_inline_instance(xhtml.as_bytes()).unwrap();
+        let value = |name: &str| instance.facts.iter().find(|fact| fact.name == name).unwrap().value.as_str();
+        assert_eq!(value("DocumentPeriodEndDate"), "2025-09-30");
+        assert_eq!(value("Revenues"), "3000100000");
+        assert_eq!(value("NetIncomeLoss"), "-12500000");
+        assert_eq!(instance.facts[1].concept(), "us-gaap:Revenues");
+    }
+}
@@ -0,0 +1,268 @@
+// XBRL instance documents and inline XBRL filings: contexts, units and facts
+// Only what is needed to derive structured document data; taxonomies are not loaded
+
+pub mod earning_release;
+pub mod inline;
+
+pub use earning_release::{derive_earning_release, reconcile_earning_release, statements_in_units, XbrlEarningRelease};
+pub use inline::{is_inline_xbrl, parse_inline_instance};
+
+use std::collections::BTreeMap;
+use roxmltree::Node;
+
+pub const XBRLI_NAMESPACE: &str = "http://www.xbrl.org/2003/instance";
+const XSI_NAMESPACE: &str = "http://www.w3.org/2001/XMLSchema-instance";
+const ISO4217_NAMESPACE: &str = "http://www.xbrl.org/2003/iso4217";
+
+// Namespaces whose elements are never facts
+const NON_FACT_NAMESPACES: &[&str] = &[
+    XBRLI_NAMESPACE,
+    "http://www.xbrl.org/2003/linkbase",
+    "http://www.w3.org/1999/xlink",
+    "http://www.xbrl.org/2003/XLink",
+];
+
+/// Calendar date of a context period
+#[derive(Clone, Copy, Debug, PartialEq, Eq, PartialOrd, Ord)]
+pub struct XbrlDate {
+    pub year: u16,
+    pub month: u8,
+    pub day: u8,
+}
+
+impl XbrlDate {
+    /// Parse an xs:date (YYYY-MM-DD); time and timezone suffixes are ignored
+    pub fn parse(text: &str) -> Option<XbrlDate> {
+        let date = text.trim().get(..10)?;
+        let mut parts = date.split('-');
+        let year = parts.next()?.parse().ok()?;
+        let month = parts.next()?.parse().ok()?;
+        let day = parts.next()?.parse().ok()?;
+        if !(1..=12).contains(&month) || !(1..=31).contains(&day) {
+            return None;
+        }
+        Some(XbrlDate { year, month, day })
+    }
+
+    /// Days since 1970-01-01 (proleptic Gregorian calendar)
+    pub fn days_since_epoch(&self) -> i64 {
+        crate::utils::days_from_civil(self.year as i64, self.month as u32, self.day as u32)
+    }
+
+    /// Calendar quarter (1-4) the date falls in
+    pub fn quarter(&self) -> u8 {
+        (self.month - 1) / 3 + 1
+    }
+}
+
+impl std::fmt::Display for XbrlDate {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{:04}-{:02}-{:02}", self.year, self.month, self.day)
+    }
+}
+
+#[derive(Clone, Debug, PartialEq)]
+pub enum XbrlPeriod {
+    Instant(XbrlDate),
+    Duration { start: XbrlDate, end: XbrlDate },
+    Forever,
+}
+
+impl XbrlPeriod {
+    /// Date the period ends on (None for forever)
+    pub fn end_date(&self) -> Option<XbrlDate> {
+        match self {
+            XbrlPeriod::Instant(date) | XbrlPeriod::Duration { end: date, .. } => Some(*date),
+            XbrlPeriod::Forever => None,
+        }
+    }
+}
+
+#[derive(Clone, Debug)]
+pub struct XbrlContext {
+    pub id: String,
+    pub entity_identifier: String,
+    pub period: XbrlPeriod,
+    // Contexts with segment or scenario members report a breakdown, not the consolidated total
+    pub has_dimensions: bool,
+}
+
+#[derive(Clone, Debug, PartialEq)]
+pub enum XbrlUnit {
+    Measure(String),                                  // e.g. "iso4217:EGP", "xbrli:shares"
+    Divide { numerator: String, denominator: String }, // e.g. per-share amounts
+}
+
+impl XbrlUnit {
+    /// ISO 4217 code of a monetary unit
+    pub fn currency(&self) -> Option<&str> {
+        match self {
+            XbrlUnit::Measure(measure) => measure.strip_prefix("iso4217:"),
+            XbrlUnit::Divide { .. } => None,
+        }
+    }
+}
+
+impl std::fmt::Display for XbrlUnit {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            XbrlUnit::Measure(measure) => write!(f, "{}", measure),
+            XbrlUnit::Divide { numerator, denominator } => write!(f, "{}/{}", numerator, denominator),
+        }
+    }
+}
+
+#[derive(Clone, Debug)]
+pub struct XbrlFact {
+    pub namespace: String,
+    pub prefix: String,
+    pub name: String,
+    pub context_ref: String,
+    pub unit_ref: Option<String>,
+    pub value: String, // numeric facts are already scaled and signed
+    pub is_nil: bool,
+}
+
+impl XbrlFact {
+    /// Prefixed concept name, e.g. "us-gaap:Revenues"
+    pub fn concept(&self) -> String {
+        if self.prefix.is_empty() {
+            self.name.clone()
+        } else {
+            format!("{}:{}", self.prefix, self.name)
+        }
+    }
+}
+
+/// Tagged fact whose displayed value could not be converted; value holds the displayed text
+#[derive(Clone, Debug)]
+pub struct UnreadableFact {
+    pub fact: XbrlFact,
+    pub error: String,
+}
+
+#[derive(Clone, Debug, Default)]
+pub struct XbrlInstance {
+    pub contexts: BTreeMap<String, XbrlContext>,
+    pub units: BTreeMap<String, XbrlUnit>,
+    pub facts: Vec<XbrlFact>,
+    // Only inline XBRL has displayed values to convert; they matter only if derivation needs them
+    pub unreadable_facts: Vec<UnreadableFact>,
+}
+
+/// Parse an XBRL instance document
+pub fn parse_instance(data: &[u8]) -> Result<XbrlInstance, String> {
+    let text = std::str::from_utf8(data).map_err(|_| "XBRL instance is not valid UTF-8".to_string())?;
+    let document = roxmltree::Document::parse(text.trim_start_matches('\u{FEFF}'))
+        .map_err(|e| format!("XBRL instance is not well-formed XML: {}", e))?;
+
+    let root = document.root_element();
+    if root.tag_name().name() != "xbrl" {
+        return Err(format!("Expected an XBRL instance (xbrl root element), found <{}>", root.tag_name().name()));
+    }
+
+    let mut instance = XbrlInstance::default();
+    for node in root.children().filter(Node::is_element) {
+        match node.tag_name().name() {
+            "context" => {
+                let context = parse_context(node)?;
+                instance.contexts.insert(context.id.clone(), context);
+            }
+            "unit" => {
+                let (id, unit) = parse_unit(node)?;
+                instance.units.insert(id, unit);
+            }
+            _ => {
+                if let Some(fact) = parse_fact(node) {
+                    instance.facts.push(fact);
+                }
+            }
+        }
+    }
+
+    Ok(instance)
+}
+
+/// Parse an xbrli:context element
+pub fn parse_context(node: Node) -> Result<XbrlContext, String> {
+    let id = node.attribute("id").ok_or("XBRL context without an id")?.to_string();
+    let entity = child_element(node, "entity").ok_or_else(|| format!("XBRL context {} has no entity", id))?;
+    let entity_identifier = child_element(entity, "identifier").map(element_text).unwrap_or_default();
+    let has_dimensions = child_element(entity, "segment").is_some() || child_element(node, "scenario").is_some();
+
+    let period_node = child_element(node, "period").ok_or_else(|| format!("XBRL context {} has no period", id))?;
+    let date = |name: &str| {
+        child_element(period_node, name)
+            .and_then(|element| XbrlDate::parse(&element_text(element)))
+            .ok_or_else(|| format!("XBRL context {} has an invalid {}", id, name))
+    };
+
+    let period = if child_element(period_node, "instant").is_some() {
+        XbrlPeriod::Instant(date("instant")?)
+    } else if child_element(period_node, "forever").is_some() {
+        XbrlPeriod::Forever
+    } else {
+        XbrlPeriod::Duration { start: date("startDate")?, end: date("endDate")? }
+    };
+
+    Ok(XbrlContext { id, entity_identifier, period, has_dimensions })
+}
+
+/// Parse an xbrli:unit element
+pub fn parse_unit(node: Node) -> Result<(String, XbrlUnit), String> {
+    let id = node.attribute("id").ok_or("XBRL unit without an id")?.to_string();
+    let measure = |parent: Node| child_element(parent, "measure").map(normalize_measure).unwrap_or_default();
+
+    let unit = match child_element(node, "divide") {
+        Some(divide) => XbrlUnit::Divide {
+            numerator: child_element(divide, "unitNumerator").map(measure).unwrap_or_default(),
+            denominator: child_element(divide, "unitDenominator").map(measure).unwrap_or_default(),
+        },
+        None => XbrlUnit::Measure(measure(node)),
+    };
+
+    Ok((id, unit))
+}
+
+// Facts are elements outside the XBRL infrastructure namespaces that reference a context
+fn parse_fact(node: Node) -> Option<XbrlFact> {
+    let namespace = node.tag_name().namespace().unwrap_or_default();
+    if NON_FACT_NAMESPACES.contains(&namespace) {
+        return None;
+    }
+    let context_ref = node.attribute("contextRef")?;
+
+    Some(XbrlFact {
+        namespace: namespace.to_string(),
+        prefix: node.lookup_prefix(namespace).unwrap_or_default().to_string(),
+        name: node.tag_name().name().to_string(),
+        context_ref: context_ref.to_string(),
+        unit_ref: node.attribute("unitRef").map(str::to_string),
+        value: element_text(node),
+        is_nil: node.attribute((XSI_NAMESPACE, "nil")) == Some("true"),
+    })
+}
+
+// Measures are QNames; currencies are reported with the conventional iso4217 prefix whatever the filer used
+fn normalize_measure(node: Node) -> String {
+    let text = element_text(node);
+    match text.split_once(':') {
+        Some((prefix, code)) if node.lookup_namespace_uri(Some(prefix)) == Some(ISO4217_NAMESPACE) => format!("iso4217:{}", code),
+        _ => text,
+    }
+}
+
+/// First child element with the given local name
+pub fn child_element<'a, 'input>(node: Node<'a, 'input>, name: &str) -> Option<Node<'a, 'input>> {
+    node.children().find(|child| child.is_element() && child.tag_name().name() == name)
+}
+
+/// Concatenated text content of an element, trimmed
+pub fn element_text(node: Node) -> String {
+    node.descendants()
+        .filter(Node::is_text)
+        .filter_map(|text| text.text())
+        .collect::<String>()
+        .trim()
+        .to_string()
+}
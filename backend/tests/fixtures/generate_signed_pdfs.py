@@ -0,0 +1,87 @@
+#!/usr/bin/env python3
+# Regenerates the signed PDFs used by the signature tests in src/pdf/signatures.rs
+# Usage: python3 backend/tests/fixtures/generate_signed_pdfs.py (needs the openssl command line tool)
+#
+# Each file is a one-page PDF with a detached CMS signature over its ByteRange, made with a fresh
+# self-signed certificate valid for ten years. Keys, certificates and signing times change on every run;
+# the tests only rely on the signatures verifying. Tampered and malformed variants are derived in the tests.
+
+import os
+import subprocess
+import tempfile
+
+FIXTURES = os.path.dirname(os.path.abspath(__file__))
+CONTENTS_HEX_LENGTH = 8192  # room for the CMS structure with one certificate
+
+
+def build_pdf():
+    objects = [
+        b"<< /Type /Catalog /Pages 2 0 R /AcroForm << /Fields [4 0 R] /SigFlags 3 >> >>",
+        b"<< /Type /Pages /Kids [3 0 R] /Count 1 >>",
+        b"<< /Type /Page /Parent 2 0 R /MediaBox [0 0 612 792] /Annots [4 0 R] /Contents 6 0 R"
+        b" /Resources << /Font << /F1 << /Type /Font /Subtype /Type1 /BaseFont /Helvetica >> >> >> >>",
+        b"<< /FT /Sig /T (Signature1) /V 5 0 R /Type /Annot /Subtype /Widget /Rect [0 0 0 0] /P 3 0 R >>",
+        b"<< /Type /Sig /Filter /Adobe.PPKLite /SubFilter /ETSI.CAdES.detached /M (D:20251009120000Z)"
+        b" /ByteRange [0 0000000000 0000000000 0000000000] /Contents <" + b"0" * CONTENTS_HEX_LENGTH + b"> >>",
+        b"<< /Length 44 >>\nstream\nBT /F1 24 Tf 72 700 Td (Signed hello) Tj ET\nendstream",
+    ]
+    pdf = bytearray(b"%PDF-1.7\n")
+    offsets = []
+    for number, body in enumerate(objects, 1):
+        offsets.append(len(pdf))
+        pdf += b"%d 0 obj\n%s\nendobj\n" % (number, body)
+    xref = len(pdf)
+    pdf += b"xref\n0 %d\n0000000000 65535 f \n" % (len(objects) + 1)
+    for offset in offsets:
+        pdf += b"%010d 00000 n \n" % offset
+    pdf += b"trailer\n<< /Size %d /Root 1 0 R >>\nstartxref\n%d\n%%%%EOF\n" % (len(objects) + 1, xref)
+
+    # The signed bytes are everything except the Contents hex string, delimiters included
+    contents_start = pdf.index(b"/Contents <") + len(b"/Contents ")
+    contents_end = pdf.index(b">", contents_start) + 1
+    byte_range = b"0 %010d %010d %010d" % (contents_start, contents_end, len(pdf) - contents_end)
+    placeholder = pdf.index(b"0 0000000000 0000000000 0000000000")
+    pdf[placeholder:placeholder + len(byte_range)] = byte_range
+    return pdf, contents_start
+
+
+def sign(pdf, contents_start, key, certificate, directory):
+    contents_end = pdf.index(b">", contents_start) + 1
+    signed_path = os.path.join(directory, "signed_bytes")
+    with open(signed_path, "wb") as file:
+        file.write(bytes(pdf[:contents_start]) + bytes(pdf[contents_end:]))
+    signature = subprocess.check_output([
+        "openssl", "cms", "-sign", "-binary", "-outform", "DER", "-nosmimecap", "-md", "sha256",
+        "-in", signed_path, "-signer", certificate, "-inkey", key,
+    ]).hex().encode()
+    assert len(signature) <= CONTENTS_HEX_LENGTH
+    pdf[contents_start + 1:contents_start + 1 + len(signature)] = signature
+
+
+def self_signed_certificate(directory, name, key_options):
+    key = os.path.join(directory, name + ".key")
+    certificate = os.path.join(directory, name + ".pem")
+    subprocess.check_call([
+        "openssl", "req", "-x509", "-newkey", *key_options, "-nodes", "-days", "3650",
+        "-subj", "/CN=Test Signer/O=ChainNotary Test/C=US", "-keyout", key, "-out", certificate,
+    ], stderr=subprocess.DEVNULL)
+    return key, certificate
+
+
+def main():
+    with tempfile.TemporaryDirectory() as directory:
+        for name, key_options in [
+            ("signed_rsa.pdf", ["rsa:2048"]),
+            ("signed_ec.pdf", ["ec", "-pkeyopt", "ec_paramgen_curve:P-256"]),
+        ]:
+            key, certificate = self_signed_certificate(directory, name, key_options)
+            pdf, contents_start = build_pdf()
+            sign(pdf, contents_start, key, certificate, directory)
+            path = os.path.join(FIXTURES, name)
+            with open(path, "wb") as file:
+                file.write(pdf)
+            print("wrote", path)
+
+
+if __name__ == "__main__":
+    main()
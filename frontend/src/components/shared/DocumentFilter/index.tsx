@@ -104,6 +104,12 @@ const DocumentFilter: React.FC<DocumentFilterProps> = ({
           <Form.Item name="document_type" label="Document Type">
             <Select placeholder="Select type" allowClear>
               <Option value="EarningRelease">Earning Release</Option>
+              <Option value="AnnualReport">Annual Report</Option>
+              <Option value="DividendAnnouncement">
+                Dividend Announcement
+              </Option>
+              <Option value="BoardResolution">Board Resolution</Option>
+              <Option value="Prospectus">Prospectus</Option>
             </Select>
           </Form.Item>
         </Col>
@@ -43,10 +43,16 @@ const DocumentList: FC<DocumentListProps> = ({
   };
 
   const getDocumentTypeLabel = (doc: Document) => {
-    if (doc.document_data.EarningRelease) {
-      const data = doc.document_data.EarningRelease;
-      return `Q${data.quarter} ${data.year}`;
+    const data = doc.document_data;
+    if ("EarningRelease" in data) {
+      return `Q${data.EarningRelease.quarter} ${data.EarningRelease.year}`;
     }
+    if ("AnnualReport" in data) return `FY ${data.AnnualReport.fiscal_year}`;
+    if ("DividendAnnouncement" in data)
+      return `Dividend FY ${data.DividendAnnouncement.fiscal_year}`;
+    if ("BoardResolution" in data)
+      return `Resolution ${data.BoardResolution.resolution_number}`;
+    if ("Prospectus" in data) return data.Prospectus.security_type;
     return "Unknown";
   };
 
@@ -5,21 +5,24 @@ import type { BaseButtonProps } from 'antd/es/button/button';
 import { type FC } from 'react';
 
 type Props = {
-  file_data: Uint8Array | number[],
+  file_data?: Uint8Array | number[],
+  // Fetches the contents on click when file_data is not loaded
+  getFileData?: () => Promise<Uint8Array>,
   file_type: string,
   file_name: string
 } & BaseButtonProps
 
 const DownLoadButton: FC<Props> = (props) => {
-  const { file_data, file_type, file_name, children } = props;
-
-  const handleDownload = () => {
-    if (!file_data || file_data.length === 0) {
-      console.error("No data provided for download.");
-      return;
-    }
+  const { file_data, getFileData, file_type, file_name, children, ...buttonProps } = props;
 
+  const handleDownload = async () => {
     try {
+      const data = file_data && file_data.length > 0 ? file_data : await getFileData?.();
+      if (!data || data.length === 0) {
+        console.error("No data provided for download.");
+        return;
+      }
+
       // Infer extension if not given
       let finalFileName = file_name;
       if (!finalFileName) {
@@ -31,7 +34,7 @@ const DownLoadButton: FC<Props> = (props) => {
       }
 
       // Convert byte array to a Blob
-      const blob = new Blob([new Uint8Array(file_data)], { type: file_type });
+      const blob = new Blob([new Uint8Array(data)], { type: file_type });
 
       // Create temporary object URL
       const url = window.URL.createObjectURL(blob);
@@ -53,7 +56,7 @@ const DownLoadButton: FC<Props> = (props) => {
   };
 
   return (
-    <Button className="inline-flex items-center px-6 py-3 border border-gray-300 rounded-md shadow-sm text-sm font-medium text-gray-700 bg-white hover:bg-gray-50 focus:outline-none focus:ring-2 focus:ring-offset-2 focus:ring-blue-500" {...props} onClick={handleDownload} icon={<DownloadOutlined />}>
+    <Button className="inline-flex items-center px-6 py-3 border border-gray-300 rounded-md shadow-sm text-sm font-medium text-gray-700 bg-white hover:bg-gray-50 focus:outline-none focus:ring-2 focus:ring-offset-2 focus:ring-blue-500" {...buttonProps} onClick={handleDownload} icon={<DownloadOutlined />}>
       {children}
     </Button>
   )
@@ -1,6 +1,7 @@
 import { useAppSelector } from "@/store/hooks";
 import computeFileHash from "@/utils/compileFileHash";
 import getUint8Array from "@/utils/getUint8Array";
+import { toDecimal } from "@/utils/decimal";
 import { parseXBRL } from "@/utils/xbrlParser";
 import { InboxOutlined } from "@ant-design/icons";
 import { Upload, type FormInstance, type UploadProps } from "antd";
@@ -20,8 +21,11 @@ const Dragger: FC<Props> = ({ form }) => {
     maxCount: 1,
 
     onChange: async (info) => {
-      const { status, size, type: file_type, originFileObj } = info.file;
+      const { status, size, type, originFileObj } = info.file;
       if (status === "done") {
+        // Browsers report no type for .xbrl files; the canister reads XBRL instances as XML
+        const isXbrl = info.file.name?.toLowerCase().endsWith(".xbrl");
+        const file_type = isXbrl ? "application/xml" : type;
         const name = form.getFieldValue("name");
         const file_data = await getUint8Array(originFileObj as File);
         const file_hash = await computeFileHash(file_data);
@@ -33,8 +37,8 @@ const Dragger: FC<Props> = ({ form }) => {
           name: name || info.file.name,
         });
 
-        // Auto-fill form if XBRL file
-        if (info.file.name?.toLowerCase().endsWith(".xbrl")) {
+        // Auto-fill form if XBRL file (the canister replaces the figures with the filed values on publication)
+        if (isXbrl) {
           try {
             const text = new TextDecoder().decode(file_data);
             const xbrlData = parseXBRL(text);
@@ -49,16 +53,18 @@ const Dragger: FC<Props> = ({ form }) => {
             if (xbrlData.quarter) earningReleaseData.quarter = xbrlData.quarter;
 
             // Balance sheet data
-            const balanceSheetData: Record<string, number> = {};
+            const balanceSheetData: Record<string, bigint> = {};
             if (xbrlData.totalAssets)
-              balanceSheetData.total_assets = xbrlData.totalAssets;
+              balanceSheetData.total_assets = toDecimal(xbrlData.totalAssets);
             if (xbrlData.totalLiabilities)
-              balanceSheetData.total_liabilities = xbrlData.totalLiabilities;
+              balanceSheetData.total_liabilities = toDecimal(
+                xbrlData.totalLiabilities,
+              );
             if (xbrlData.totalEquity)
-              balanceSheetData.total_equity = xbrlData.totalEquity;
+              balanceSheetData.total_equity = toDecimal(xbrlData.totalEquity);
             if (xbrlData.totalLiabilitiesAndEquity)
               balanceSheetData.total_liabilities_and_equity =
-                xbrlData.totalLiabilitiesAndEquity;
+                toDecimal(xbrlData.totalLiabilitiesAndEquity);
 
             if (Object.keys(balanceSheetData).length > 0) {
               earningReleaseData.consolidated_balance_sheet_data =
@@ -66,15 +72,20 @@ const Dragger: FC<Props> = ({ form }) => {
             }
 
             // Income data
-            const incomeData: Record<string, number> = {};
+            const incomeData: Record<string, bigint> = {};
             if (xbrlData.grossProfit)
-              incomeData.gross_profit = xbrlData.grossProfit;
-            if (xbrlData.netProfit) incomeData.net_profit = xbrlData.netProfit;
+              incomeData.gross_profit = toDecimal(xbrlData.grossProfit);
+            if (xbrlData.netProfit)
+              incomeData.net_profit = toDecimal(xbrlData.netProfit);
             if (xbrlData.operatingProfit)
-              incomeData.operating_profit = xbrlData.operatingProfit;
+              incomeData.operating_profit = toDecimal(
+                xbrlData.operatingProfit,
+              );
             if (xbrlData.profitBeforeTax)
-              incomeData.profit_before_tax = xbrlData.profitBeforeTax;
-            if (xbrlData.ebitda) incomeData.ebitda = xbrlData.ebitda;
+              incomeData.profit_before_tax = toDecimal(
+                xbrlData.profitBeforeTax,
+              );
+            if (xbrlData.ebitda) incomeData.ebitda = toDecimal(xbrlData.ebitda);
 
             if (Object.keys(incomeData).length > 0) {
               earningReleaseData.consolidated_income_data = incomeData;
@@ -2,6 +2,7 @@ import Dragger from "@/components/shared/Dragger";
 import useFormValidation from "@/hooks/useFormValidation";
 import createDocumentService from "@/services/documents/createDocument.service";
 import { useAppSelector } from "@/store/hooks";
+import { fromDecimal, toDecimal } from "@/utils/decimal";
 import { inputFormatter as formatter } from "@/utils/formatNumberWithCommas";
 import { Principal } from "@dfinity/principal";
 import {
@@ -12,6 +13,7 @@ import {
   Input,
   InputNumber,
   Row,
+  Select,
   Typography,
   type FormProps,
 } from "antd";
@@ -19,6 +21,12 @@ import type { Document } from "declarations/backend/backend.did";
 import { useState, type FC } from "react";
 import { useNavigate } from "react-router";
 
+const reportingScaleOptions = [
+  { value: "Units", label: "Units" },
+  { value: "Thousands", label: "Thousands" },
+  { value: "Millions", label: "Millions" },
+];
+
 const CreateDocument: FC = () => {
   const [isLoading, setIsLoading] = useState(false);
   const navigate = useNavigate();
@@ -154,6 +162,46 @@ const CreateDocument: FC = () => {
                     Consolidated Balance Sheet
                   </Typography.Title>
                   <Row gutter={[16, 16]}>
+                    <Col xs={{ span: 24 }} md={{ span: 12 }}>
+                      <Form.Item
+                        label="Currency"
+                        name={[
+                          "document_data",
+                          "EarningRelease",
+                          "consolidated_balance_sheet_data",
+                          "currency",
+                        ]}
+                        hasFeedback
+                        normalize={(value: string) => value?.toUpperCase()}
+                        rules={[
+                          {
+                            required: true,
+                            pattern: /^[A-Z]{3}$/,
+                            message: "Please input an ISO 4217 currency code!",
+                          },
+                        ]}
+                      >
+                        <Input placeholder="EGP" maxLength={3} />
+                      </Form.Item>
+                    </Col>
+                    <Col xs={{ span: 24 }} md={{ span: 12 }}>
+                      <Form.Item
+                        label="Reported In"
+                        name={[
+                          "document_data",
+                          "EarningRelease",
+                          "consolidated_balance_sheet_data",
+                          "scale",
+                        ]}
+                        initialValue={{ Units: null }}
+                        getValueProps={(value) => ({
+                          value: value && Object.keys(value)[0],
+                        })}
+                        normalize={(value: string) => ({ [value]: null })}
+                      >
+                        <Select options={reportingScaleOptions} />
+                      </Form.Item>
+                    </Col>
                     <Col xs={{ span: 24 }} md={{ span: 12 }}>
                       <Form.Item
                         label="Total Equity"
@@ -164,6 +212,10 @@ const CreateDocument: FC = () => {
                           "total_equity",
                         ]}
                         hasFeedback
+                        normalize={toDecimal}
+                        getValueProps={(value) => ({
+                          value: fromDecimal(value),
+                        })}
                         rules={[
                           {
                             required: true,
@@ -172,6 +224,7 @@ const CreateDocument: FC = () => {
                         ]}
                       >
                         <InputNumber
+                          stringMode
                           formatter={formatter}
                           parser={(value) =>
                             value?.replace(
@@ -193,6 +246,10 @@ const CreateDocument: FC = () => {
                           "total_liabilities_and_equity",
                         ]}
                         hasFeedback
+                        normalize={toDecimal}
+                        getValueProps={(value) => ({
+                          value: fromDecimal(value),
+                        })}
                         rules={[
                           {
                             required: true,
@@ -202,6 +259,7 @@ const CreateDocument: FC = () => {
                         ]}
                       >
                         <InputNumber
+                          stringMode
                           formatter={formatter}
                           parser={(value) =>
                             value?.replace(
@@ -223,6 +281,10 @@ const CreateDocument: FC = () => {
                           "total_assets",
                         ]}
                         hasFeedback
+                        normalize={toDecimal}
+                        getValueProps={(value) => ({
+                          value: fromDecimal(value),
+                        })}
                         rules={[
                           {
                             required: true,
@@ -231,6 +293,7 @@ const CreateDocument: FC = () => {
                         ]}
                       >
                         <InputNumber
+                          stringMode
                           formatter={formatter}
                           parser={(value) =>
                             value?.replace(
@@ -252,6 +315,10 @@ const CreateDocument: FC = () => {
                           "total_liabilities",
                         ]}
                         hasFeedback
+                        normalize={toDecimal}
+                        getValueProps={(value) => ({
+                          value: fromDecimal(value),
+                        })}
                         rules={[
                           {
                             required: true,
@@ -260,6 +327,7 @@ const CreateDocument: FC = () => {
                         ]}
                       >
                         <InputNumber
+                          stringMode
                           formatter={formatter}
                           parser={(value) =>
                             value?.replace(
@@ -280,6 +348,114 @@ const CreateDocument: FC = () => {
                     Consolidated Income
                   </Typography.Title>
                   <Row gutter={[16, 16]}>
+                    <Col xs={{ span: 24 }} md={{ span: 12 }}>
+                      <Form.Item
+                        label="Currency"
+                        name={[
+                          "document_data",
+                          "EarningRelease",
+                          "consolidated_income_data",
+                          "currency",
+                        ]}
+                        hasFeedback
+                        normalize={(value: string) => value?.toUpperCase()}
+                        rules={[
+                          {
+                            required: true,
+                            pattern: /^[A-Z]{3}$/,
+                            message: "Please input an ISO 4217 currency code!",
+                          },
+                        ]}
+                      >
+                        <Input placeholder="EGP" maxLength={3} />
+                      </Form.Item>
+                    </Col>
+                    <Col xs={{ span: 24 }} md={{ span: 12 }}>
+                      <Form.Item
+                        label="Reported In"
+                        name={[
+                          "document_data",
+                          "EarningRelease",
+                          "consolidated_income_data",
+                          "scale",
+                        ]}
+                        initialValue={{ Units: null }}
+                        getValueProps={(value) => ({
+                          value: value && Object.keys(value)[0],
+                        })}
+                        normalize={(value: string) => ({ [value]: null })}
+                      >
+                        <Select options={reportingScaleOptions} />
+                      </Form.Item>
+                    </Col>
+                    <Col xs={{ span: 24 }} md={{ span: 12 }}>
+                      <Form.Item
+                        label="Revenue"
+                        name={[
+                          "document_data",
+                          "EarningRelease",
+                          "consolidated_income_data",
+                          "revenue",
+                        ]}
+                        hasFeedback
+                        normalize={toDecimal}
+                        getValueProps={(value) => ({
+                          value: fromDecimal(value),
+                        })}
+                        rules={[
+                          {
+                            required: true,
+                            message: "Please input the revenue!",
+                          },
+                        ]}
+                      >
+                        <InputNumber
+                          stringMode
+                          formatter={formatter}
+                          parser={(value) =>
+                            value?.replace(
+                              /\$\s?|(,*)/g,
+                              "",
+                            ) as unknown as number
+                          }
+                          className="w-full!"
+                        />
+                      </Form.Item>
+                    </Col>
+                    <Col xs={{ span: 24 }} md={{ span: 12 }}>
+                      <Form.Item
+                        label="Cost of Sales"
+                        name={[
+                          "document_data",
+                          "EarningRelease",
+                          "consolidated_income_data",
+                          "cost_of_sales",
+                        ]}
+                        hasFeedback
+                        normalize={toDecimal}
+                        getValueProps={(value) => ({
+                          value: fromDecimal(value),
+                        })}
+                        rules={[
+                          {
+                            required: true,
+                            message: "Please input the cost of sales!",
+                          },
+                        ]}
+                      >
+                        <InputNumber
+                          stringMode
+                          formatter={formatter}
+                          parser={(value) =>
+                            value?.replace(
+                              /\$\s?|(,*)/g,
+                              "",
+                            ) as unknown as number
+                          }
+                          className="w-full!"
+                        />
+                      </Form.Item>
+                    </Col>
                     <Col xs={{ span: 24 }} md={{ span: 12 }}>
                       <Form.Item
                         label="EBITDA"
@@ -290,6 +466,10 @@ const CreateDocument: FC = () => {
                           "ebitda",
                         ]}
                         hasFeedback
+                        normalize={toDecimal}
+                        getValueProps={(value) => ({
+                          value: fromDecimal(value),
+                        })}
                         rules={[
                           {
                             required: true,
@@ -298,6 +478,7 @@ const CreateDocument: FC = () => {
                         ]}
                       >
                         <InputNumber
+                          stringMode
                           formatter={formatter}
                           parser={(value) =>
                             value?.replace(
@@ -319,6 +500,10 @@ const CreateDocument: FC = () => {
                           "gross_profit",
                         ]}
                         hasFeedback
+                        normalize={toDecimal}
+                        getValueProps={(value) => ({
+                          value: fromDecimal(value),
+                        })}
                         rules={[
                           {
                             required: true,
@@ -327,6 +512,7 @@ const CreateDocument: FC = () => {
                         ]}
                       >
                         <InputNumber
+                          stringMode
                           formatter={formatter}
                           parser={(value) =>
                             value?.replace(
@@ -348,6 +534,10 @@ const CreateDocument: FC = () => {
                           "net_profit",
                         ]}
                         hasFeedback
+                        normalize={toDecimal}
+                        getValueProps={(value) => ({
+                          value: fromDecimal(value),
+                        })}
                         rules={[
                           {
                             required: true,
@@ -356,6 +546,7 @@ const CreateDocument: FC = () => {
                         ]}
                       >
                         <InputNumber
+                          stringMode
                           formatter={formatter}
                           parser={(value) =>
                             value?.replace(
@@ -377,6 +568,10 @@ const CreateDocument: FC = () => {
                           "operating_profit",
                         ]}
                         hasFeedback
+                        normalize={toDecimal}
+                        getValueProps={(value) => ({
+                          value: fromDecimal(value),
+                        })}
                         rules={[
                           {
                             required: true,
@@ -385,6 +580,7 @@ const CreateDocument: FC = () => {
                         ]}
                       >
                         <InputNumber
+                          stringMode
                           formatter={formatter}
                           parser={(value) =>
                             value?.replace(
@@ -406,12 +602,267 @@ const CreateDocument: FC = () => {
                           "profit_before_tax",
                         ]}
                         hasFeedback
+                        normalize={toDecimal}
+                        getValueProps={(value) => ({
+                          value: fromDecimal(value),
+                        })}
                         rules={[
                           {
                             required: true,
                             message: "Please input the profit before tax!",
                           },
                         ]}
+                      >
+                        <InputNumber
+                          stringMode
+                          formatter={formatter}
+                          parser={(value) =>
+                            value?.replace(
+                              /\$\s?|(,*)/g,
+                              "",
+                            ) as unknown as number
+                          }
+                          className="w-full!"
+                        />
+                      </Form.Item>
+                    </Col>
+                  </Row>
+                </div>
+              </Col>
+              <Col span={24}>
+                <div className="bg-gray-50 p-4! rounded-lg">
+                  <Typography.Title level={5} className="mb-4">
+                    Consolidated Cash Flow
+                  </Typography.Title>
+                  <Row gutter={[16, 16]}>
+                    <Col xs={{ span: 24 }} md={{ span: 12 }}>
+                      <Form.Item
+                        label="Operating Cash Flow"
+                        name={[
+                          "document_data",
+                          "EarningRelease",
+                          "consolidated_cash_flow_data",
+                          "operating_cash_flow",
+                        ]}
+                        hasFeedback
+                        rules={[
+                          {
+                            required: true,
+                            message: "Please input the operating cash flow!",
+                          },
+                        ]}
+                      >
+                        <InputNumber
+                          formatter={formatter}
+                          parser={(value) =>
+                            value?.replace(
+                              /\$\s?|(,*)/g,
+                              "",
+                            ) as unknown as number
+                          }
+                          className="w-full!"
+                        />
+                      </Form.Item>
+                    </Col>
+                    <Col xs={{ span: 24 }} md={{ span: 12 }}>
+                      <Form.Item
+                        label="Investing Cash Flow"
+                        name={[
+                          "document_data",
+                          "EarningRelease",
+                          "consolidated_cash_flow_data",
+                          "investing_cash_flow",
+                        ]}
+                        hasFeedback
+                        rules={[
+                          {
+                            required: true,
+                            message: "Please input the investing cash flow!",
+                          },
+                        ]}
+                      >
+                        <InputNumber
+                          formatter={formatter}
+                          parser={(value) =>
+                            value?.replace(
+                              /\$\s?|(,*)/g,
+                              "",
+                            ) as unknown as number
+                          }
+                          className="w-full!"
+                        />
+                      </Form.Item>
+                    </Col>
+                    <Col xs={{ span: 24 }} md={{ span: 12 }}>
+                      <Form.Item
+                        label="Financing Cash Flow"
+                        name={[
+                          "document_data",
+                          "EarningRelease",
+                          "consolidated_cash_flow_data",
+                          "financing_cash_flow",
+                        ]}
+                        hasFeedback
+                        rules={[
+                          {
+                            required: true,
+                            message: "Please input the financing cash flow!",
+                          },
+                        ]}
+                      >
+                        <InputNumber
+                          formatter={formatter}
+                          parser={(value) =>
+                            value?.replace(
+                              /\$\s?|(,*)/g,
+                              "",
+                            ) as unknown as number
+                          }
+                          className="w-full!"
+                        />
+                      </Form.Item>
+                    </Col>
+                    <Col xs={{ span: 24 }} md={{ span: 12 }}>
+                      <Form.Item
+                        label="Capital Expenditure"
+                        name={[
+                          "document_data",
+                          "EarningRelease",
+                          "consolidated_cash_flow_data",
+                          "capital_expenditure",
+                        ]}
+                        hasFeedback
+                        rules={[
+                          {
+                            required: true,
+                            message: "Please input the capital expenditure!",
+                          },
+                        ]}
+                      >
+                        <InputNumber
+                          formatter={formatter}
+                          parser={(value) =>
+                            value?.replace(
+                              /\$\s?|(,*)/g,
+                              "",
+                            ) as unknown as number
+                          }
+                          className="w-full!"
+                        />
+                      </Form.Item>
+                    </Col>
+                  </Row>
+                </div>
+              </Col>
+              <Col span={24}>
+                <div className="bg-gray-50 p-4! rounded-lg">
+                  <Typography.Title level={5} className="mb-4">
+                    Per Share
+                  </Typography.Title>
+                  <Row gutter={[16, 16]}>
+                    <Col xs={{ span: 24 }} md={{ span: 12 }}>
+                      <Form.Item
+                        label="EPS (Basic)"
+                        name={[
+                          "document_data",
+                          "EarningRelease",
+                          "per_share_data",
+                          "eps_basic",
+                        ]}
+                        hasFeedback
+                        rules={[
+                          {
+                            required: true,
+                            message: "Please input the basic EPS!",
+                          },
+                        ]}
+                      >
+                        <InputNumber
+                          formatter={formatter}
+                          parser={(value) =>
+                            value?.replace(
+                              /\$\s?|(,*)/g,
+                              "",
+                            ) as unknown as number
+                          }
+                          className="w-full!"
+                        />
+                      </Form.Item>
+                    </Col>
+                    <Col xs={{ span: 24 }} md={{ span: 12 }}>
+                      <Form.Item
+                        label="EPS (Diluted)"
+                        name={[
+                          "document_data",
+                          "EarningRelease",
+                          "per_share_data",
+                          "eps_diluted",
+                        ]}
+                        hasFeedback
+                        rules={[
+                          {
+                            required: true,
+                            message: "Please input the diluted EPS!",
+                          },
+                        ]}
+                      >
+                        <InputNumber
+                          formatter={formatter}
+                          parser={(value) =>
+                            value?.replace(
+                              /\$\s?|(,*)/g,
+                              "",
+                            ) as unknown as number
+                          }
+                          className="w-full!"
+                        />
+                      </Form.Item>
+                    </Col>
+                    <Col xs={{ span: 24 }} md={{ span: 12 }}>
+                      <Form.Item
+                        label="Shares Outstanding"
+                        name={[
+                          "document_data",
+                          "EarningRelease",
+                          "per_share_data",
+                          "shares_outstanding",
+                        ]}
+                        hasFeedback
+                        rules={[
+                          {
+                            required: true,
+                            message: "Please input the shares outstanding!",
+                          },
+                        ]}
+                      >
+                        <InputNumber
+                          formatter={formatter}
+                          parser={(value) =>
+                            value?.replace(
+                              /\$\s?|(,*)/g,
+                              "",
+                            ) as unknown as number
+                          }
+                          className="w-full!"
+                        />
+                      </Form.Item>
+                    </Col>
+                    <Col xs={{ span: 24 }} md={{ span: 12 }}>
+                      <Form.Item
+                        label="Dividends Per Share"
+                        name={[
+                          "document_data",
+                          "EarningRelease",
+                          "per_share_data",
+                          "dividends_per_share",
+                        ]}
+                        hasFeedback
+                        rules={[
+                          {
+                            required: true,
+                            message: "Please input the dividends per share!",
+                          },
+                        ]}
                       >
                         <InputNumber
                           formatter={formatter}
@@ -480,6 +931,9 @@ const CreateDocument: FC = () => {
               hidden
               initialValue={ownerPrincipal}
             ></Form.Item>
+            <Form.Item name="retraction" hidden initialValue={[]}></Form.Item>
+            <Form.Item name="supersedes" hidden initialValue={[]}></Form.Item>
+            <Form.Item name="version" hidden initialValue={1}></Form.Item>
           </Form>
         </div>
       </div>
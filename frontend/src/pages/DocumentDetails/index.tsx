@@ -1,7 +1,9 @@
 import DownLoadButton from "@/components/shared/DownLoadButton";
 import LoadingSpinner from "@/components/shared/LoadingSpinner";
 import getDocumentDetails from "@/services/documents/getDocumentDetails";
+import getDocumentFile from "@/services/documents/getDocumentFile";
 import { useAppSelector } from "@/store/hooks";
+import { formatDecimal, getReportingUnitLabel } from "@/utils/decimal";
 import { formatNumberWithCommas } from "@/utils/formatNumberWithCommas";
 import getLabeledQuarter from "@/utils/getLabeledQuarter";
 import { HomeOutlined } from "@ant-design/icons";
@@ -25,17 +27,35 @@ const DocumentDetails: FC = () => {
       EarningRelease: {
         earning_release_id: "",
         consolidated_balance_sheet_data: {
-          total_assets: 0,
-          total_equity: 0,
-          total_liabilities_and_equity: 0,
-          total_liabilities: 0,
+          currency: "",
+          scale: { Units: null },
+          total_assets: BigInt(0),
+          total_equity: BigInt(0),
+          total_liabilities_and_equity: BigInt(0),
+          total_liabilities: BigInt(0),
         },
         consolidated_income_data: {
-          ebitda: 0,
-          gross_profit: 0,
-          net_profit: 0,
-          operating_profit: 0,
-          profit_before_tax: 0,
+          currency: "",
+          scale: { Units: null },
+          revenue: BigInt(0),
+          cost_of_sales: BigInt(0),
+          ebitda: BigInt(0),
+          gross_profit: BigInt(0),
+          net_profit: BigInt(0),
+          operating_profit: BigInt(0),
+          profit_before_tax: BigInt(0),
+        },
+        consolidated_cash_flow_data: {
+          operating_cash_flow: 0,
+          investing_cash_flow: 0,
+          financing_cash_flow: 0,
+          capital_expenditure: 0,
+        },
+        per_share_data: {
+          eps_basic: 0,
+          eps_diluted: 0,
+          shares_outstanding: 0,
+          dividends_per_share: 0,
         },
         quarter: 0,
         year: 0,
@@ -50,6 +70,9 @@ const DocumentDetails: FC = () => {
     publication_date: BigInt(0),
     owner: Principal.fromText(principal),
     name: "",
+    retraction: [],
+    supersedes: [],
+    version: 1,
   });
   const [isLoading, setIsLoading] = useState<boolean>(true);
   const { id: document_id } = useParams<{ id: string }>();
@@ -92,6 +115,11 @@ const DocumentDetails: FC = () => {
     );
   }
 
+  const earningRelease =
+    "EarningRelease" in documentDetails.document_data
+      ? documentDetails.document_data.EarningRelease
+      : undefined;
+
   return (
     <div className="min-h-screen bg-gray-50 py-12">
       <div className="max-w-2xl mx-auto px-4 sm:px-6 lg:px-8">
@@ -102,7 +130,7 @@ const DocumentDetails: FC = () => {
             {documentDetails.document_id ? (
               <QRCode
                 className="w-full"
-                value={`${window.location.host}/document/${documentDetails.document_id}/view`}
+                value={`${window.location.origin}/verify/${documentDetails.document_id}`}
               />
             ) : (
               <Cross className="w-8 h-8 text-white rotate-45" />
@@ -118,6 +146,16 @@ const DocumentDetails: FC = () => {
               There was an error getting your document. Please try again later.
             </p>
           )}
+          {documentDetails.retraction.length > 0 && (
+            <div className="bg-red-50 border border-red-200 rounded-lg p-4 mb-8 text-left">
+              <p className="text-sm font-medium text-red-700">
+                This document was retracted by its publisher
+              </p>
+              <p className="text-sm text-red-600">
+                {documentDetails.retraction[0].reason}
+              </p>
+            </div>
+          )}
           {documentDetails.document_id && (
             <div className="bg-gray-50 rounded-lg p-6 mb-8">
               <div className="text-left space-y-6">
@@ -149,24 +187,26 @@ const DocumentDetails: FC = () => {
                       </Typography.Paragraph>
                     </Col>
 
-                    <Col xs={{ span: 24 }} md={{ span: 12 }}>
-                      <p className="text-sm font-medium text-gray-500 mb-1 text-center md:text-left">
-                        Quarter
-                      </p>
-                      <Typography.Paragraph className="flex justify-between text-gray-900 font-mono wrap-break-word text-center md:text-left">
-                        {getLabeledQuarter(
-                          documentDetails.document_data.EarningRelease.quarter,
-                        )}
-                      </Typography.Paragraph>
-                    </Col>
-                    <Col xs={{ span: 24 }} md={{ span: 12 }}>
-                      <p className="text-sm font-medium text-gray-500 mb-1 text-center md:text-left">
-                        Year
-                      </p>
-                      <p className="text-gray-900 font-mono text-center md:text-left">
-                        {documentDetails.document_data.EarningRelease.year}
-                      </p>
-                    </Col>
+                    {earningRelease && (
+                      <>
+                        <Col xs={{ span: 24 }} md={{ span: 12 }}>
+                          <p className="text-sm font-medium text-gray-500 mb-1 text-center md:text-left">
+                            Quarter
+                          </p>
+                          <Typography.Paragraph className="flex justify-between text-gray-900 font-mono wrap-break-word text-center md:text-left">
+                            {getLabeledQuarter(earningRelease.quarter)}
+                          </Typography.Paragraph>
+                        </Col>
+                        <Col xs={{ span: 24 }} md={{ span: 12 }}>
+                          <p className="text-sm font-medium text-gray-500 mb-1 text-center md:text-left">
+                            Year
+                          </p>
+                          <p className="text-gray-900 font-mono text-center md:text-left">
+                            {earningRelease.year}
+                          </p>
+                        </Col>
+                      </>
+                    )}
 
                     {documentDetails.description && (
                       <Col span={24}>
@@ -180,124 +220,247 @@ const DocumentDetails: FC = () => {
                     )}
                   </Row>
 
-                  <Divider orientation="center" />
-                  <Typography.Title
-                    level={4}
-                    className="text-center md:text-left"
-                  >
-                    Consolidated Income Statement
-                  </Typography.Title>
-                  <Row gutter={[24, 16]}>
-                    <Col xs={{ span: 24 }} md={{ span: 12 }}>
-                      <p className="text-sm font-medium text-gray-500 mb-1 text-center md:text-left">
-                        EBITDA
-                      </p>
-                      <p className="text-gray-900 font-mono text-sm break-all text-center md:text-left">
-                        {formatNumberWithCommas(
-                          documentDetails?.document_data.EarningRelease
-                            .consolidated_income_data.ebitda,
-                        )}
-                      </p>
-                    </Col>
-                    <Col xs={{ span: 24 }} md={{ span: 12 }}>
-                      <p className="text-sm font-medium text-gray-500 mb-1 text-center md:text-left">
-                        Gross Profit
-                      </p>
-                      <p className="text-gray-900 font-mono text-sm break-all text-center md:text-left">
-                        {formatNumberWithCommas(
-                          documentDetails?.document_data.EarningRelease
-                            .consolidated_income_data.gross_profit,
-                        )}
-                      </p>
-                    </Col>
-                    <Col xs={{ span: 24 }} md={{ span: 12 }}>
-                      <p className="text-sm font-medium text-gray-500 mb-1 text-center md:text-left">
-                        Net Profit
-                      </p>
-                      <p className="text-gray-900 font-mono text-sm break-all text-center md:text-left">
-                        {formatNumberWithCommas(
-                          documentDetails?.document_data.EarningRelease
-                            .consolidated_income_data.net_profit,
-                        )}
-                      </p>
-                    </Col>
-                    <Col xs={{ span: 24 }} md={{ span: 12 }}>
-                      <p className="text-sm font-medium text-gray-500 mb-1 text-center md:text-left">
-                        Operating Profit
-                      </p>
-                      <p className="text-gray-900 font-mono text-sm break-all text-center md:text-left">
-                        {formatNumberWithCommas(
-                          documentDetails?.document_data.EarningRelease
-                            .consolidated_income_data.operating_profit,
-                        )}
-                      </p>
-                    </Col>
-                    <Col xs={{ span: 24 }} md={{ span: 12 }}>
-                      <p className="text-sm font-medium text-gray-500 mb-1 text-center md:text-left">
-                        Profit Before Tax
-                      </p>
-                      <p className="text-gray-900 font-mono text-sm break-all text-center md:text-left">
-                        {formatNumberWithCommas(
-                          documentDetails?.document_data.EarningRelease
-                            .consolidated_income_data.profit_before_tax,
-                        )}
-                      </p>
-                    </Col>
-                  </Row>
-                  <Divider orientation="center" />
-                  <Typography.Title
-                    level={4}
-                    className="text-center md:text-left"
-                  >
-                    Consolidated Balance Sheet
-                  </Typography.Title>
-                  <Row gutter={[24, 16]}>
-                    <Col xs={{ span: 24 }} md={{ span: 12 }}>
-                      <p className="text-sm font-medium text-gray-500 mb-1 text-center md:text-left">
-                        Total Equity
-                      </p>
-                      <p className="text-gray-900 font-mono text-sm break-all text-center md:text-left">
-                        {formatNumberWithCommas(
-                          documentDetails?.document_data.EarningRelease
-                            .consolidated_balance_sheet_data.total_equity,
-                        )}
-                      </p>
-                    </Col>
-                    <Col xs={{ span: 24 }} md={{ span: 12 }}>
-                      <p className="text-sm font-medium text-gray-500 mb-1 text-center md:text-left">
-                        Total Liabilities and Equity
-                      </p>
-                      <p className="text-gray-900 font-mono text-sm break-all text-center md:text-left">
-                        {formatNumberWithCommas(
-                          documentDetails?.document_data.EarningRelease
-                            .consolidated_balance_sheet_data
-                            .total_liabilities_and_equity,
-                        )}
-                      </p>
-                    </Col>
-                    <Col xs={{ span: 24 }} md={{ span: 12 }}>
-                      <p className="text-sm font-medium text-gray-500 mb-1 text-center md:text-left">
-                        Total Assets
-                      </p>
-                      <p className="text-gray-900 font-mono text-sm break-all text-center md:text-left">
-                        {formatNumberWithCommas(
-                          documentDetails?.document_data.EarningRelease
-                            .consolidated_balance_sheet_data.total_assets,
+                  {earningRelease && (
+                    <>
+                      <Divider orientation="center" />
+                      <Typography.Title
+                        level={4}
+                        className="text-center md:text-left"
+                      >
+                        Consolidated Income Statement
+                      </Typography.Title>
+                      <p className="text-sm text-gray-500 mb-4 text-center md:text-left">
+                        {getReportingUnitLabel(
+                          earningRelease.consolidated_income_data,
                         )}
                       </p>
-                    </Col>
-                    <Col xs={{ span: 24 }} md={{ span: 12 }}>
-                      <p className="text-sm font-medium text-gray-500 mb-1 text-center md:text-left">
-                        Total Liabilities
-                      </p>
-                      <p className="text-gray-900 font-mono text-sm break-all text-center md:text-left">
-                        {formatNumberWithCommas(
-                          documentDetails?.document_data.EarningRelease
-                            .consolidated_balance_sheet_data.total_liabilities,
+                      <Row gutter={[24, 16]}>
+                        <Col xs={{ span: 24 }} md={{ span: 12 }}>
+                          <p className="text-sm font-medium text-gray-500 mb-1 text-center md:text-left">
+                            Revenue
+                          </p>
+                          <p className="text-gray-900 font-mono text-sm break-all text-center md:text-left">
+                            {formatDecimal(
+                              earningRelease.consolidated_income_data.revenue,
+                            )}
+                          </p>
+                        </Col>
+                        <Col xs={{ span: 24 }} md={{ span: 12 }}>
+                          <p className="text-sm font-medium text-gray-500 mb-1 text-center md:text-left">
+                            Cost of Sales
+                          </p>
+                          <p className="text-gray-900 font-mono text-sm break-all text-center md:text-left">
+                            {formatDecimal(
+                              earningRelease.consolidated_income_data.cost_of_sales,
+                            )}
+                          </p>
+                        </Col>
+                        <Col xs={{ span: 24 }} md={{ span: 12 }}>
+                          <p className="text-sm font-medium text-gray-500 mb-1 text-center md:text-left">
+                            EBITDA
+                          </p>
+                          <p className="text-gray-900 font-mono text-sm break-all text-center md:text-left">
+                            {formatDecimal(
+                              earningRelease.consolidated_income_data.ebitda,
+                            )}
+                          </p>
+                        </Col>
+                        <Col xs={{ span: 24 }} md={{ span: 12 }}>
+                          <p className="text-sm font-medium text-gray-500 mb-1 text-center md:text-left">
+                            Gross Profit
+                          </p>
+                          <p className="text-gray-900 font-mono text-sm break-all text-center md:text-left">
+                            {formatDecimal(
+                              earningRelease.consolidated_income_data.gross_profit,
+                            )}
+                          </p>
+                        </Col>
+                        <Col xs={{ span: 24 }} md={{ span: 12 }}>
+                          <p className="text-sm font-medium text-gray-500 mb-1 text-center md:text-left">
+                            Net Profit
+                          </p>
+                          <p className="text-gray-900 font-mono text-sm break-all text-center md:text-left">
+                            {formatDecimal(
+                              earningRelease.consolidated_income_data.net_profit,
+                            )}
+                          </p>
+                        </Col>
+                        <Col xs={{ span: 24 }} md={{ span: 12 }}>
+                          <p className="text-sm font-medium text-gray-500 mb-1 text-center md:text-left">
+                            Operating Profit
+                          </p>
+                          <p className="text-gray-900 font-mono text-sm break-all text-center md:text-left">
+                            {formatDecimal(
+                              earningRelease.consolidated_income_data.operating_profit,
+                            )}
+                          </p>
+                        </Col>
+                        <Col xs={{ span: 24 }} md={{ span: 12 }}>
+                          <p className="text-sm font-medium text-gray-500 mb-1 text-center md:text-left">
+                            Profit Before Tax
+                          </p>
+                          <p className="text-gray-900 font-mono text-sm break-all text-center md:text-left">
+                            {formatDecimal(
+                              earningRelease.consolidated_income_data.profit_before_tax,
+                            )}
+                          </p>
+                        </Col>
+                      </Row>
+                      <Divider orientation="center" />
+                      <Typography.Title
+                        level={4}
+                        className="text-center md:text-left"
+                      >
+                        Consolidated Balance Sheet
+                      </Typography.Title>
+                      <p className="text-sm text-gray-500 mb-4 text-center md:text-left">
+                        {getReportingUnitLabel(
+                          earningRelease.consolidated_balance_sheet_data,
                         )}
                       </p>
-                    </Col>
-                  </Row>
+                      <Row gutter={[24, 16]}>
+                        <Col xs={{ span: 24 }} md={{ span: 12 }}>
+                          <p className="text-sm font-medium text-gray-500 mb-1 text-center md:text-left">
+                            Total Equity
+                          </p>
+                          <p className="text-gray-900 font-mono text-sm break-all text-center md:text-left">
+                            {formatDecimal(
+                              earningRelease.consolidated_balance_sheet_data.total_equity,
+                            )}
+                          </p>
+                        </Col>
+                        <Col xs={{ span: 24 }} md={{ span: 12 }}>
+                          <p className="text-sm font-medium text-gray-500 mb-1 text-center md:text-left">
+                            Total Liabilities and Equity
+                          </p>
+                          <p className="text-gray-900 font-mono text-sm break-all text-center md:text-left">
+                            {formatDecimal(
+                              earningRelease.consolidated_balance_sheet_data
+                                .total_liabilities_and_equity,
+                            )}
+                          </p>
+                        </Col>
+                        <Col xs={{ span: 24 }} md={{ span: 12 }}>
+                          <p className="text-sm font-medium text-gray-500 mb-1 text-center md:text-left">
+                            Total Assets
+                          </p>
+                          <p className="text-gray-900 font-mono text-sm break-all text-center md:text-left">
+                            {formatDecimal(
+                              earningRelease.consolidated_balance_sheet_data.total_assets,
+                            )}
+                          </p>
+                        </Col>
+                        <Col xs={{ span: 24 }} md={{ span: 12 }}>
+                          <p className="text-sm font-medium text-gray-500 mb-1 text-center md:text-left">
+                            Total Liabilities
+                          </p>
+                          <p className="text-gray-900 font-mono text-sm break-all text-center md:text-left">
+                            {formatDecimal(
+                              earningRelease.consolidated_balance_sheet_data.total_liabilities,
+                            )}
+                          </p>
+                        </Col>
+                      </Row>
+                      <Divider orientation="center" />
+                      <Typography.Title
+                        level={4}
+                        className="text-center md:text-left"
+                      >
+                        Consolidated Cash Flow
+                      </Typography.Title>
+                      <Row gutter={[24, 16]}>
+                        <Col xs={{ span: 24 }} md={{ span: 12 }}>
+                          <p className="text-sm font-medium text-gray-500 mb-1 text-center md:text-left">
+                            Operating Cash Flow
+                          </p>
+                          <p className="text-gray-900 font-mono text-sm break-all text-center md:text-left">
+                            {formatNumberWithCommas(
+                              earningRelease.consolidated_cash_flow_data.operating_cash_flow,
+                            )}
+                          </p>
+                        </Col>
+                        <Col xs={{ span: 24 }} md={{ span: 12 }}>
+                          <p className="text-sm font-medium text-gray-500 mb-1 text-center md:text-left">
+                            Investing Cash Flow
+                          </p>
+                          <p className="text-gray-900 font-mono text-sm break-all text-center md:text-left">
+                            {formatNumberWithCommas(
+                              earningRelease.consolidated_cash_flow_data.investing_cash_flow,
+                            )}
+                          </p>
+                        </Col>
+                        <Col xs={{ span: 24 }} md={{ span: 12 }}>
+                          <p className="text-sm font-medium text-gray-500 mb-1 text-center md:text-left">
+                            Financing Cash Flow
+                          </p>
+                          <p className="text-gray-900 font-mono text-sm break-all text-center md:text-left">
+                            {formatNumberWithCommas(
+                              earningRelease.consolidated_cash_flow_data.financing_cash_flow,
+                            )}
+                          </p>
+                        </Col>
+                        <Col xs={{ span: 24 }} md={{ span: 12 }}>
+                          <p className="text-sm font-medium text-gray-500 mb-1 text-center md:text-left">
+                            Capital Expenditure
+                          </p>
+                          <p className="text-gray-900 font-mono text-sm break-all text-center md:text-left">
+                            {formatNumberWithCommas(
+                              earningRelease.consolidated_cash_flow_data.capital_expenditure,
+                            )}
+                          </p>
+                        </Col>
+                      </Row>
+                      <Divider orientation="center" />
+                      <Typography.Title
+                        level={4}
+                        className="text-center md:text-left"
+                      >
+                        Per Share
+                      </Typography.Title>
+                      <Row gutter={[24, 16]}>
+                        <Col xs={{ span: 24 }} md={{ span: 12 }}>
+                          <p className="text-sm font-medium text-gray-500 mb-1 text-center md:text-left">
+                            EPS (Basic)
+                          </p>
+                          <p className="text-gray-900 font-mono text-sm break-all text-center md:text-left">
+                            {formatNumberWithCommas(
+                              earningRelease.per_share_data.eps_basic,
+                            )}
+                          </p>
+                        </Col>
+                        <Col xs={{ span: 24 }} md={{ span: 12 }}>
+                          <p className="text-sm font-medium text-gray-500 mb-1 text-center md:text-left">
+                            EPS (Diluted)
+                          </p>
+                          <p className="text-gray-900 font-mono text-sm break-all text-center md:text-left">
+                            {formatNumberWithCommas(
+                              earningRelease.per_share_data.eps_diluted,
+                            )}
+                          </p>
+                        </Col>
+                        <Col xs={{ span: 24 }} md={{ span: 12 }}>
+                          <p className="text-sm font-medium text-gray-500 mb-1 text-center md:text-left">
+                            Shares Outstanding
+                          </p>
+                          <p className="text-gray-900 font-mono text-sm break-all text-center md:text-left">
+                            {formatNumberWithCommas(
+                              earningRelease.per_share_data.shares_outstanding,
+                            )}
+                          </p>
+                        </Col>
+                        <Col xs={{ span: 24 }} md={{ span: 12 }}>
+                          <p className="text-sm font-medium text-gray-500 mb-1 text-center md:text-left">
+                            Dividends Per Share
+                          </p>
+                          <p className="text-gray-900 font-mono text-sm break-all text-center md:text-left">
+                            {formatNumberWithCommas(
+                              earningRelease.per_share_data.dividends_per_share,
+                            )}
+                          </p>
+                        </Col>
+                      </Row>
+                    </>
+                  )}
                 </div>
               </div>
             </div>
@@ -306,7 +469,7 @@ const DocumentDetails: FC = () => {
             {documentDetails.document_id && (
               <DownLoadButton
                 type="primary"
-                file_data={documentDetails.file_data}
+                getFileData={() => getDocumentFile(documentDetails.document_id)}
                 file_type={documentDetails.file_type}
                 file_name={documentDetails.document_id}
               >
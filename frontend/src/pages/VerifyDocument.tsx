@@ -0,0 +1,135 @@
+import LoadingSpinner from "@/components/shared/LoadingSpinner";
+import getDocumentDetails from "@/services/documents/getDocumentDetails";
+import computeFileHash from "@/utils/compileFileHash";
+import getUint8Array from "@/utils/getUint8Array";
+import { UploadOutlined } from "@ant-design/icons";
+import { Alert, Button, Result, Typography, Upload } from "antd";
+import type { Document } from "declarations/backend/backend.did";
+import { useCallback, useEffect, useState, type FC } from "react";
+import { NavLink, useParams } from "react-router";
+
+// Public page linked from certificate QR codes: no login is required
+const VerifyDocument: FC = () => {
+  const { id: documentId } = useParams<{ id: string }>();
+  const [document, setDocument] = useState<Document | null>(null);
+  const [isLoading, setIsLoading] = useState<boolean>(true);
+  const [fileMatches, setFileMatches] = useState<boolean | null>(null);
+
+  const loadDocument = useCallback(async () => {
+    try {
+      setIsLoading(true);
+      const responseData = await getDocumentDetails(documentId || "");
+      setDocument(responseData.length ? responseData[0] : null);
+    } catch (error) {
+      console.error("Error fetching document:", error);
+      setDocument(null);
+    } finally {
+      setIsLoading(false);
+    }
+  }, [documentId]);
+
+  useEffect(() => {
+    loadDocument();
+  }, [loadDocument]);
+
+  // The file is hashed in the browser and never uploaded
+  const checkFile = async (file: File) => {
+    const fileHash = await computeFileHash(await getUint8Array(file));
+    setFileMatches(fileHash === document?.file_hash);
+    return false;
+  };
+
+  if (isLoading) return <LoadingSpinner />;
+
+  if (!document) {
+    return (
+      <div className="flex justify-center items-center h-screen">
+        <Result
+          status="error"
+          title="Document not found"
+          subTitle="No notarized document has this ID."
+          extra={<NavLink to="/">Back Home</NavLink>}
+        />
+      </div>
+    );
+  }
+
+  const retraction = document.retraction[0];
+  const publicationDate = new Date(
+    Number(document.publication_date) / 1_000_000,
+  ).toUTCString();
+
+  return (
+    <div className="min-h-screen bg-gray-50 py-12">
+      <div className="max-w-2xl mx-auto px-4 sm:px-6 lg:px-8">
+        <div className="bg-white rounded-lg shadow-sm p-8">
+          <Typography.Title level={3}>{document.name}</Typography.Title>
+          {retraction ? (
+            <Alert
+              className="mb-6"
+              type="error"
+              showIcon
+              message="Notarized, then retracted by its publisher"
+              description={`Retracted on ${new Date(
+                Number(retraction.retracted_at) / 1_000_000,
+              ).toUTCString()}: ${retraction.reason}`}
+            />
+          ) : (
+            <Alert
+              className="mb-6"
+              type="success"
+              showIcon
+              message="This document is notarized on the Internet Computer"
+            />
+          )}
+
+          <div className="space-y-4 mb-8">
+            <div>
+              <p className="text-sm font-medium text-gray-500 mb-1">Company</p>
+              <p className="text-gray-900">{document.company_name}</p>
+            </div>
+            <div>
+              <p className="text-sm font-medium text-gray-500 mb-1">Published</p>
+              <p className="text-gray-900">{publicationDate}</p>
+            </div>
+            <div>
+              <p className="text-sm font-medium text-gray-500 mb-1">Document ID</p>
+              <Typography.Paragraph copyable className="font-mono wrap-break-word">
+                {document.document_id}
+              </Typography.Paragraph>
+            </div>
+            <div>
+              <p className="text-sm font-medium text-gray-500 mb-1">SHA-256 hash</p>
+              <Typography.Paragraph copyable className="font-mono wrap-break-word">
+                {document.file_hash}
+              </Typography.Paragraph>
+            </div>
+          </div>
+
+          <Typography.Title level={5}>Check your copy</Typography.Title>
+          <Upload beforeUpload={checkFile} showUploadList={false}>
+            <Button icon={<UploadOutlined />}>Select file</Button>
+          </Upload>
+          {fileMatches === true && (
+            <Alert
+              className="mt-4"
+              type="success"
+              showIcon
+              message="The file matches the notarized document"
+            />
+          )}
+          {fileMatches === false && (
+            <Alert
+              className="mt-4"
+              type="error"
+              showIcon
+              message="The file differs from the notarized document"
+            />
+          )}
+        </div>
+      </div>
+    </div>
+  );
+};
+
+export default VerifyDocument;
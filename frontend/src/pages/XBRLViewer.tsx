@@ -1,4 +1,5 @@
 import getDocumentDetails from "@/services/documents/getDocumentDetails";
+import getDocumentFile from "@/services/documents/getDocumentFile";
 import { useAppSelector } from "@/store/hooks";
 import { parseXBRL, type XBRLData } from "@/utils/xbrlParser";
 import {
@@ -46,7 +47,7 @@ const XBRLViewer: FC = () => {
         if (response.length > 0) {
           const document = response[0];
           if (document) {
-            const fileData = new Uint8Array(document.file_data);
+            const fileData = await getDocumentFile(document.document_id);
             const text = new TextDecoder().decode(fileData);
             const parsedData = parseXBRL(text);
             setXbrlData(parsedData);
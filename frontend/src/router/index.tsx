@@ -14,6 +14,7 @@ import DocumentHistory from "@/pages/UserAccount/DocumentHistory";
 import Profile from "@/pages/UserAccount/Profile";
 import Settings from "@/pages/UserAccount/Settings";
 import UserRegistration from "@/pages/UserRegistration";
+import VerifyDocument from "@/pages/VerifyDocument";
 import XBRLViewer from "@/pages/XBRLViewer";
 import ProtectedRoute from "@/router/ProtectedRoute";
 import { createBrowserRouter } from "react-router";
@@ -31,6 +32,10 @@ const router = createBrowserRouter([
         path: "register",
         Component: UserRegistration,
       },
+      {
+        path: "verify/:id",
+        Component: VerifyDocument,
+      },
       {
         path: "document",
         children: [
@@ -0,0 +1,33 @@
+import { backend } from 'declarations/backend';
+
+// The canister caps each chunk at about 1.9 MB so it fits in a query reply
+const CHUNK_SIZE = BigInt(1_900_000);
+
+// Download the file contents of a document chunk by chunk
+const getDocumentFile = async (documentId: string): Promise<Uint8Array> => {
+  if (!documentId) throw new Error('Document ID is required');
+
+  const chunks: Uint8Array[] = [];
+  let offset = BigInt(0);
+  let totalSize = BigInt(1);
+  while (offset < totalSize) {
+    const response = await backend.get_document_file_chunk(documentId, offset, CHUNK_SIZE);
+    if ('Err' in response) throw new Error(response.Err);
+
+    const chunk = new Uint8Array(response.Ok.chunk);
+    totalSize = response.Ok.total_size;
+    if (chunk.length === 0) break;
+    chunks.push(chunk);
+    offset += BigInt(chunk.length);
+  }
+
+  const fileData = new Uint8Array(chunks.reduce((size, chunk) => size + chunk.length, 0));
+  let position = 0;
+  for (const chunk of chunks) {
+    fileData.set(chunk, position);
+    position += chunk.length;
+  }
+  return fileData;
+}
+
+export default getDocumentFile;
@@ -16,6 +16,7 @@ export interface QueryDocumentsFilters {
   sort_by?: string;
   sort_order?: string;
   include_file_data?: boolean;
+  include_superseded?: boolean;
 }
 
 export interface QueryDocumentsResult {
@@ -40,6 +41,7 @@ const queryDocuments = async (
     filters.sort_by ? [filters.sort_by] : [],
     filters.sort_order ? [filters.sort_order] : [],
     filters.include_file_data !== undefined ? [filters.include_file_data] : [],
+    filters.include_superseded !== undefined ? [filters.include_superseded] : [],
   );
 
   return {
@@ -0,0 +1,60 @@
+// Financial statement amounts are fixed-point integers with four decimal places
+export const DECIMAL_PLACES = 4;
+const SCALE = 10n ** BigInt(DECIMAL_PLACES);
+
+/**
+ * Converts an input value to the backend's fixed-point representation
+ * @param value - Plain decimal text (e.g. "-1250.5") or a number
+ * @returns Amount in ten-thousandths, extra decimal places are truncated
+ */
+export function toDecimal(value: string | number | null | undefined): bigint {
+  const text = `${value ?? ""}`.trim();
+  const match = /^([+-]?)(\d*)(?:\.(\d*))?$/.exec(text);
+  if (!text || !match) return 0n;
+
+  const [, sign, integer, fraction = ""] = match;
+  const digits = fraction.padEnd(DECIMAL_PLACES, "0").slice(0, DECIMAL_PLACES);
+  const scaled = BigInt(integer || "0") * SCALE + BigInt(digits);
+  return sign === "-" ? -scaled : scaled;
+}
+
+/**
+ * Converts a fixed-point amount back to plain decimal text
+ * @param value - Amount in ten-thousandths
+ * @returns Decimal text without trailing zeros, e.g. "1250.5"
+ */
+export function fromDecimal(value: bigint | undefined): string {
+  if (value === undefined) return "";
+  const sign = value < 0n ? "-" : "";
+  const magnitude = value < 0n ? -value : value;
+  const integer = magnitude / SCALE;
+  const fraction = (magnitude % SCALE)
+    .toString()
+    .padStart(DECIMAL_PLACES, "0")
+    .replace(/0+$/, "");
+  return `${sign}${integer}${fraction ? `.${fraction}` : ""}`;
+}
+
+/**
+ * Formats a fixed-point amount with commas as thousand separators
+ * @param value - Amount in ten-thousandths
+ * @returns Formatted string, e.g. "1,250.5"
+ */
+export function formatDecimal(value: bigint): string {
+  const [integer, fraction] = fromDecimal(value).split(".");
+  const grouped = integer.replace(/\B(?=(\d{3})+(?!\d))/g, ",");
+  return fraction ? `${grouped}.${fraction}` : grouped;
+}
+
+/**
+ * Describes the unit a statement is reported in
+ * @param statement - Income statement or balance sheet with currency and scale
+ * @returns Label such as "EGP thousands"
+ */
+export function getReportingUnitLabel(statement: {
+  currency: string;
+  scale: Record<string, null>;
+}): string {
+  const scale = Object.keys(statement.scale)[0]?.toLowerCase() ?? "units";
+  return `${statement.currency || "Currency not specified"} ${scale}`;
+}
@@ -0,0 +1,166 @@
+// Regenerates the synthetic receipts in tests/fixtures
+// Usage: cargo run -p chain-notary-verifier --example generate_fixtures
+//
+// The IC is replaced by BLS keys derived from a fixed seed, so the output is identical on every run
+// and the receipts verify only against tests/fixtures/root_key.hex, never against the mainnet key.
+// mainnet_certificate.hex is not generated: it was recorded from the IC mainnet
+
+use std::path::Path;
+use candid::Principal;
+use chain_notary_verifier::{Receipt, Retraction, calculate_file_hash, document_leaf_hash};
+use ic_certification::hash_tree::{fork, leaf};
+use ic_certification::{AsHashTree, Certificate, Delegation, Hash, HashTree, RbTree, labeled, labeled_hash};
+use ic_verify_bls_signature::PrivateKey;
+use rand_chacha::ChaCha20Rng;
+use rand_chacha::rand_core::SeedableRng;
+use serde::Serialize;
+use serde_bytes::ByteBuf;
+
+const FIXTURES: &str = concat!(env!("CARGO_MANIFEST_DIR"), "/tests/fixtures");
+const SEED: u64 = 7;
+
+// DER prefix of a BLS12-381 G2 public key as used by the IC
+const BLS_DER_PREFIX: &str = "308182301d060d2b0601040182dc7c0503010201060c2b0601040182dc7c05030201036100";
+
+const DOCUMENT_ID: &str = "document_1760000000000000000";
+// Duplicate publication of the same file, retracted by its publisher a day later
+const RETRACTED_DOCUMENT_ID: &str = "document_1760000500000000000";
+const CANISTER_ID: &str = "bkyz2-fmaaa-aaaaa-qaaaq-cai";
+const SUBNET_ID: &str = "tdb26-jop6k-aogll-7ltgs-eruif-6kk7m-qpktf-gdiqx-mxtrf-vb5e6-eqe";
+const PUBLICATION_DATE: u64 = 1_760_000_000_000_000_000;
+const RETRACTION_TIME: u64 = 1_760_086_400_000_000_000;
+const CERTIFICATE_TIME: u64 = 1_760_086_500_000_000_000;
+
+fn der_public_key(key: &PrivateKey) -> Vec<u8> {
+    let mut der = hex::decode(BLS_DER_PREFIX).unwrap();
+    der.extend_from_slice(&key.public_key().serialize());
+    der
+}
+
+// Self-describing CBOR, as produced by the IC
+fn encode_cbor<T: Serialize>(value: &T) -> Vec<u8> {
+    let mut serializer = serde_cbor::Serializer::new(Vec::new());
+    serializer.self_describe().unwrap();
+    value.serialize(&mut serializer).unwrap();
+    serializer.into_inner()
+}
+
+fn encode_leb128(mut value: u64) -> Vec<u8> {
+    let mut bytes = Vec::new();
+    loop {
+        let byte = (value & 0x7f) as u8;
+        value >>= 7;
+        if value == 0 {
+            bytes.push(byte);
+            return bytes;
+        }
+        bytes.push(byte | 0x80);
+    }
+}
+
+fn sign_certificate(key: &PrivateKey, tree: HashTree, delegation: Option<Delegation>) -> Vec<u8> {
+    let mut message = b"\x0Dic-state-root".to_vec();
+    message.extend_from_slice(&tree.digest());
+    let signature = key.sign(&message).serialize().to_vec();
+    encode_cbor(&Certificate { tree, signature, delegation })
+}
+
+// State tree with the canister's certified data and the certificate time
+fn state_tree(canister_id: &Principal, certified_data: Hash) -> HashTree {
+    fork(
+        labeled("canister", labeled(canister_id.as_slice(), labeled("certified_data", leaf(certified_data.to_vec())))),
+        labeled("time", leaf(encode_leb128(CERTIFICATE_TIME))),
+    )
+}
+
+// Certificate signed by the subnet key, with a root-signed delegation covering [low, high]
+fn delegated_certificate(root: &PrivateKey, subnet: &PrivateKey, state: HashTree, low: Principal, high: Principal) -> Vec<u8> {
+    let subnet_id = Principal::from_text(SUBNET_ID).unwrap();
+    let ranges = encode_cbor(&vec![(ByteBuf::from(low.as_slice()), ByteBuf::from(high.as_slice()))]);
+    let delegation_tree = fork(
+        labeled("subnet", labeled(subnet_id.as_slice(), fork(
+            labeled("canister_ranges", leaf(ranges)),
+            labeled("public_key", leaf(der_public_key(subnet))),
+        ))),
+        labeled("time", leaf(encode_leb128(CERTIFICATE_TIME))),
+    );
+    let delegation = Delegation {
+        subnet_id: subnet_id.as_slice().to_vec(),
+        certificate: sign_certificate(root, delegation_tree, None),
+    };
+    sign_certificate(subnet, state, Some(delegation))
+}
+
+fn write_fixture(name: &str, contents: String) {
+    let path = Path::new(FIXTURES).join(name);
+    std::fs::write(&path, contents + "\n").unwrap();
+    println!("wrote {}", path.display());
+}
+
+fn main() {
+    let mut rng = ChaCha20Rng::seed_from_u64(SEED);
+    let root = PrivateKey::random(&mut rng);
+    let subnet = PrivateKey::random(&mut rng);
+    write_fixture("root_key.hex", hex::encode(der_public_key(&root)));
+
+    let file = std::fs::read(Path::new(FIXTURES).join("earnings_release_q3_2025.txt")).unwrap();
+    let file_hash = calculate_file_hash(&file);
+    let owner = Principal::self_authenticating(b"chain-notary-test-owner");
+    let canister_id = Principal::from_text(CANISTER_ID).unwrap();
+
+    let retraction = Retraction {
+        retracted_at: RETRACTION_TIME,
+        reason: "Published twice in error; see document_1760000000000000000".to_string(),
+        file_purged: false,
+    };
+    let retracted_publication_date = PUBLICATION_DATE + 500_000_000_000;
+
+    // Documents between other records, so the witnesses contain pruned branches
+    let mut documents: RbTree<Vec<u8>, Hash> = RbTree::new();
+    documents.insert(b"document_1759999000000000000".to_vec(), [1; 32]);
+    documents.insert(DOCUMENT_ID.as_bytes().to_vec(), document_leaf_hash(&file_hash, PUBLICATION_DATE, &owner, None));
+    documents.insert(
+        RETRACTED_DOCUMENT_ID.as_bytes().to_vec(),
+        document_leaf_hash(&file_hash, retracted_publication_date, &owner, Some(&retraction)),
+    );
+    documents.insert(b"document_1760001000000000000".to_vec(), [2; 32]);
+    let certified_data = labeled_hash(b"documents", &documents.root_hash());
+
+    let witness = |document_id: &str| encode_cbor(&labeled("documents", documents.witness(document_id.as_bytes())));
+    let receipt = |certificate: Vec<u8>| Receipt {
+        document_id: DOCUMENT_ID.to_string(),
+        file_hash: file_hash.clone(),
+        publication_date: PUBLICATION_DATE,
+        owner,
+        canister_id,
+        retraction: None,
+        certificate,
+        witness: witness(DOCUMENT_ID),
+    };
+
+    let root_signed = sign_certificate(&root, state_tree(&canister_id, certified_data), None);
+    write_fixture("receipt_root_signed.json", receipt(root_signed).to_json());
+
+    let delegated = delegated_certificate(
+        &root, &subnet, state_tree(&canister_id, certified_data),
+        Principal::from_slice(&[0x80, 0, 0, 0, 0, 0x10, 0, 0, 1, 1]),
+        Principal::from_slice(&[0x80, 0, 0, 0, 0, 0x1f, 0xff, 0xff, 1, 1]),
+    );
+    write_fixture("receipt_delegated.json", receipt(delegated).to_json());
+
+    let out_of_range = delegated_certificate(
+        &root, &subnet, state_tree(&canister_id, certified_data),
+        Principal::from_text("rwlgt-iiaaa-aaaaa-aaaaa-cai").unwrap(),
+        Principal::from_text("rrkah-fqaaa-aaaaa-aaaaq-cai").unwrap(),
+    );
+    write_fixture("receipt_delegated_out_of_range.json", receipt(out_of_range).to_json());
+
+    let retracted = Receipt {
+        document_id: RETRACTED_DOCUMENT_ID.to_string(),
+        publication_date: retracted_publication_date,
+        retraction: Some(retraction),
+        witness: witness(RETRACTED_DOCUMENT_ID),
+        ..receipt(sign_certificate(&root, state_tree(&canister_id, certified_data), None))
+    };
+    write_fixture("receipt_retracted.json", retracted.to_json());
+}
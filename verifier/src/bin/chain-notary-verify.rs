@@ -0,0 +1,161 @@
+// chain-notary-verify: check a local file against a ChainNotary notarization receipt
+// Usage: chain-notary-verify [--root-key <HEX|FILE>] <FILE> <RECEIPT_JSON>
+
+use std::path::Path;
+use std::process::ExitCode;
+use chain_notary_verifier::{IC_MAINNET_ROOT_KEY, Receipt, calculate_file_hash, verify_receipt};
+
+const USAGE: &str = "Usage: chain-notary-verify [--root-key <HEX|FILE>] <FILE> <RECEIPT_JSON>
+
+Verifies that FILE matches a notarization receipt exported by the ChainNotary
+canister (get_notarization_receipt_json) and that the receipt is certified by
+the Internet Computer.
+
+Options:
+  --root-key <HEX|FILE>  DER-encoded IC root key as hex, or a file containing it
+                         (defaults to the IC mainnet root key; use the local
+                         replica's key when verifying against dfx)
+  -h, --help             Print this help
+
+Exit status: 0 if verification passed, 1 if it failed, 2 on usage or I/O errors,
+3 if verification passed but the publisher has retracted the document";
+
+struct Args {
+    file: String,
+    receipt: String,
+    root_key: Option<String>,
+}
+
+fn parse_args() -> Result<Args, String> {
+    let mut positional = Vec::new();
+    let mut root_key = None;
+    let mut args = std::env::args().skip(1);
+
+    while let Some(arg) = args.next() {
+        match arg.as_str() {
+            "-h" | "--help" => {
+                println!("{}", USAGE);
+                std::process::exit(0);
+            }
+            "--root-key" => {
+                root_key = Some(args.next().ok_or("--root-key requires a value")?);
+            }
+            _ if arg.starts_with("--") => return Err(format!("Unknown option: {}", arg)),
+            _ => positional.push(arg),
+        }
+    }
+
+    match <[String; 2]>::try_from(positional) {
+        Ok([file, receipt]) => Ok(Args { file, receipt, root_key }),
+        Err(_) => Err("Expected a file and a receipt JSON".to_string()),
+    }
+}
+
+// Accept either hex on the command line or a path to a file containing hex
+fn load_root_key(root_key: Option<&str>) -> Result<Vec<u8>, String> {
+    let hex_key = match root_key {
+        None => IC_MAINNET_ROOT_KEY.to_string(),
+        Some(value) if Path::new(value).is_file() => std::fs::read_to_string(value)
+            .map_err(|e| format!("Failed to read root key {}: {}", value, e))?,
+        Some(value) => value.to_string(),
+    };
+
+    hex::decode(hex_key.trim()).map_err(|e| format!("Root key is not valid hex: {}", e))
+}
+
+/// Format nanoseconds since the epoch as "YYYY-MM-DD HH:MM:SS UTC"
+fn format_timestamp(timestamp_nanos: u64) -> String {
+    let seconds = timestamp_nanos / 1_000_000_000;
+    let days = (seconds / 86_400) as i64;
+    let time_of_day = seconds % 86_400;
+
+    // Civil date from days since 1970-01-01 (proleptic Gregorian calendar)
+    let z = days + 719_468;
+    let era = z.div_euclid(146_097);
+    let day_of_era = z.rem_euclid(146_097);
+    let year_of_era = (day_of_era - day_of_era / 1460 + day_of_era / 36_524 - day_of_era / 146_096) / 365;
+    let day_of_year = day_of_era - (365 * year_of_era + year_of_era / 4 - year_of_era / 100);
+    let month_index = (5 * day_of_year + 2) / 153;
+    let day = day_of_year - (153 * month_index + 2) / 5 + 1;
+    let month = if month_index < 10 { month_index + 3 } else { month_index - 9 };
+    let year = year_of_era + era * 400 + if month <= 2 { 1 } else { 0 };
+
+    format!(
+        "{:04}-{:02}-{:02} {:02}:{:02}:{:02} UTC",
+        year, month, day,
+        time_of_day / 3600, (time_of_day % 3600) / 60, time_of_day % 60
+    )
+}
+
+/// Outcome of a verification run that read its inputs
+enum Outcome {
+    Passed,
+    Retracted,
+    Failed,
+}
+
+fn run(args: &Args) -> Result<Outcome, String> {
+    let file_data = std::fs::read(&args.file)
+        .map_err(|e| format!("Failed to read {}: {}", args.file, e))?;
+    let receipt_json = std::fs::read_to_string(&args.receipt)
+        .map_err(|e| format!("Failed to read {}: {}", args.receipt, e))?;
+    let receipt = Receipt::from_json(&receipt_json).map_err(|e| e.to_string())?;
+    let root_key = load_root_key(args.root_key.as_deref())?;
+
+    println!("ChainNotary verification report");
+    println!("  File:            {}", args.file);
+    println!("  Receipt:         {}", args.receipt);
+    println!("  Document ID:     {}", receipt.document_id);
+    println!("  Canister ID:     {}", receipt.canister_id);
+    println!("  Owner:           {}", receipt.owner);
+    println!("  Published:       {}", format_timestamp(receipt.publication_date));
+    println!("  Notarized hash:  {}", receipt.file_hash);
+    println!("  File hash:       {}", calculate_file_hash(&file_data));
+
+    match verify_receipt(&receipt, &file_data, &root_key) {
+        Ok(verified) => {
+            if let Some(time) = verified.certificate_time {
+                println!("  Certified at:    {}", format_timestamp(time));
+            }
+            match verified.retraction {
+                Some(retraction) => {
+                    println!("  Retracted:       {}", format_timestamp(retraction.retracted_at));
+                    println!("  Reason:          {}", retraction.reason);
+                    if retraction.file_purged {
+                        println!("  File purged:     yes");
+                    }
+                    println!("  Result:          RETRACTED (notarization is authentic, but the publisher withdrew the document)");
+                    Ok(Outcome::Retracted)
+                }
+                None => {
+                    println!("  Result:          PASS");
+                    Ok(Outcome::Passed)
+                }
+            }
+        }
+        Err(e) => {
+            println!("  Result:          FAIL ({})", e);
+            Ok(Outcome::Failed)
+        }
+    }
+}
+
+fn main() -> ExitCode {
+    let args = match parse_args() {
+        Ok(args) => args,
+        Err(e) => {
+            eprintln!("{}\n\n{}", e, USAGE);
+            return ExitCode::from(2);
+        }
+    };
+
+    match run(&args) {
+        Ok(Outcome::Passed) => ExitCode::SUCCESS,
+        Ok(Outcome::Failed) => ExitCode::from(1),
+        Ok(Outcome::Retracted) => ExitCode::from(3),
+        Err(e) => {
+            eprintln!("Error: {}", e);
+            ExitCode::from(2)
+        }
+    }
+}
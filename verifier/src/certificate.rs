@@ -0,0 +1,115 @@
+// IC certificate verification (BLS signature, delegation and certified data lookup)
+// See https://internetcomputer.org/docs/references/ic-interface-spec/#certification
+
+use candid::Principal;
+use ic_certification::{Certificate, LookupResult};
+use serde_bytes::ByteBuf;
+use crate::error::VerificationError;
+
+// Domain separator prepended to the root hash before signing
+const IC_STATE_ROOT_DOMAIN_SEPARATOR: &[u8] = b"\x0Dic-state-root";
+
+// DER prefix of a BLS12-381 G2 public key as used by the IC
+const BLS_DER_PREFIX: [u8; 37] = [
+    0x30, 0x81, 0x82, 0x30, 0x1d, 0x06, 0x0d, 0x2b, 0x06, 0x01, 0x04, 0x01, 0x82, 0xdc, 0x7c, 0x05,
+    0x03, 0x01, 0x02, 0x01, 0x06, 0x0c, 0x2b, 0x06, 0x01, 0x04, 0x01, 0x82, 0xdc, 0x7c, 0x05, 0x03,
+    0x02, 0x01, 0x03, 0x61, 0x00,
+];
+const BLS_KEY_LENGTH: usize = 96;
+
+/// DER-encoded root public key of the IC mainnet (hex)
+pub const IC_MAINNET_ROOT_KEY: &str = "308182301d060d2b0601040182dc7c0503010201060c2b0601040182dc7c05030201036100814c0e6ec71fab583b08bd81373c255c3c371b2e84863c98a4f1e08b74235d14fb5d9c0cd546d9685f913a0c0b2cc5341583bf4b4392e467db96d65b9bb4cb717112f8472e0d5a4d14505ffd7484b01291091c5f87b98883463f98091a0baaae";
+
+/// Decode a CBOR-encoded certificate
+pub fn decode_certificate(bytes: &[u8]) -> Result<Certificate, VerificationError> {
+    serde_cbor::from_slice(bytes).map_err(|e| VerificationError::InvalidCertificate(e.to_string()))
+}
+
+/// Verify a certificate was signed by the IC for the given canister
+/// `root_key` is the DER-encoded IC root public key
+pub fn verify_certificate(certificate: &Certificate, canister_id: &Principal, root_key: &[u8]) -> Result<(), VerificationError> {
+    let signing_key = match &certificate.delegation {
+        None => root_key.to_vec(),
+        Some(delegation) => {
+            let delegation_certificate = decode_certificate(&delegation.certificate)?;
+            if delegation_certificate.delegation.is_some() {
+                return Err(VerificationError::InvalidCertificate("nested delegations are not allowed".to_string()));
+            }
+            verify_signature(&delegation_certificate, root_key)?;
+
+            let subnet_id = delegation.subnet_id.as_slice();
+            check_canister_ranges(&delegation_certificate, subnet_id, canister_id)?;
+
+            match delegation_certificate.tree.lookup_path([b"subnet".as_slice(), subnet_id, b"public_key"]) {
+                LookupResult::Found(public_key) => public_key.to_vec(),
+                _ => return Err(VerificationError::InvalidCertificate("delegation is missing the subnet public key".to_string())),
+            }
+        }
+    };
+
+    verify_signature(certificate, &signing_key)
+}
+
+/// Look up the certified data of a canister in a (verified) certificate
+pub fn lookup_certified_data<'a>(certificate: &'a Certificate, canister_id: &Principal) -> Option<&'a [u8]> {
+    match certificate.tree.lookup_path([b"canister".as_slice(), canister_id.as_slice(), b"certified_data"]) {
+        LookupResult::Found(data) => Some(data),
+        _ => None,
+    }
+}
+
+/// Read the certificate time (nanoseconds since the epoch, LEB128-encoded in the tree)
+pub fn lookup_time(certificate: &Certificate) -> Option<u64> {
+    match certificate.tree.lookup_path([b"time".as_slice()]) {
+        LookupResult::Found(bytes) => decode_leb128(bytes),
+        _ => None,
+    }
+}
+
+fn verify_signature(certificate: &Certificate, der_key: &[u8]) -> Result<(), VerificationError> {
+    let key = extract_bls_key(der_key)?;
+    let mut message = IC_STATE_ROOT_DOMAIN_SEPARATOR.to_vec();
+    message.extend_from_slice(&certificate.tree.digest());
+
+    ic_verify_bls_signature::verify_bls_signature(&certificate.signature, &message, key)
+        .map_err(|_| VerificationError::InvalidSignature)
+}
+
+fn extract_bls_key(der_key: &[u8]) -> Result<&[u8], VerificationError> {
+    if der_key.len() != BLS_DER_PREFIX.len() + BLS_KEY_LENGTH || !der_key.starts_with(&BLS_DER_PREFIX) {
+        return Err(VerificationError::InvalidPublicKey);
+    }
+    Ok(&der_key[BLS_DER_PREFIX.len()..])
+}
+
+// The delegation lists the canister ID ranges the subnet is responsible for
+fn check_canister_ranges(delegation_certificate: &Certificate, subnet_id: &[u8], canister_id: &Principal) -> Result<(), VerificationError> {
+    let ranges = match delegation_certificate.tree.lookup_path([b"subnet".as_slice(), subnet_id, b"canister_ranges"]) {
+        LookupResult::Found(ranges) => ranges,
+        _ => return Err(VerificationError::InvalidCertificate("delegation is missing canister ranges".to_string())),
+    };
+
+    let ranges: Vec<(ByteBuf, ByteBuf)> = serde_cbor::from_slice(ranges)
+        .map_err(|e| VerificationError::InvalidCertificate(format!("invalid canister ranges: {}", e)))?;
+
+    let canister = canister_id.as_slice();
+    if ranges.iter().any(|(low, high)| low.as_slice() <= canister && canister <= high.as_slice()) {
+        Ok(())
+    } else {
+        Err(VerificationError::CanisterNotInDelegationRange)
+    }
+}
+
+fn decode_leb128(bytes: &[u8]) -> Option<u64> {
+    let mut value: u64 = 0;
+    for (i, byte) in bytes.iter().enumerate() {
+        if i >= 10 {
+            return None;
+        }
+        value |= u64::from(byte & 0x7f) << (7 * i);
+        if byte & 0x80 == 0 {
+            return Some(value);
+        }
+    }
+    None
+}
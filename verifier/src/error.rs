@@ -0,0 +1,53 @@
+use std::fmt;
+
+/// Reasons a notarization receipt can fail verification
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum VerificationError {
+    /// The file does not hash to the notarized SHA-256 digest
+    FileHashMismatch { expected: String, actual: String },
+    /// The receipt JSON could not be parsed
+    InvalidReceipt(String),
+    /// The IC certificate could not be decoded or is malformed
+    InvalidCertificate(String),
+    /// The Merkle witness could not be decoded
+    InvalidWitness(String),
+    /// The root (or subnet) public key is not a DER-encoded BLS12-381 key
+    InvalidPublicKey,
+    /// The BLS signature over the certificate's root hash is invalid
+    InvalidSignature,
+    /// The delegation does not cover the receipt's canister
+    CanisterNotInDelegationRange,
+    /// The witness root does not match the canister's certified data
+    CertifiedDataMismatch,
+    /// The witness does not contain the receipt's document
+    DocumentNotCertified,
+    /// The certified record differs from the receipt's hash, timestamp, owner or retraction
+    DocumentRecordMismatch,
+}
+
+impl fmt::Display for VerificationError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            VerificationError::FileHashMismatch { expected, actual } => {
+                write!(f, "file hash mismatch: receipt has {}, file hashes to {}", expected, actual)
+            }
+            VerificationError::InvalidReceipt(e) => write!(f, "invalid receipt: {}", e),
+            VerificationError::InvalidCertificate(e) => write!(f, "invalid certificate: {}", e),
+            VerificationError::InvalidWitness(e) => write!(f, "invalid witness: {}", e),
+            VerificationError::InvalidPublicKey => write!(f, "invalid BLS public key"),
+            VerificationError::InvalidSignature => write!(f, "certificate signature verification failed"),
+            VerificationError::CanisterNotInDelegationRange => {
+                write!(f, "canister is not in the delegated subnet's canister ranges")
+            }
+            VerificationError::CertifiedDataMismatch => {
+                write!(f, "witness does not match the canister's certified data")
+            }
+            VerificationError::DocumentNotCertified => write!(f, "document is not present in the witness"),
+            VerificationError::DocumentRecordMismatch => {
+                write!(f, "certified document record does not match the receipt")
+            }
+        }
+    }
+}
+
+impl std::error::Error for VerificationError {}
@@ -0,0 +1,10 @@
+// ChainNotary Receipt Verifier
+// Verifies notarization receipts exported by the ChainNotary canister without network access
+
+pub mod error;
+pub mod receipt;
+pub mod certificate;
+
+pub use error::VerificationError;
+pub use receipt::{Receipt, Retraction, VerifiedReceipt, calculate_file_hash, document_leaf_hash, verify_receipt};
+pub use certificate::{IC_MAINNET_ROOT_KEY, verify_certificate};
@@ -0,0 +1,130 @@
+// Notarization receipts and end-to-end receipt verification
+
+use candid::Principal;
+use ic_certification::{Hash, HashTree, LookupResult};
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+use crate::certificate::{decode_certificate, lookup_certified_data, lookup_time, verify_certificate};
+use crate::error::VerificationError;
+
+// Label under which the canister certifies its document tree
+const DOCUMENTS_TREE_LABEL: &[u8] = b"documents";
+
+/// Notarization receipt as returned by the canister's `get_notarization_receipt`
+/// Binary fields are hex-encoded in JSON
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, Eq)]
+pub struct Receipt {
+    pub document_id: String,
+    pub file_hash: String,
+    pub publication_date: u64,
+    pub owner: Principal,
+    pub canister_id: Principal,
+    /// Absent in receipts of documents that were never retracted
+    #[serde(default)]
+    pub retraction: Option<Retraction>,
+    #[serde(with = "hex")]
+    pub certificate: Vec<u8>,
+    #[serde(with = "hex")]
+    pub witness: Vec<u8>,
+}
+
+impl Receipt {
+    pub fn from_json(json: &str) -> Result<Self, VerificationError> {
+        serde_json::from_str(json).map_err(|e| VerificationError::InvalidReceipt(e.to_string()))
+    }
+
+    pub fn to_json(&self) -> String {
+        serde_json::to_string_pretty(self).unwrap_or_default()
+    }
+}
+
+/// Retraction of a document by its publisher, as certified by the canister
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, Eq)]
+pub struct Retraction {
+    pub retracted_at: u64,
+    pub reason: String,
+    /// The canister no longer serves the file
+    pub file_purged: bool,
+}
+
+/// Facts established by a successful verification
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct VerifiedReceipt {
+    pub document_id: String,
+    pub file_hash: String,
+    pub publication_date: u64,
+    pub owner: Principal,
+    pub canister_id: Principal,
+    /// Set if the document was retracted when the certificate was issued
+    pub retraction: Option<Retraction>,
+    /// Time at which the IC signed the certificate (nanoseconds since the epoch)
+    pub certificate_time: Option<u64>,
+}
+
+/// Hash of the certified fields of a document
+/// Must match `document_leaf_hash` in the canister's certification module
+pub fn document_leaf_hash(file_hash: &str, publication_date: u64, owner: &Principal, retraction: Option<&Retraction>) -> Hash {
+    let owner_bytes = owner.as_slice();
+    let mut hasher = Sha256::new();
+    hasher.update((file_hash.len() as u32).to_be_bytes());
+    hasher.update(file_hash.as_bytes());
+    hasher.update(publication_date.to_be_bytes());
+    hasher.update((owner_bytes.len() as u32).to_be_bytes());
+    hasher.update(owner_bytes);
+    if let Some(retraction) = retraction {
+        hasher.update(retraction.retracted_at.to_be_bytes());
+        hasher.update(Sha256::digest(retraction.reason.as_bytes()));
+        hasher.update([retraction.file_purged as u8]);
+    }
+    hasher.finalize().into()
+}
+
+/// SHA-256 of the file contents, hex-encoded the same way as the canister's `calculate_file_hash`
+pub fn calculate_file_hash(file_data: &[u8]) -> String {
+    hex::encode(Sha256::digest(file_data))
+}
+
+/// Verify a receipt against the original file bytes and the DER-encoded IC root key
+pub fn verify_receipt(receipt: &Receipt, file_data: &[u8], root_key: &[u8]) -> Result<VerifiedReceipt, VerificationError> {
+    // 1. The file must hash to the notarized digest
+    let actual_hash = calculate_file_hash(file_data);
+    if actual_hash != receipt.file_hash.to_lowercase() {
+        return Err(VerificationError::FileHashMismatch {
+            expected: receipt.file_hash.clone(),
+            actual: actual_hash,
+        });
+    }
+
+    // 2. The certificate must be signed by the IC (directly or via subnet delegation)
+    let certificate = decode_certificate(&receipt.certificate)?;
+    verify_certificate(&certificate, &receipt.canister_id, root_key)?;
+
+    // 3. The witness must match the canister's certified data
+    let witness: HashTree = serde_cbor::from_slice(&receipt.witness)
+        .map_err(|e| VerificationError::InvalidWitness(e.to_string()))?;
+    let certified_data = lookup_certified_data(&certificate, &receipt.canister_id)
+        .ok_or(VerificationError::CertifiedDataMismatch)?;
+    if certified_data != witness.digest() {
+        return Err(VerificationError::CertifiedDataMismatch);
+    }
+
+    // 4. The witness must certify this document's hash, timestamp, owner and retraction state
+    let leaf = match witness.lookup_path([DOCUMENTS_TREE_LABEL, receipt.document_id.as_bytes()]) {
+        LookupResult::Found(leaf) => leaf,
+        _ => return Err(VerificationError::DocumentNotCertified),
+    };
+    let expected_leaf = document_leaf_hash(&receipt.file_hash, receipt.publication_date, &receipt.owner, receipt.retraction.as_ref());
+    if leaf != expected_leaf {
+        return Err(VerificationError::DocumentRecordMismatch);
+    }
+
+    Ok(VerifiedReceipt {
+        document_id: receipt.document_id.clone(),
+        file_hash: receipt.file_hash.clone(),
+        publication_date: receipt.publication_date,
+        owner: receipt.owner,
+        canister_id: receipt.canister_id,
+        retraction: receipt.retraction.clone(),
+        certificate_time: lookup_time(&certificate),
+    })
+}
@@ -0,0 +1,57 @@
+// End-to-end runs of the chain-notary-verify binary against recorded receipts
+
+use std::process::Command;
+
+const FIXTURES: &str = concat!(env!("CARGO_MANIFEST_DIR"), "/tests/fixtures");
+
+fn verify(file: &str, receipt: &str) -> (i32, String) {
+    let output = Command::new(env!("CARGO_BIN_EXE_chain-notary-verify"))
+        .arg("--root-key")
+        .arg(format!("{}/root_key.hex", FIXTURES))
+        .arg(format!("{}/{}", FIXTURES, file))
+        .arg(format!("{}/{}", FIXTURES, receipt))
+        .output()
+        .unwrap();
+    (output.status.code().unwrap(), String::from_utf8_lossy(&output.stdout).into_owned())
+}
+
+#[test]
+fn passes_for_notarized_file() {
+    let (status, report) = verify("earnings_release_q3_2025.txt", "receipt_delegated.json");
+    assert_eq!(status, 0);
+    assert!(report.contains("Result:          PASS"));
+    assert!(report.contains("Published:       2025-10-09 08:53:20 UTC"));
+    assert!(report.contains("Certified at:    2025-10-10 08:55:00 UTC"));
+}
+
+#[test]
+fn reports_retracted_document() {
+    let (status, report) = verify("earnings_release_q3_2025.txt", "receipt_retracted.json");
+    assert_eq!(status, 3);
+    assert!(report.contains("Retracted:       2025-10-10 08:53:20 UTC"));
+    assert!(report.contains("Reason:          Published twice in error; see document_1760000000000000000"));
+    assert!(report.contains("Result:          RETRACTED"));
+}
+
+#[test]
+fn fails_for_other_file() {
+    let (status, report) = verify("root_key.hex", "receipt_root_signed.json");
+    assert_eq!(status, 1);
+    assert!(report.contains("Result:          FAIL (file hash mismatch"));
+}
+
+#[test]
+fn fails_against_mainnet_root_key() {
+    let output = Command::new(env!("CARGO_BIN_EXE_chain-notary-verify"))
+        .arg(format!("{}/earnings_release_q3_2025.txt", FIXTURES))
+        .arg(format!("{}/receipt_root_signed.json", FIXTURES))
+        .output()
+        .unwrap();
+    assert_eq!(output.status.code(), Some(1));
+}
+
+#[test]
+fn rejects_missing_arguments() {
+    let output = Command::new(env!("CARGO_BIN_EXE_chain-notary-verify")).output().unwrap();
+    assert_eq!(output.status.code(), Some(2));
+}
@@ -0,0 +1,43 @@
+// Certificate verification against a certificate recorded from the IC mainnet
+// The certificate answers a read_state request for ivg37-qiaaa-aaaab-aaaga-cai and is signed by
+// a subnet key delegated by the mainnet root key (same test vector as the ic-agent crate)
+
+use candid::Principal;
+use chain_notary_verifier::certificate::{decode_certificate, lookup_time};
+use chain_notary_verifier::{IC_MAINNET_ROOT_KEY, VerificationError, verify_certificate};
+use ic_certification::Certificate;
+
+const CANISTER_ID: &str = "ivg37-qiaaa-aaaab-aaaga-cai";
+
+fn certificate() -> Certificate {
+    let bytes = hex::decode(include_str!("fixtures/mainnet_certificate.hex").trim()).unwrap();
+    decode_certificate(&bytes).unwrap()
+}
+
+fn mainnet_root_key() -> Vec<u8> {
+    hex::decode(IC_MAINNET_ROOT_KEY).unwrap()
+}
+
+#[test]
+fn verifies_mainnet_certificate() {
+    let certificate = certificate();
+    assert!(certificate.delegation.is_some());
+    let canister_id = Principal::from_text(CANISTER_ID).unwrap();
+    assert_eq!(verify_certificate(&certificate, &canister_id, &mainnet_root_key()), Ok(()));
+    assert_eq!(lookup_time(&certificate), Some(1_645_601_880_652_705_378));
+}
+
+#[test]
+fn rejects_mainnet_certificate_for_canister_on_other_subnet() {
+    let canister_id = Principal::from_text("bkyz2-fmaaa-aaaaa-qaaaq-cai").unwrap();
+    let result = verify_certificate(&certificate(), &canister_id, &mainnet_root_key());
+    assert_eq!(result, Err(VerificationError::CanisterNotInDelegationRange));
+}
+
+#[test]
+fn rejects_mainnet_certificate_under_test_root_key() {
+    let test_root_key = hex::decode(include_str!("fixtures/root_key.hex").trim()).unwrap();
+    let canister_id = Principal::from_text(CANISTER_ID).unwrap();
+    let result = verify_certificate(&certificate(), &canister_id, &test_root_key);
+    assert_eq!(result, Err(VerificationError::InvalidSignature));
+}
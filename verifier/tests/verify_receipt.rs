@@ -0,0 +1,195 @@
+// Receipt verification against recorded certificates
+// Fixtures were produced with a fixed test root key (tests/fixtures/root_key.hex) by examples/generate_fixtures.rs
+
+use candid::Principal;
+use chain_notary_verifier::{Receipt, Retraction, VerificationError, document_leaf_hash, verify_receipt};
+
+const FILE: &[u8] = include_bytes!("fixtures/earnings_release_q3_2025.txt");
+const ROOT_SIGNED: &str = include_str!("fixtures/receipt_root_signed.json");
+const DELEGATED: &str = include_str!("fixtures/receipt_delegated.json");
+const DELEGATED_OUT_OF_RANGE: &str = include_str!("fixtures/receipt_delegated_out_of_range.json");
+const RETRACTED: &str = include_str!("fixtures/receipt_retracted.json");
+
+fn root_key() -> Vec<u8> {
+    hex::decode(include_str!("fixtures/root_key.hex").trim()).unwrap()
+}
+
+fn receipt(json: &str) -> Receipt {
+    Receipt::from_json(json).unwrap()
+}
+
+#[test]
+fn verifies_root_signed_receipt() {
+    let verified = verify_receipt(&receipt(ROOT_SIGNED), FILE, &root_key()).unwrap();
+    assert_eq!(verified.document_id, "document_1760000000000000000");
+    assert_eq!(verified.publication_date, 1_760_000_000_000_000_000);
+    assert_eq!(verified.canister_id, Principal::from_text("bkyz2-fmaaa-aaaaa-qaaaq-cai").unwrap());
+    assert_eq!(verified.certificate_time, Some(1_760_086_500_000_000_000));
+    assert_eq!(verified.retraction, None);
+}
+
+#[test]
+fn verifies_delegated_receipt() {
+    let verified = verify_receipt(&receipt(DELEGATED), FILE, &root_key()).unwrap();
+    assert_eq!(verified.file_hash, "2a6614cf988f8e3ff1426ffc23e07823762c84f9b0611caf61b33b0022d55556");
+}
+
+#[test]
+fn verifies_retracted_receipt() {
+    let verified = verify_receipt(&receipt(RETRACTED), FILE, &root_key()).unwrap();
+    assert_eq!(verified.document_id, "document_1760000500000000000");
+    let retraction = verified.retraction.unwrap();
+    assert_eq!(retraction.retracted_at, 1_760_086_400_000_000_000);
+    assert_eq!(retraction.reason, "Published twice in error; see document_1760000000000000000");
+    assert!(!retraction.file_purged);
+}
+
+#[test]
+fn rejects_retracted_receipt_without_retraction() {
+    let mut receipt = receipt(RETRACTED);
+    receipt.retraction = None;
+    let result = verify_receipt(&receipt, FILE, &root_key());
+    assert_eq!(result, Err(VerificationError::DocumentRecordMismatch));
+}
+
+#[test]
+fn rejects_tampered_retraction() {
+    let mut reason_changed = receipt(RETRACTED);
+    reason_changed.retraction.as_mut().unwrap().reason = "Superseded".to_string();
+    assert_eq!(verify_receipt(&reason_changed, FILE, &root_key()), Err(VerificationError::DocumentRecordMismatch));
+
+    let mut purge_changed = receipt(RETRACTED);
+    purge_changed.retraction.as_mut().unwrap().file_purged = true;
+    assert_eq!(verify_receipt(&purge_changed, FILE, &root_key()), Err(VerificationError::DocumentRecordMismatch));
+}
+
+#[test]
+fn rejects_retraction_added_to_active_receipt() {
+    let mut receipt = receipt(ROOT_SIGNED);
+    receipt.retraction = Receipt::from_json(RETRACTED).unwrap().retraction;
+    let result = verify_receipt(&receipt, FILE, &root_key());
+    assert_eq!(result, Err(VerificationError::DocumentRecordMismatch));
+}
+
+#[test]
+fn rejects_modified_file() {
+    let mut file = FILE.to_vec();
+    file[0] ^= 1;
+    let result = verify_receipt(&receipt(ROOT_SIGNED), &file, &root_key());
+    assert!(matches!(result, Err(VerificationError::FileHashMismatch { .. })));
+}
+
+#[test]
+fn rejects_wrong_root_key() {
+    let mut key = root_key();
+    let last = key.len() - 1;
+    key[last] ^= 1;
+    assert_eq!(verify_receipt(&receipt(ROOT_SIGNED), FILE, &key), Err(VerificationError::InvalidSignature));
+    assert_eq!(verify_receipt(&receipt(DELEGATED), FILE, &key), Err(VerificationError::InvalidSignature));
+}
+
+#[test]
+fn rejects_malformed_root_key() {
+    let result = verify_receipt(&receipt(ROOT_SIGNED), FILE, &[0u8; 32]);
+    assert_eq!(result, Err(VerificationError::InvalidPublicKey));
+}
+
+#[test]
+fn rejects_canister_outside_delegation() {
+    let result = verify_receipt(&receipt(DELEGATED_OUT_OF_RANGE), FILE, &root_key());
+    assert_eq!(result, Err(VerificationError::CanisterNotInDelegationRange));
+}
+
+#[test]
+fn rejects_other_canister() {
+    let mut receipt = receipt(ROOT_SIGNED);
+    receipt.canister_id = Principal::from_text("rrkah-fqaaa-aaaaa-aaaaq-cai").unwrap();
+    let result = verify_receipt(&receipt, FILE, &root_key());
+    assert_eq!(result, Err(VerificationError::CertifiedDataMismatch));
+}
+
+#[test]
+fn rejects_tampered_publication_date() {
+    let mut receipt = receipt(ROOT_SIGNED);
+    receipt.publication_date += 1;
+    let result = verify_receipt(&receipt, FILE, &root_key());
+    assert_eq!(result, Err(VerificationError::DocumentRecordMismatch));
+}
+
+#[test]
+fn rejects_tampered_owner() {
+    let mut receipt = receipt(ROOT_SIGNED);
+    receipt.owner = Principal::anonymous();
+    let result = verify_receipt(&receipt, FILE, &root_key());
+    assert_eq!(result, Err(VerificationError::DocumentRecordMismatch));
+}
+
+#[test]
+fn rejects_document_missing_from_witness() {
+    let mut receipt = receipt(ROOT_SIGNED);
+    receipt.document_id = "document_1760000000000000001".to_string();
+    let result = verify_receipt(&receipt, FILE, &root_key());
+    assert_eq!(result, Err(VerificationError::DocumentNotCertified));
+}
+
+#[test]
+fn rejects_tampered_signature() {
+    let mut receipt = receipt(ROOT_SIGNED);
+    let last = receipt.certificate.len() - 1;
+    receipt.certificate[last] ^= 1;
+    let result = verify_receipt(&receipt, FILE, &root_key());
+    assert!(matches!(
+        result,
+        Err(VerificationError::InvalidSignature) | Err(VerificationError::InvalidCertificate(_))
+    ));
+}
+
+#[test]
+fn receipt_json_round_trips() {
+    let original = receipt(DELEGATED);
+    assert_eq!(Receipt::from_json(&original.to_json()).unwrap(), original);
+    let retracted = receipt(RETRACTED);
+    assert_eq!(Receipt::from_json(&retracted.to_json()).unwrap(), retracted);
+}
+
+#[test]
+fn accepts_receipt_json_without_retraction_field() {
+    // Receipts exported before retractions were certified have no "retraction" key
+    let mut json: serde_json::Value = serde_json::from_str(ROOT_SIGNED).unwrap();
+    json.as_object_mut().unwrap().remove("retraction");
+    let receipt = Receipt::from_json(&json.to_string()).unwrap();
+    assert_eq!(receipt.retraction, None);
+    assert!(verify_receipt(&receipt, FILE, &root_key()).is_ok());
+}
+
+#[test]
+fn leaf_hash_matches_canister_encoding() {
+    // Pinned against the canister's certification::document_leaf_hash
+    let leaf = document_leaf_hash(
+        "2a6614cf988f8e3ff1426ffc23e07823762c84f9b0611caf61b33b0022d55556",
+        1_760_000_000_000_000_000,
+        &Principal::from_text("bkyz2-fmaaa-aaaaa-qaaaq-cai").unwrap(),
+        None,
+    );
+    assert_eq!(hex::encode(leaf), LEAF_VECTOR);
+}
+
+#[test]
+fn retracted_leaf_hash_matches_canister_encoding() {
+    // Pinned against the canister's certification::document_leaf_hash
+    let retraction = Retraction {
+        retracted_at: 1_760_086_400_000_000_000,
+        reason: "Superseded by the restated release".to_string(),
+        file_purged: true,
+    };
+    let leaf = document_leaf_hash(
+        "2a6614cf988f8e3ff1426ffc23e07823762c84f9b0611caf61b33b0022d55556",
+        1_760_000_000_000_000_000,
+        &Principal::from_text("bkyz2-fmaaa-aaaaa-qaaaq-cai").unwrap(),
+        Some(&retraction),
+    );
+    assert_eq!(hex::encode(leaf), RETRACTED_LEAF_VECTOR);
+}
+
+const LEAF_VECTOR: &str = "35725cd1437d8df6ab95de11f6721cc4b3b9f209d9b3f9136114e1944af99541";
+const RETRACTED_LEAF_VECTOR: &str = "912b29a05c0165418201adaebbfb4ba74cc1cf4ed7f42a3cf0d3a7386cc30239";